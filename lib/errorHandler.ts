@@ -1,5 +1,6 @@
 // Comprehensive error handling for blockchain operations
 import { WalletError } from '@solana/wallet-adapter-base'
+import { PROGRAM_ERRORS, ProgramError, ProgramErrorCategory } from './programErrors'
 
 export enum ErrorCode {
   // Wallet Errors
@@ -28,6 +29,8 @@ export enum ErrorCode {
   INVALID_ACCOUNT_DATA = 'INVALID_ACCOUNT_DATA',
   CAMPAIGN_NOT_FOUND = 'CAMPAIGN_NOT_FOUND',
   CAMPAIGN_ENDED = 'CAMPAIGN_ENDED',
+  NOT_AUTHORIZED = 'NOT_AUTHORIZED',
+  INVALID_CAMPAIGN_STATE = 'INVALID_CAMPAIGN_STATE',
   
   // Token Errors
   TOKEN_ACCOUNT_NOT_FOUND = 'TOKEN_ACCOUNT_NOT_FOUND',
@@ -60,6 +63,50 @@ export interface BlockchainError {
   severity: 'low' | 'medium' | 'high' | 'critical'
   context?: Record<string, any>
   timestamp: number
+  // The launch fund program's own error, when that's what failed
+  programError?: ProgramError & { code: number }
+}
+
+// How each group of the launch fund program's errors (programs/launch_fund/src/errors.rs) is presented
+const PROGRAM_ERROR_CATEGORIES: Record<ProgramErrorCategory, {
+  code: ErrorCode
+  userMessage: string
+  recoverable: boolean
+  retryable: boolean
+  severity: BlockchainError['severity']
+}> = {
+  // 6000-6099
+  validation: {
+    code: ErrorCode.INVALID_INPUT,
+    userMessage: 'Some of the details entered are not valid. Please check them and try again.',
+    recoverable: true,
+    retryable: false,
+    severity: 'low'
+  },
+  // 6100-6199
+  auth: {
+    code: ErrorCode.NOT_AUTHORIZED,
+    userMessage: 'Your wallet is not allowed to do this.',
+    recoverable: false,
+    retryable: false,
+    severity: 'medium'
+  },
+  // 6200-6299
+  state: {
+    code: ErrorCode.INVALID_CAMPAIGN_STATE,
+    userMessage: 'This action is not available for the campaign right now.',
+    recoverable: false,
+    retryable: false,
+    severity: 'medium'
+  },
+  // 6300-6399
+  math: {
+    code: ErrorCode.INSUFFICIENT_BALANCE,
+    userMessage: 'The amount is more than is available. Try a smaller amount.',
+    recoverable: true,
+    retryable: false,
+    severity: 'medium'
+  }
 }
 
 export class ErrorHandler {
@@ -122,6 +169,25 @@ export class ErrorHandler {
       } as BlockchainError
     }
 
+    // Launch fund program errors carry a numeric code, which names the variant and its group
+    const programCode: number | undefined = error?.error?.errorCode?.number ?? error?.code
+    const programError = typeof programCode === 'number' && PROGRAM_ERRORS[programCode]
+      ? { ...PROGRAM_ERRORS[programCode], code: programCode }
+      : undefined
+    if (programError) {
+      const category = PROGRAM_ERROR_CATEGORIES[programError.category]
+      return {
+        ...baseError,
+        code: category.code,
+        message: programError.message,
+        programError,
+        userMessage: category.userMessage,
+        recoverable: category.recoverable,
+        retryable: category.retryable,
+        severity: category.severity
+      } as BlockchainError
+    }
+
     // Check error message patterns
     const errorMessage = error?.message || error?.toString() || ''
     const errorLower = errorMessage.toLowerCase()
@@ -321,6 +387,16 @@ export class ErrorHandler {
         suggestions.push('Try refreshing the page and reconnecting')
         break
 
+      case ErrorCode.NOT_AUTHORIZED:
+        suggestions.push('Check that the connected wallet is the one this action belongs to')
+        suggestions.push('Switch wallets and try again')
+        break
+
+      case ErrorCode.INVALID_CAMPAIGN_STATE:
+        suggestions.push('Refresh the page to see where the campaign stands now')
+        suggestions.push('Some actions only open before or after the campaign ends or graduates')
+        break
+
       case ErrorCode.TOKEN_ACCOUNT_NOT_FOUND:
         suggestions.push('The token account will be created automatically')
         suggestions.push('Ensure you have enough SOL for account creation fees')
@@ -0,0 +1,232 @@
+// Generated by scripts/generate-program-errors.js from programs/launch_fund/src/errors.rs.
+// Do not edit by hand; rerun npm run generate:errors instead.
+
+export type ProgramErrorCategory = 'validation' | 'auth' | 'state' | 'math'
+
+export interface ProgramError {
+  name: string
+  message: string
+  category: ProgramErrorCategory
+}
+
+export const PROGRAM_ERRORS: Record<number, ProgramError> = {
+  6000: { name: 'InvalidAmount', message: "Invalid contribution amount", category: 'validation' },
+  6001: { name: 'InvalidEndTime', message: "Campaign end time must be in the future", category: 'validation' },
+  6002: { name: 'InvalidExtensionDuration', message: "Extension duration must be positive", category: 'validation' },
+  6003: { name: 'InvalidReserveRatio', message: "Reserve ratio cannot exceed 100%", category: 'validation' },
+  6004: { name: 'MissingGovernanceAccounts', message: "Governance token accounts are missing or invalid", category: 'validation' },
+  6005: { name: 'InvalidLockup', message: "Lockup duration cannot be negative", category: 'validation' },
+  6006: { name: 'InvalidRecontributeRatio', message: "Recontribute ratio must be between 1 and 10000 bps", category: 'validation' },
+  6007: { name: 'InvalidFee', message: "Fee exceeds the allowed maximum", category: 'validation' },
+  6008: { name: 'InvalidTokenMint', message: "Mint does not belong to this campaign", category: 'validation' },
+  6009: { name: 'InvalidTokenAccount', message: "Account is not a valid token account", category: 'validation' },
+  6010: { name: 'MissingTicketAccounts', message: "Claim ticket accounts are missing or invalid", category: 'validation' },
+  6011: { name: 'InvalidCampaignName', message: "Campaign name must be between 1 and 50 bytes", category: 'validation' },
+  6012: { name: 'MetadataTooLong', message: "Campaign description, symbol or token name is too long", category: 'validation' },
+  6013: { name: 'InvalidCampaignLimits', message: "Campaign limits are inconsistent", category: 'validation' },
+  6014: { name: 'InvalidCampaignDuration', message: "Campaign duration is outside the platform limits", category: 'validation' },
+  6015: { name: 'InvalidTargetAmount', message: "Target amount is outside the platform limits", category: 'validation' },
+  6016: { name: 'InvalidMatchTerms', message: "Invalid match terms", category: 'validation' },
+  6017: { name: 'MissingMatchCommitment', message: "Campaign has a sponsor match that must be passed in", category: 'validation' },
+  6018: { name: 'MissingSymbolIndex', message: "Platform requires a symbol index for new campaigns", category: 'validation' },
+  6019: { name: 'InvalidGraduationThreshold', message: "Graduation threshold must be at least the target", category: 'validation' },
+  6020: { name: 'ZeroAmount', message: "Amount must be greater than zero", category: 'validation' },
+  6021: { name: 'AirdropExceedsSupply', message: "Airdrop must be non-zero and within the campaign's airdrop reserve", category: 'validation' },
+  6022: { name: 'InvalidRate', message: "Conversion rate must be greater than zero", category: 'validation' },
+  6023: { name: 'InvalidFeeShare', message: "Frontend fee share cannot exceed 100%", category: 'validation' },
+  6024: { name: 'InvalidFeeTiers', message: "Fee tiers must start at zero and have strictly increasing thresholds", category: 'validation' },
+  6025: { name: 'FeeTooHigh', message: "Fee exceeds the platform maximum", category: 'validation' },
+  6026: { name: 'InvalidExpiry', message: "Expiry must be in the future", category: 'validation' },
+  6027: { name: 'InvalidRegion', message: "Region index must be below 64", category: 'validation' },
+  6028: { name: 'CompressionUnsupported', message: "Compressed records are not supported for governance campaigns", category: 'validation' },
+  6029: { name: 'InvalidGovernanceAccount', message: "Account is not a governance of the given realm", category: 'validation' },
+  6030: { name: 'InvalidSecondarySlope', message: "Secondary curve must rise by at least the closing price per SOL", category: 'validation' },
+  6031: { name: 'InvalidTwapWindow', message: "TWAP window must be positive", category: 'validation' },
+  6032: { name: 'InvalidStartTime', message: "Start time must be between now and the end time", category: 'validation' },
+  6033: { name: 'PreDepositsUnsupported', message: "Pre-launch deposits can't be combined with governance tokens, a price impact cap or allowlist tiers", category: 'validation' },
+  6034: { name: 'InvalidCreatorRoles', message: "Unknown creator role bits", category: 'validation' },
+  6035: { name: 'InvalidBasketLeg', message: "Basket leg accounts are missing, repeated or don't match", category: 'validation' },
+  6036: { name: 'BasketUnsupported', message: "Ticket and governance token campaigns can't be bought into through a basket", category: 'validation' },
+  6037: { name: 'InsurancePoolRequired', message: "The insurance pool account is required while it takes a fee share", category: 'validation' },
+  6038: { name: 'MissingPaymentReference', message: "Pass the Solana Pay reference as the first remaining account", category: 'validation' },
+  6039: { name: 'InvalidDistribution', message: "Unsold supply shares must add up to 100%", category: 'validation' },
+  6040: { name: 'InvalidStretchGoals', message: "Stretch goals must be ascending, above the target and at most four", category: 'validation' },
+  6041: { name: 'StakingUnsupported', message: "Staking needs a plain curve campaign: no freezing, tickets or governance tokens", category: 'validation' },
+  6042: { name: 'InvalidContributorMilestones', message: "Contributor milestones must be ascending, non-zero and at most four", category: 'validation' },
+  6043: { name: 'InvalidLaunchCalendar', message: "Launch calendar windows need a positive length and capacity", category: 'validation' },
+  6044: { name: 'InvalidHardCap', message: "Hard cap must be zero or at least the target", category: 'validation' },
+  6045: { name: 'InvalidParentCampaign', message: "Invalid parent campaign", category: 'validation' },
+  6046: { name: 'ContributionBelowMinimum', message: "Contribution is below the minimum", category: 'validation' },
+  6047: { name: 'BidBelowReserve', message: "Bid is below the closing curve price", category: 'validation' },
+  6048: { name: 'MissingWinningBid', message: "Winning bid accounts are required", category: 'validation' },
+  6049: { name: 'InvalidRedirectTarget', message: "Refunds can only be redirected into a different, plain curve campaign", category: 'validation' },
+  6050: { name: 'InvalidCircuitBreaker', message: "Circuit breaker needs a positive window and cooldown", category: 'validation' },
+  6051: { name: 'InvalidSalaryStream', message: "Salary stream duration must be positive", category: 'validation' },
+  6052: { name: 'DonationModeUnsupported', message: "Donation campaigns don't issue tokens, so token options and trading are unavailable", category: 'validation' },
+  6053: { name: 'InvalidRefundBatch', message: "Refund batches take up to ten record, contributor and backer profile triples for this campaign", category: 'validation' },
+  6054: { name: 'InvalidIdentityHandle', message: "Identity handle must be a .sol domain or a did:method:id DID of at most 64 bytes", category: 'validation' },
+  6055: { name: 'InvalidSupplyAllocation', message: "Reserved allocations must leave the curve some supply", category: 'validation' },
+  6056: { name: 'InvalidAdapterName', message: "Adapter name must be 1-32 bytes", category: 'validation' },
+  6057: { name: 'InvalidAllowlistTier', message: "Allowlist tiers need an index under the limit, a root, a window inside the campaign and a bonus within the cap", category: 'validation' },
+  6058: { name: 'AllowlistCapExceeded', message: "Contribution would take the wallet past its allowlist tier's cap", category: 'validation' },
+  6059: { name: 'AllowlistTierRequired', message: "Pass the campaign's allowlist tier as the first remaining account", category: 'validation' },
+  6060: { name: 'InvalidDripCap', message: "Drip cap must be at most 100% and needs an epoch of at least 8 seconds", category: 'validation' },
+  6061: { name: 'InvalidTokenSymbol', message: "Token symbol must be 1-10 ASCII letters or digits", category: 'validation' },
+  6062: { name: 'InvalidTokenName', message: "Token name must be 1-50 ASCII letters, digits and spaces", category: 'validation' },
+  6063: { name: 'NameBlocklistEntryRequired', message: "Name blocklist entry accounts are required while the blocklist is enabled", category: 'validation' },
+  6064: { name: 'InvalidFaqEntry', message: "FAQ entries need an answer hash and a URI of at most 200 bytes", category: 'validation' },
+  6065: { name: 'InvalidContributionSplit', message: "Splits need 1-3 beneficiary ATAs of the campaign's mint, each paired with its contribution record, non-zero shares totalling at most 100%, and a campaign minting transferable tokens without a governance token", category: 'validation' },
+  6066: { name: 'InvalidPreDepositRefund', message: "Rejected campaigns must refund every queued deposit, passed as deposit and depositor pairs", category: 'validation' },
+  6067: { name: 'InvalidHoldPeriod', message: "Minimum holding period can't be negative or outlast the campaign", category: 'validation' },
+  6068: { name: 'InvalidRevenueAccounts', message: "Revenue token accounts don't match the stream's mint", category: 'validation' },
+  6069: { name: 'PriceFeedRequired', message: "This campaign publishes a price feed, which every trade must pass", category: 'validation' },
+  6070: { name: 'InvalidCurveConfig', message: "Curve needs a whole token to cost at least a lamport and a bonus of at most 100% with somewhere to end", category: 'validation' },
+  6071: { name: 'InvalidLpMint', message: "LP mint isn't from the platform's AMM", category: 'validation' },
+  6072: { name: 'SymbolIndexMismatch', message: "Symbol index belongs to another campaign", category: 'validation' },
+  6073: { name: 'UnexpectedTicketMint', message: "A ticket mint can only be created for a uniform-price raise", category: 'validation' },
+  6074: { name: 'GovernanceUnsupported', message: "Governance campaigns can't use this path, which can't carry their governance tokens", category: 'validation' },
+  6100: { name: 'Unauthorized', message: "Unauthorized access", category: 'auth' },
+  6101: { name: 'NoVotingPower', message: "No voting power in this campaign", category: 'auth' },
+  6102: { name: 'IntentExpired', message: "Signed intent has expired", category: 'auth' },
+  6103: { name: 'InvalidSignature', message: "Missing or invalid Ed25519 signature", category: 'auth' },
+  6104: { name: 'NonceAlreadyUsed', message: "Nonce has already been used", category: 'auth' },
+  6105: { name: 'InvalidFrontendPayout', message: "Frontend payout account does not match its registration", category: 'auth' },
+  6106: { name: 'InvalidMerkleProof', message: "Invalid merkle proof", category: 'auth' },
+  6107: { name: 'UnlockNotApproved', message: "Token holders have not approved the unlock", category: 'auth' },
+  6108: { name: 'InvalidWormholeMessage', message: "Invalid Wormhole message", category: 'auth' },
+  6109: { name: 'BadgeNotEarned', message: "Badge criteria not met", category: 'auth' },
+  6110: { name: 'NotCampaignCreator', message: "Only the campaign creator can do this", category: 'auth' },
+  6111: { name: 'NotAdmin', message: "Only the platform admin can do this", category: 'auth' },
+  6112: { name: 'NotUpgradeAuthority', message: "Only the program upgrade authority can do this", category: 'auth' },
+  6113: { name: 'RegionAttestationRequired', message: "Campaign requires a region attestation for this contributor", category: 'auth' },
+  6114: { name: 'RegionRestricted', message: "Contributor's region is restricted by this campaign", category: 'auth' },
+  6115: { name: 'NotCoCreator', message: "Not a co-creator of this campaign", category: 'auth' },
+  6116: { name: 'MissingCreatorRole', message: "Co-creator lacks the role this requires", category: 'auth' },
+  6117: { name: 'NotReviewer', message: "Only the platform reviewer can do this", category: 'auth' },
+  6118: { name: 'AuthorityHeldByGovernance', message: "Campaign authority has been handed to token holder governance", category: 'auth' },
+  6119: { name: 'WithdrawalNotApproved', message: "Withdrawal is above the approval threshold and lacks top backer sign-off", category: 'auth' },
+  6120: { name: 'WithdrawalApprovalMismatch', message: "Approval is for a different withdrawal amount", category: 'auth' },
+  6121: { name: 'NotTopBacker', message: "Only the campaign's top backers can approve withdrawals", category: 'auth' },
+  6122: { name: 'BelowContentThreshold', message: "Contribution is below the gated content threshold", category: 'auth' },
+  6123: { name: 'BidCommitmentMismatch', message: "Revealed bid doesn't match its commitment", category: 'auth' },
+  6124: { name: 'DenylistEntryRequired', message: "Denylist entry account is required while screening is enabled", category: 'auth' },
+  6125: { name: 'AddressDenied', message: "Address is on the denylist", category: 'auth' },
+  6126: { name: 'NotCampaignGovernance', message: "Only the campaign's governance can do this", category: 'auth' },
+  6127: { name: 'InvalidSnsDomain', message: "Name account isn't that .sol domain or isn't owned by the creator", category: 'auth' },
+  6128: { name: 'NameBlocked', message: "Symbol or name is on the platform blocklist", category: 'auth' },
+  6129: { name: 'InvalidWithdrawalDestination', message: "Withdrawals are paid to the campaign creator", category: 'auth' },
+  6130: { name: 'IdentityAttestationExpired', message: "Identity attestation has expired", category: 'auth' },
+  6200: { name: 'CampaignNotActive', message: "Campaign is not active", category: 'state' },
+  6201: { name: 'DeadlinePassed', message: "Campaign deadline has passed", category: 'state' },
+  6202: { name: 'ExtensionAlreadyFinalized', message: "Extension proposal already finalized", category: 'state' },
+  6203: { name: 'VotingClosed', message: "Voting period has closed", category: 'state' },
+  6204: { name: 'VotingStillOpen', message: "Voting period is still open", category: 'state' },
+  6205: { name: 'TargetNotReached', message: "Campaign has not reached its target", category: 'state' },
+  6206: { name: 'CampaignNotGraduated', message: "Campaign has not graduated", category: 'state' },
+  6207: { name: 'NoPriceFloor', message: "Campaign has no price floor", category: 'state' },
+  6208: { name: 'LockupActive', message: "Tokens are still locked", category: 'state' },
+  6209: { name: 'FreezeNotEnabled', message: "Campaign does not freeze tokens during the raise", category: 'state' },
+  6210: { name: 'TicketModeActive', message: "Tokens are not issued until a uniform-price raise closes", category: 'state' },
+  6211: { name: 'CampaignFailed', message: "Campaign has failed", category: 'state' },
+  6212: { name: 'CampaignNotFailed', message: "Campaign has not failed", category: 'state' },
+  6213: { name: 'NothingToRefund', message: "Nothing left to refund", category: 'state' },
+  6214: { name: 'RefundAmountMismatch', message: "Refund must cover the full amount received", category: 'state' },
+  6215: { name: 'LiquidityUnlocked', message: "Liquidity has already been unlocked", category: 'state' },
+  6216: { name: 'MatchStillActive', message: "Match has not expired yet", category: 'state' },
+  6217: { name: 'CampaignStillActive', message: "Campaign is still active", category: 'state' },
+  6218: { name: 'HoldingPeriodNotMet', message: "Tokens have not been held long enough", category: 'state' },
+  6219: { name: 'NothingToRebate', message: "No fees left to rebate", category: 'state' },
+  6220: { name: 'SwapNotConfigured', message: "No swap program is configured for stablecoin payouts", category: 'state' },
+  6221: { name: 'CampaignAlreadyGraduated', message: "Campaign has already graduated", category: 'state' },
+  6222: { name: 'SimulationOnly', message: "Simulation finished; see return data for the outcome", category: 'state' },
+  6223: { name: 'LendingNotConfigured', message: "No lending program is configured", category: 'state' },
+  6224: { name: 'NothingLent', message: "Campaign has no SOL out on loan", category: 'state' },
+  6225: { name: 'FundsStillLent', message: "Recall lent SOL before settling the campaign", category: 'state' },
+  6226: { name: 'CompressionNotEnabled', message: "Campaign does not keep compressed contribution records", category: 'state' },
+  6227: { name: 'CompressionAlreadyEnabled', message: "Compressed records are already enabled", category: 'state' },
+  6228: { name: 'TooManyActiveCampaigns', message: "Creator already has the maximum number of active campaigns", category: 'state' },
+  6229: { name: 'CreatorCooldownActive', message: "Creator is in the cooldown after a failed campaign", category: 'state' },
+  6230: { name: 'CampaignAlreadySettled', message: "Campaign has already been settled", category: 'state' },
+  6231: { name: 'SecondaryCurveDisabled', message: "Campaign has no secondary curve", category: 'state' },
+  6232: { name: 'CampaignPaused', message: "Campaign is paused", category: 'state' },
+  6233: { name: 'CampaignNotPaused', message: "Exits can only be queued while the campaign is paused", category: 'state' },
+  6234: { name: 'ExitQueueNotEmpty', message: "Queued exits must be processed first", category: 'state' },
+  6235: { name: 'CampaignNotStarted', message: "Campaign has not started yet", category: 'state' },
+  6236: { name: 'CampaignAlreadyStarted', message: "Campaign has already started", category: 'state' },
+  6237: { name: 'PreDepositsDisabled', message: "Campaign does not take pre-launch deposits", category: 'state' },
+  6238: { name: 'PreDepositsPending', message: "Pre-launch deposits must be converted first", category: 'state' },
+  6239: { name: 'TooManyCoCreators', message: "Campaign already has the maximum number of co-creators", category: 'state' },
+  6240: { name: 'ContributionsPaused', message: "The campaign team has paused contributions", category: 'state' },
+  6241: { name: 'IllegalStatusTransition', message: "Campaign status can't change that way", category: 'state' },
+  6242: { name: 'CampaignPendingReview', message: "Campaign is awaiting platform review", category: 'state' },
+  6243: { name: 'CampaignNotPendingReview', message: "Campaign is not awaiting review", category: 'state' },
+  6244: { name: 'FreezeAuthorityRevoked', message: "Campaign no longer holds the mint's freeze authority", category: 'state' },
+  6245: { name: 'TeamSellEmbargo', message: "Campaign team tokens are locked until the post-graduation lockup ends", category: 'state' },
+  6246: { name: 'CampaignNotStale', message: "Campaign has been backed, has finished or isn't old enough to expire", category: 'state' },
+  6247: { name: 'DistributionNotConfigured', message: "Campaign has no unsold supply distribution", category: 'state' },
+  6248: { name: 'DistributionAlreadyFinalized', message: "Unsold supply has already been distributed", category: 'state' },
+  6249: { name: 'StretchGoalLocked', message: "Funds past the latest stretch goal reached stay locked until the next one", category: 'state' },
+  6250: { name: 'WithdrawalApprovalsDisabled', message: "Campaign does not require approval for this withdrawal", category: 'state' },
+  6251: { name: 'StakeLocked', message: "Staked tokens are locked until the campaign graduates or fails", category: 'state' },
+  6252: { name: 'AttestationOracleNotSet', message: "No attestation oracle has been configured", category: 'state' },
+  6253: { name: 'PriceCrankTooSoon', message: "Price was cranked too recently", category: 'state' },
+  6254: { name: 'LaunchSlotFull', message: "This launch window is fully booked", category: 'state' },
+  6255: { name: 'LaunchWindowPassed', message: "Launch windows must be booked before they open", category: 'state' },
+  6256: { name: 'LaunchSlotAlreadyReserved', message: "Campaign already holds a launch slot", category: 'state' },
+  6257: { name: 'NoLaunchSlot', message: "Campaign has no launch slot", category: 'state' },
+  6258: { name: 'NotInLaunchWindow', message: "Outside the campaign's launch window", category: 'state' },
+  6259: { name: 'ParentCampaignUnresolved', message: "Parent campaign has neither graduated nor failed", category: 'state' },
+  6260: { name: 'ParentCampaignAlreadyResolved', message: "Parent campaign outcome already applied", category: 'state' },
+  6261: { name: 'ContentGatingDisabled', message: "Campaign has no gated content", category: 'state' },
+  6262: { name: 'ClosingAuctionUnavailable', message: "No closing auction for this campaign", category: 'state' },
+  6263: { name: 'AuctionBiddingClosed', message: "Closing auction bidding has ended", category: 'state' },
+  6264: { name: 'AuctionNotRevealing', message: "Closing auction is not in its reveal window", category: 'state' },
+  6265: { name: 'AuctionNotEnded', message: "Closing auction hasn't ended", category: 'state' },
+  6266: { name: 'AuctionAlreadySettled', message: "Closing auction already settled", category: 'state' },
+  6267: { name: 'BidAlreadyRevealed', message: "Bid already revealed", category: 'state' },
+  6268: { name: 'NoPendingRevenue', message: "No revenue deposited since the last snapshot", category: 'state' },
+  6269: { name: 'CircuitBreakerTripped', message: "Buy would move the price too far from its recent average", category: 'state' },
+  6270: { name: 'NotDonationCampaign', message: "Only donation campaigns issue donation receipts", category: 'state' },
+  6271: { name: 'IdentityVerifierNotSet', message: "No identity verifier has been configured", category: 'state' },
+  6272: { name: 'DepositAdapterInactive', message: "Deposit adapter hasn't been approved or has been switched off", category: 'state' },
+  6273: { name: 'AllowlistPhaseActive', message: "Only allowlisted wallets can contribute until the allowlist phase ends", category: 'state' },
+  6274: { name: 'AllowlistTierClosed', message: "Allowlist tier is outside its window", category: 'state' },
+  6275: { name: 'DripCapExceeded', message: "Withdrawal exceeds this epoch's drip cap", category: 'state' },
+  6276: { name: 'TooManyFaqEntries', message: "Campaign already has the maximum number of FAQ entries", category: 'state' },
+  6277: { name: 'NoTierBonusPending', message: "No allowlist tier bonus is waiting to be claimed", category: 'state' },
+  6278: { name: 'RefundBatchUnsupported', message: "Only ticket and donation campaigns can be refunded in batches; token holders refund themselves", category: 'state' },
+  6279: { name: 'PriceHistoryMigrated', message: "Price history already uses the current layout", category: 'state' },
+  6280: { name: 'RevenueClaimsNotOpen', message: "Revenue snapshot is still open to challenge", category: 'state' },
+  6281: { name: 'RevenueEpochClosed', message: "Revenue epoch has been voided or swept", category: 'state' },
+  6282: { name: 'RevenueChallengeClosed', message: "Revenue snapshot is past its challenge window", category: 'state' },
+  6283: { name: 'RevenueClaimWindowOpen', message: "Revenue epoch's claim window hasn't ended", category: 'state' },
+  6284: { name: 'SnsDomainStillOwned', message: "The creator still owns this .sol domain", category: 'state' },
+  6285: { name: 'ContentKeyStillEligible', message: "Backer still qualifies for gated content", category: 'state' },
+  6286: { name: 'ExtensionVoteTooLate', message: "Extension voting must close before the campaign deadline", category: 'state' },
+  6287: { name: 'VotingNotOpen', message: "Voting hasn't opened yet", category: 'state' },
+  6300: { name: 'InsufficientFunds', message: "Insufficient funds", category: 'math' },
+  6301: { name: 'MathOverflow', message: "Arithmetic overflow", category: 'math' },
+  6302: { name: 'ReserveLocked', message: "Amount would dip into the locked floor reserve", category: 'math' },
+  6303: { name: 'SlippageExceeded', message: "Output is below the minimum requested", category: 'math' },
+  6304: { name: 'AirdropExhausted', message: "Airdrop allocation exhausted", category: 'math' },
+  6305: { name: 'PriceImpactExceeded', message: "Contribution moves the price more than the campaign allows", category: 'math' },
+  6306: { name: 'AmountTooSmall', message: "Amount is too small to produce any output", category: 'math' },
+  6307: { name: 'FloorReserveExhausted', message: "Floor reserve cannot cover this redemption", category: 'math' },
+  6308: { name: 'InsufficientCampaignBalance', message: "Campaign account does not hold enough lamports", category: 'math' },
+  6309: { name: 'TreasuryBuyExceedsWithdrawn', message: "Treasury buys cannot exceed what the creator has withdrawn", category: 'math' },
+  6310: { name: 'RebateVaultExhausted', message: "Rebate vault cannot cover this rebate", category: 'math' },
+  6311: { name: 'InsufficientCurveLiquidity', message: "Not enough SOL on the curve outside the floor reserve to buy these tokens back", category: 'math' },
+  6312: { name: 'WithdrawalExceedsRaised', message: "Withdrawal exceeds the amount raised", category: 'math' },
+  6313: { name: 'CurveReserveLocked', message: "Amount would dip into the curve's buy-back reserve", category: 'math' },
+  6314: { name: 'LendingCallFailed', message: "Lending program did not move the expected funds", category: 'math' },
+  6315: { name: 'RentReserveViolated', message: "Campaign vault would drop below its rent-exempt reserve", category: 'math' },
+  6316: { name: 'InvariantViolated', message: "Campaign accounting invariant violated", category: 'math' },
+  6317: { name: 'InsufficientInsuranceFunds', message: "The insurance pool cannot cover that compensation", category: 'math' },
+  6318: { name: 'InsuranceClaimsExhausted', message: "Claims exceed the ruling's total basis", category: 'math' },
+  6319: { name: 'InsufficientStake', message: "Not that many tokens staked", category: 'math' },
+  6320: { name: 'HardCapExceeded', message: "Purchase would exceed the campaign's hard cap", category: 'math' },
+  6321: { name: 'BidDepositTooSmall', message: "Bid deposit doesn't cover the lot", category: 'math' },
+  6322: { name: 'SupplyBucketExhausted', message: "Mint would exceed its supply bucket", category: 'math' },
+  6323: { name: 'RelayEscrowInsufficient', message: "Relay escrow can't cover this, or would be left below its rent-exempt minimum", category: 'math' },
+  6324: { name: 'GovernanceTokensShort', message: "Tokens go back to the curve only with the governance tokens minted alongside them", category: 'math' },
+}
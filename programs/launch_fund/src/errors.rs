@@ -0,0 +1,602 @@
+// Program errors, grouped so clients can tell what kind of failure happened from
+// the code alone. Anchor offsets every variant by 6000, so each group starts at a
+// fixed hundred and new variants go at the end of their group.
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    // Validation: malformed arguments, accounts or campaign options (6000..6099)
+    #[msg("Invalid contribution amount")]
+    InvalidAmount = 0,
+    #[msg("Campaign end time must be in the future")]
+    InvalidEndTime,
+    #[msg("Extension duration must be positive")]
+    InvalidExtensionDuration,
+    #[msg("Reserve ratio cannot exceed 100%")]
+    InvalidReserveRatio,
+    #[msg("Governance token accounts are missing or invalid")]
+    MissingGovernanceAccounts,
+    #[msg("Lockup duration cannot be negative")]
+    InvalidLockup,
+    #[msg("Recontribute ratio must be between 1 and 10000 bps")]
+    InvalidRecontributeRatio,
+    #[msg("Fee exceeds the allowed maximum")]
+    InvalidFee,
+    #[msg("Mint does not belong to this campaign")]
+    InvalidTokenMint,
+    #[msg("Account is not a valid token account")]
+    InvalidTokenAccount,
+    #[msg("Claim ticket accounts are missing or invalid")]
+    MissingTicketAccounts,
+    #[msg("Campaign name must be between 1 and 50 bytes")]
+    InvalidCampaignName,
+    #[msg("Campaign description, symbol or token name is too long")]
+    MetadataTooLong,
+    #[msg("Campaign limits are inconsistent")]
+    InvalidCampaignLimits,
+    #[msg("Campaign duration is outside the platform limits")]
+    InvalidCampaignDuration,
+    #[msg("Target amount is outside the platform limits")]
+    InvalidTargetAmount,
+    #[msg("Invalid match terms")]
+    InvalidMatchTerms,
+    #[msg("Campaign has a sponsor match that must be passed in")]
+    MissingMatchCommitment,
+    #[msg("Platform requires a symbol index for new campaigns")]
+    MissingSymbolIndex,
+    #[msg("Graduation threshold must be at least the target")]
+    InvalidGraduationThreshold,
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Airdrop must be non-zero and within the campaign's airdrop reserve")]
+    AirdropExceedsSupply,
+    #[msg("Conversion rate must be greater than zero")]
+    InvalidRate,
+    #[msg("Frontend fee share cannot exceed 100%")]
+    InvalidFeeShare,
+    #[msg("Fee tiers must start at zero and have strictly increasing thresholds")]
+    InvalidFeeTiers,
+    #[msg("Fee exceeds the platform maximum")]
+    FeeTooHigh,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Region index must be below 64")]
+    InvalidRegion,
+    #[msg("Compressed records are not supported for governance campaigns")]
+    CompressionUnsupported,
+    #[msg("Account is not a governance of the given realm")]
+    InvalidGovernanceAccount,
+    #[msg("Secondary curve must rise by at least the closing price per SOL")]
+    InvalidSecondarySlope,
+    #[msg("TWAP window must be positive")]
+    InvalidTwapWindow,
+    #[msg("Start time must be between now and the end time")]
+    InvalidStartTime,
+    #[msg("Pre-launch deposits can't be combined with governance tokens, a price impact cap or allowlist tiers")]
+    PreDepositsUnsupported,
+    #[msg("Unknown creator role bits")]
+    InvalidCreatorRoles,
+    #[msg("Basket leg accounts are missing, repeated or don't match")]
+    InvalidBasketLeg,
+    #[msg("Ticket and governance token campaigns can't be bought into through a basket")]
+    BasketUnsupported,
+    #[msg("The insurance pool account is required while it takes a fee share")]
+    InsurancePoolRequired,
+    #[msg("Pass the Solana Pay reference as the first remaining account")]
+    MissingPaymentReference,
+    #[msg("Unsold supply shares must add up to 100%")]
+    InvalidDistribution,
+    #[msg("Stretch goals must be ascending, above the target and at most four")]
+    InvalidStretchGoals,
+    #[msg("Staking needs a plain curve campaign: no freezing, tickets or governance tokens")]
+    StakingUnsupported,
+    #[msg("Contributor milestones must be ascending, non-zero and at most four")]
+    InvalidContributorMilestones,
+    #[msg("Launch calendar windows need a positive length and capacity")]
+    InvalidLaunchCalendar,
+    #[msg("Hard cap must be zero or at least the target")]
+    InvalidHardCap,
+    #[msg("Invalid parent campaign")]
+    InvalidParentCampaign,
+    #[msg("Contribution is below the minimum")]
+    ContributionBelowMinimum,
+    #[msg("Bid is below the closing curve price")]
+    BidBelowReserve,
+    #[msg("Winning bid accounts are required")]
+    MissingWinningBid,
+    #[msg("Refunds can only be redirected into a different, plain curve campaign")]
+    InvalidRedirectTarget,
+    #[msg("Circuit breaker needs a positive window and cooldown")]
+    InvalidCircuitBreaker,
+    #[msg("Salary stream duration must be positive")]
+    InvalidSalaryStream,
+    #[msg("Donation campaigns don't issue tokens, so token options and trading are unavailable")]
+    DonationModeUnsupported,
+    #[msg("Refund batches take up to ten record, contributor and backer profile triples for this campaign")]
+    InvalidRefundBatch,
+    #[msg("Identity handle must be a .sol domain or a did:method:id DID of at most 64 bytes")]
+    InvalidIdentityHandle,
+    #[msg("Reserved allocations must leave the curve some supply")]
+    InvalidSupplyAllocation,
+    #[msg("Adapter name must be 1-32 bytes")]
+    InvalidAdapterName,
+    #[msg("Allowlist tiers need an index under the limit, a root, a window inside the campaign and a bonus within the cap")]
+    InvalidAllowlistTier,
+    #[msg("Contribution would take the wallet past its allowlist tier's cap")]
+    AllowlistCapExceeded,
+    #[msg("Pass the campaign's allowlist tier as the first remaining account")]
+    AllowlistTierRequired,
+    #[msg("Drip cap must be at most 100% and needs an epoch of at least 8 seconds")]
+    InvalidDripCap,
+    #[msg("Token symbol must be 1-10 ASCII letters or digits")]
+    InvalidTokenSymbol,
+    #[msg("Token name must be 1-50 ASCII letters, digits and spaces")]
+    InvalidTokenName,
+    #[msg("Name blocklist entry accounts are required while the blocklist is enabled")]
+    NameBlocklistEntryRequired,
+    #[msg("FAQ entries need an answer hash and a URI of at most 200 bytes")]
+    InvalidFaqEntry,
+    #[msg("Splits need 1-3 beneficiary ATAs of the campaign's mint, each paired with its contribution record, non-zero shares totalling at most 100%, and a campaign minting transferable tokens without a governance token")]
+    InvalidContributionSplit,
+    #[msg("Rejected campaigns must refund every queued deposit, passed as deposit and depositor pairs")]
+    InvalidPreDepositRefund,
+    #[msg("Minimum holding period can't be negative or outlast the campaign")]
+    InvalidHoldPeriod,
+    #[msg("Revenue token accounts don't match the stream's mint")]
+    InvalidRevenueAccounts,
+    #[msg("This campaign publishes a price feed, which every trade must pass")]
+    PriceFeedRequired,
+    #[msg("Curve needs a whole token to cost at least a lamport and a bonus of at most 100% with somewhere to end")]
+    InvalidCurveConfig,
+    #[msg("LP mint isn't from the platform's AMM")]
+    InvalidLpMint,
+    #[msg("Symbol index belongs to another campaign")]
+    SymbolIndexMismatch,
+    #[msg("A ticket mint can only be created for a uniform-price raise")]
+    UnexpectedTicketMint,
+    #[msg("Governance campaigns can't use this path, which can't carry their governance tokens")]
+    GovernanceUnsupported,
+
+    // Auth: the signer or an attestation isn't allowed to do this (6100..6199)
+    #[msg("Unauthorized access")]
+    Unauthorized = 100,
+    #[msg("No voting power in this campaign")]
+    NoVotingPower,
+    #[msg("Signed intent has expired")]
+    IntentExpired,
+    #[msg("Missing or invalid Ed25519 signature")]
+    InvalidSignature,
+    #[msg("Nonce has already been used")]
+    NonceAlreadyUsed,
+    #[msg("Frontend payout account does not match its registration")]
+    InvalidFrontendPayout,
+    #[msg("Invalid merkle proof")]
+    InvalidMerkleProof,
+    #[msg("Token holders have not approved the unlock")]
+    UnlockNotApproved,
+    #[msg("Invalid Wormhole message")]
+    InvalidWormholeMessage,
+    #[msg("Badge criteria not met")]
+    BadgeNotEarned,
+    #[msg("Only the campaign creator can do this")]
+    NotCampaignCreator,
+    #[msg("Only the platform admin can do this")]
+    NotAdmin,
+    #[msg("Only the program upgrade authority can do this")]
+    NotUpgradeAuthority,
+    #[msg("Campaign requires a region attestation for this contributor")]
+    RegionAttestationRequired,
+    #[msg("Contributor's region is restricted by this campaign")]
+    RegionRestricted,
+    #[msg("Not a co-creator of this campaign")]
+    NotCoCreator,
+    #[msg("Co-creator lacks the role this requires")]
+    MissingCreatorRole,
+    #[msg("Only the platform reviewer can do this")]
+    NotReviewer,
+    #[msg("Campaign authority has been handed to token holder governance")]
+    AuthorityHeldByGovernance,
+    #[msg("Withdrawal is above the approval threshold and lacks top backer sign-off")]
+    WithdrawalNotApproved,
+    #[msg("Approval is for a different withdrawal amount")]
+    WithdrawalApprovalMismatch,
+    #[msg("Only the campaign's top backers can approve withdrawals")]
+    NotTopBacker,
+    #[msg("Contribution is below the gated content threshold")]
+    BelowContentThreshold,
+    #[msg("Revealed bid doesn't match its commitment")]
+    BidCommitmentMismatch,
+    #[msg("Denylist entry account is required while screening is enabled")]
+    DenylistEntryRequired,
+    #[msg("Address is on the denylist")]
+    AddressDenied,
+    #[msg("Only the campaign's governance can do this")]
+    NotCampaignGovernance,
+    #[msg("Name account isn't that .sol domain or isn't owned by the creator")]
+    InvalidSnsDomain,
+    #[msg("Symbol or name is on the platform blocklist")]
+    NameBlocked,
+    #[msg("Withdrawals are paid to the campaign creator")]
+    InvalidWithdrawalDestination,
+    #[msg("Identity attestation has expired")]
+    IdentityAttestationExpired,
+
+    // State: the campaign or account is in the wrong phase for this (6200..6299)
+    #[msg("Campaign is not active")]
+    CampaignNotActive = 200,
+    #[msg("Campaign deadline has passed")]
+    DeadlinePassed,
+    #[msg("Extension proposal already finalized")]
+    ExtensionAlreadyFinalized,
+    #[msg("Voting period has closed")]
+    VotingClosed,
+    #[msg("Voting period is still open")]
+    VotingStillOpen,
+    #[msg("Campaign has not reached its target")]
+    TargetNotReached,
+    #[msg("Campaign has not graduated")]
+    CampaignNotGraduated,
+    #[msg("Campaign has no price floor")]
+    NoPriceFloor,
+    #[msg("Tokens are still locked")]
+    LockupActive,
+    #[msg("Campaign does not freeze tokens during the raise")]
+    FreezeNotEnabled,
+    #[msg("Tokens are not issued until a uniform-price raise closes")]
+    TicketModeActive,
+    #[msg("Campaign has failed")]
+    CampaignFailed,
+    #[msg("Campaign has not failed")]
+    CampaignNotFailed,
+    #[msg("Nothing left to refund")]
+    NothingToRefund,
+    #[msg("Refund must cover the full amount received")]
+    RefundAmountMismatch,
+    #[msg("Liquidity has already been unlocked")]
+    LiquidityUnlocked,
+    #[msg("Match has not expired yet")]
+    MatchStillActive,
+    #[msg("Campaign is still active")]
+    CampaignStillActive,
+    #[msg("Tokens have not been held long enough")]
+    HoldingPeriodNotMet,
+    #[msg("No fees left to rebate")]
+    NothingToRebate,
+    #[msg("No swap program is configured for stablecoin payouts")]
+    SwapNotConfigured,
+    #[msg("Campaign has already graduated")]
+    CampaignAlreadyGraduated,
+    #[msg("Simulation finished; see return data for the outcome")]
+    SimulationOnly,
+    #[msg("No lending program is configured")]
+    LendingNotConfigured,
+    #[msg("Campaign has no SOL out on loan")]
+    NothingLent,
+    #[msg("Recall lent SOL before settling the campaign")]
+    FundsStillLent,
+    #[msg("Campaign does not keep compressed contribution records")]
+    CompressionNotEnabled,
+    #[msg("Compressed records are already enabled")]
+    CompressionAlreadyEnabled,
+    #[msg("Creator already has the maximum number of active campaigns")]
+    TooManyActiveCampaigns,
+    #[msg("Creator is in the cooldown after a failed campaign")]
+    CreatorCooldownActive,
+    #[msg("Campaign has already been settled")]
+    CampaignAlreadySettled,
+    #[msg("Campaign has no secondary curve")]
+    SecondaryCurveDisabled,
+    #[msg("Campaign is paused")]
+    CampaignPaused,
+    #[msg("Exits can only be queued while the campaign is paused")]
+    CampaignNotPaused,
+    #[msg("Queued exits must be processed first")]
+    ExitQueueNotEmpty,
+    #[msg("Campaign has not started yet")]
+    CampaignNotStarted,
+    #[msg("Campaign has already started")]
+    CampaignAlreadyStarted,
+    #[msg("Campaign does not take pre-launch deposits")]
+    PreDepositsDisabled,
+    #[msg("Pre-launch deposits must be converted first")]
+    PreDepositsPending,
+    #[msg("Campaign already has the maximum number of co-creators")]
+    TooManyCoCreators,
+    #[msg("The campaign team has paused contributions")]
+    ContributionsPaused,
+    #[msg("Campaign status can't change that way")]
+    IllegalStatusTransition,
+    #[msg("Campaign is awaiting platform review")]
+    CampaignPendingReview,
+    #[msg("Campaign is not awaiting review")]
+    CampaignNotPendingReview,
+    #[msg("Campaign no longer holds the mint's freeze authority")]
+    FreezeAuthorityRevoked,
+    #[msg("Campaign team tokens are locked until the post-graduation lockup ends")]
+    TeamSellEmbargo,
+    #[msg("Campaign has been backed, has finished or isn't old enough to expire")]
+    CampaignNotStale,
+    #[msg("Campaign has no unsold supply distribution")]
+    DistributionNotConfigured,
+    #[msg("Unsold supply has already been distributed")]
+    DistributionAlreadyFinalized,
+    #[msg("Funds past the latest stretch goal reached stay locked until the next one")]
+    StretchGoalLocked,
+    #[msg("Campaign does not require approval for this withdrawal")]
+    WithdrawalApprovalsDisabled,
+    #[msg("Staked tokens are locked until the campaign graduates or fails")]
+    StakeLocked,
+    #[msg("No attestation oracle has been configured")]
+    AttestationOracleNotSet,
+    #[msg("Price was cranked too recently")]
+    PriceCrankTooSoon,
+    #[msg("This launch window is fully booked")]
+    LaunchSlotFull,
+    #[msg("Launch windows must be booked before they open")]
+    LaunchWindowPassed,
+    #[msg("Campaign already holds a launch slot")]
+    LaunchSlotAlreadyReserved,
+    #[msg("Campaign has no launch slot")]
+    NoLaunchSlot,
+    #[msg("Outside the campaign's launch window")]
+    NotInLaunchWindow,
+    #[msg("Parent campaign has neither graduated nor failed")]
+    ParentCampaignUnresolved,
+    #[msg("Parent campaign outcome already applied")]
+    ParentCampaignAlreadyResolved,
+    #[msg("Campaign has no gated content")]
+    ContentGatingDisabled,
+    #[msg("No closing auction for this campaign")]
+    ClosingAuctionUnavailable,
+    #[msg("Closing auction bidding has ended")]
+    AuctionBiddingClosed,
+    #[msg("Closing auction is not in its reveal window")]
+    AuctionNotRevealing,
+    #[msg("Closing auction hasn't ended")]
+    AuctionNotEnded,
+    #[msg("Closing auction already settled")]
+    AuctionAlreadySettled,
+    #[msg("Bid already revealed")]
+    BidAlreadyRevealed,
+    #[msg("No revenue deposited since the last snapshot")]
+    NoPendingRevenue,
+    #[msg("Buy would move the price too far from its recent average")]
+    CircuitBreakerTripped,
+    #[msg("Only donation campaigns issue donation receipts")]
+    NotDonationCampaign,
+    #[msg("No identity verifier has been configured")]
+    IdentityVerifierNotSet,
+    #[msg("Deposit adapter hasn't been approved or has been switched off")]
+    DepositAdapterInactive,
+    #[msg("Only allowlisted wallets can contribute until the allowlist phase ends")]
+    AllowlistPhaseActive,
+    #[msg("Allowlist tier is outside its window")]
+    AllowlistTierClosed,
+    #[msg("Withdrawal exceeds this epoch's drip cap")]
+    DripCapExceeded,
+    #[msg("Campaign already has the maximum number of FAQ entries")]
+    TooManyFaqEntries,
+    #[msg("No allowlist tier bonus is waiting to be claimed")]
+    NoTierBonusPending,
+    #[msg("Only ticket and donation campaigns can be refunded in batches; token holders refund themselves")]
+    RefundBatchUnsupported,
+    #[msg("Price history already uses the current layout")]
+    PriceHistoryMigrated,
+    #[msg("Revenue snapshot is still open to challenge")]
+    RevenueClaimsNotOpen,
+    #[msg("Revenue epoch has been voided or swept")]
+    RevenueEpochClosed,
+    #[msg("Revenue snapshot is past its challenge window")]
+    RevenueChallengeClosed,
+    #[msg("Revenue epoch's claim window hasn't ended")]
+    RevenueClaimWindowOpen,
+    #[msg("The creator still owns this .sol domain")]
+    SnsDomainStillOwned,
+    #[msg("Backer still qualifies for gated content")]
+    ContentKeyStillEligible,
+    #[msg("Extension voting must close before the campaign deadline")]
+    ExtensionVoteTooLate,
+    #[msg("Voting hasn't opened yet")]
+    VotingNotOpen,
+
+    // Math: arithmetic or balance limits (6300..6399)
+    #[msg("Insufficient funds")]
+    InsufficientFunds = 300,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Amount would dip into the locked floor reserve")]
+    ReserveLocked,
+    #[msg("Output is below the minimum requested")]
+    SlippageExceeded,
+    #[msg("Airdrop allocation exhausted")]
+    AirdropExhausted,
+    #[msg("Contribution moves the price more than the campaign allows")]
+    PriceImpactExceeded,
+    #[msg("Amount is too small to produce any output")]
+    AmountTooSmall,
+    #[msg("Floor reserve cannot cover this redemption")]
+    FloorReserveExhausted,
+    #[msg("Campaign account does not hold enough lamports")]
+    InsufficientCampaignBalance,
+    #[msg("Treasury buys cannot exceed what the creator has withdrawn")]
+    TreasuryBuyExceedsWithdrawn,
+    #[msg("Rebate vault cannot cover this rebate")]
+    RebateVaultExhausted,
+    #[msg("Not enough SOL on the curve outside the floor reserve to buy these tokens back")]
+    InsufficientCurveLiquidity,
+    #[msg("Withdrawal exceeds the amount raised")]
+    WithdrawalExceedsRaised,
+    #[msg("Amount would dip into the curve's buy-back reserve")]
+    CurveReserveLocked,
+    #[msg("Lending program did not move the expected funds")]
+    LendingCallFailed,
+    #[msg("Campaign vault would drop below its rent-exempt reserve")]
+    RentReserveViolated,
+    #[msg("Campaign accounting invariant violated")]
+    InvariantViolated,
+    #[msg("The insurance pool cannot cover that compensation")]
+    InsufficientInsuranceFunds,
+    #[msg("Claims exceed the ruling's total basis")]
+    InsuranceClaimsExhausted,
+    #[msg("Not that many tokens staked")]
+    InsufficientStake,
+    #[msg("Purchase would exceed the campaign's hard cap")]
+    HardCapExceeded,
+    #[msg("Bid deposit doesn't cover the lot")]
+    BidDepositTooSmall,
+    #[msg("Mint would exceed its supply bucket")]
+    SupplyBucketExhausted,
+    #[msg("Relay escrow can't cover this, or would be left below its rent-exempt minimum")]
+    RelayEscrowInsufficient,
+    #[msg("Tokens go back to the curve only with the governance tokens minted alongside them")]
+    GovernanceTokensShort,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCategory {
+    Validation,
+    Auth,
+    State,
+    Math,
+}
+
+impl ErrorCategory {
+    // None for codes outside this program's ranges (Anchor's own, or a CPI'd program's)
+    pub fn from_code(code: u32) -> Option<Self> {
+        match code.checked_sub(ERROR_CODE_OFFSET)? / 100 {
+            0 => Some(Self::Validation),
+            1 => Some(Self::Auth),
+            2 => Some(Self::State),
+            3 => Some(Self::Math),
+            _ => None,
+        }
+    }
+}
+
+impl ErrorCode {
+    pub fn code(self) -> u32 {
+        ERROR_CODE_OFFSET + self as u32
+    }
+
+    pub fn category(self) -> ErrorCategory {
+        // Every variant sits inside one of the ranges above
+        ErrorCategory::from_code(self.code()).unwrap_or(ErrorCategory::State)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The enum's variants as declared in this file: the group comment each one
+    // sits under, its name, and its discriminant, numbered the way the compiler
+    // does (an explicit value resets the count, everything else follows on)
+    fn declared_variants() -> Vec<(ErrorCategory, &'static str, u32)> {
+        let source = include_str!("errors.rs");
+        let body = &source[source.find("pub enum ErrorCode {").unwrap()..];
+        let body = &body[..body.find("\n}\n").unwrap()];
+
+        let mut variants = Vec::new();
+        let mut group = None;
+        let mut next = 0;
+        for line in body.lines().skip(1).map(str::trim) {
+            if let Some(comment) = line.strip_prefix("// ") {
+                group = Some(match comment.split(':').next().unwrap() {
+                    "Validation" => ErrorCategory::Validation,
+                    "Auth" => ErrorCategory::Auth,
+                    "State" => ErrorCategory::State,
+                    "Math" => ErrorCategory::Math,
+                    other => panic!("unknown error group {other}"),
+                });
+            } else if let Some(variant) = line.strip_suffix(',') {
+                let (name, value) = match variant.split_once(" = ") {
+                    Some((name, value)) => (name, value.parse().unwrap()),
+                    None => (variant, next),
+                };
+                variants.push((
+                    group.expect("variant declared before any group"),
+                    name,
+                    value,
+                ));
+                next = value + 1;
+            }
+        }
+        variants
+    }
+
+    #[test]
+    fn every_variant_sits_in_its_declared_group() {
+        let variants = declared_variants();
+        assert!(variants.len() > 200);
+        for (group, name, value) in variants {
+            assert_eq!(
+                ErrorCategory::from_code(ERROR_CODE_OFFSET + value),
+                Some(group),
+                "{name} ({}) is declared under {group:?}",
+                ERROR_CODE_OFFSET + value
+            );
+        }
+
+        // Each group opens on its own hundred
+        assert_eq!(
+            ErrorCode::InvalidAmount.category(),
+            ErrorCategory::Validation
+        );
+        assert_eq!(ErrorCode::Unauthorized.category(), ErrorCategory::Auth);
+        assert_eq!(
+            ErrorCode::CampaignNotActive.category(),
+            ErrorCategory::State
+        );
+        assert_eq!(ErrorCode::InsufficientFunds.category(), ErrorCategory::Math);
+        assert_eq!(
+            ErrorCode::GovernanceTokensShort.category(),
+            ErrorCategory::Math
+        );
+    }
+
+    #[test]
+    fn each_group_stays_under_a_hundred_variants() {
+        let variants = declared_variants();
+        for group in [
+            ErrorCategory::Validation,
+            ErrorCategory::Auth,
+            ErrorCategory::State,
+            ErrorCategory::Math,
+        ] {
+            let count = variants
+                .iter()
+                .filter(|(declared, _, _)| *declared == group)
+                .count();
+            assert!(
+                count < 100,
+                "{group:?} has {count} variants and would spill into the next range"
+            );
+        }
+    }
+
+    #[test]
+    fn frontend_error_table_is_current() {
+        // lib/programErrors.ts is generated from this file by npm run generate:errors
+        let table = include_str!("../../../lib/programErrors.ts");
+        let variants = declared_variants();
+        assert_eq!(
+            table.matches(" name: '").count(),
+            variants.len(),
+            "rerun npm run generate:errors"
+        );
+        for (_, name, value) in variants {
+            let entry = format!("  {}: {{ name: '{name}',", ERROR_CODE_OFFSET + value);
+            assert!(
+                table.contains(&entry),
+                "{name} is missing from lib/programErrors.ts; rerun npm run generate:errors"
+            );
+        }
+    }
+
+    #[test]
+    fn codes_outside_the_program_ranges_have_no_category() {
+        assert_eq!(ErrorCategory::from_code(ERROR_CODE_OFFSET - 1), None);
+        assert_eq!(ErrorCategory::from_code(ERROR_CODE_OFFSET + 400), None);
+    }
+}
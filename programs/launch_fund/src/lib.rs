@@ -1,13 +1,194 @@
+// Anchor 0.31 program macro still calls AccountInfo::realloc in generated IDL code
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{invoke, invoke_signed, set_return_data};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_spl::associated_token::{self, AssociatedToken};
+use anchor_spl::token::spl_token::{self, instruction::AuthorityType};
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{ExtensionType, StateWithExtensions},
+    state::AccountState,
+};
+use anchor_spl::token_2022::{self as token_2022, Token2022};
+use anchor_spl::token_2022_extensions;
+
+mod errors;
+#[macro_use]
+mod log_marker;
+mod naming;
+mod q64;
+mod validation;
+
+pub use errors::{ErrorCategory, ErrorCode};
 
 declare_id!("8RDF8KobfNfe4ZCPw7T3xputHQDAT7wwiBBkFeRruECo");
 
+// Contributors get 48 hours to vote on a deadline extension
+const EXTENSION_VOTING_PERIOD: i64 = 48 * 60 * 60;
+// Once a liquidity lock expires, holders get a week to vote on releasing it
+// before an unvoted lock releases anyway
+const LIQUIDITY_UNLOCK_VOTE_SECS: i64 = 7 * 24 * 60 * 60;
+
+const BPS_DENOMINATOR: u64 = 10_000;
+// Campaign mints use 9 decimals, so prices are quoted per 10^9 base units
+const TOKEN_PRECISION: u128 = 1_000_000_000;
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+// Prefix for signed contribution intents so they can't be confused with other messages
+const CONTRIBUTION_INTENT_DOMAIN: &[u8] = b"launch_fund:contribute_with_signature";
+const CAMPAIGN_ATTESTATION_DOMAIN: &[u8] = b"launch_fund:campaign_attestation";
+// Prefix of the message the identity verifier signs for a DID link
+const IDENTITY_ATTESTATION_DOMAIN: &[u8] = b"launch_fund:identity_attestation";
+
+// SPL Name Service, and the root account .sol domains hang off
+const NAME_SERVICE_PROGRAM_ID: Pubkey = pubkey!("namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX");
+const SOL_TLD_AUTHORITY: Pubkey = pubkey!("58PwtjSDuFHuUkYjH9BYnnQKHfwo9reZhC2zMJv9JGkx");
+const NAME_SERVICE_HASH_PREFIX: &str = "SPL Name Service";
+const MAX_IDENTITY_HANDLE_LEN: usize = 64;
+
+// Number of trades kept in each campaign's price history ring buffer
+const PRICE_HISTORY_LEN: usize = 64;
+
+// Campaigns kept on each homepage leaderboard
+const LEADERBOARD_LEN: usize = 10;
+
+// Size of a price history written before the zero-copy layout: discriminator,
+// campaign, head and count, the samples and top backers, then the bump
+const LEGACY_PRICE_HISTORY_SPACE: usize = 8
+    + 32
+    + 2
+    + 2
+    + std::mem::size_of::<[PriceSample; PRICE_HISTORY_LEN]>()
+    + std::mem::size_of::<[LeaderboardEntry; LEADERBOARD_LEN]>()
+    + 1;
+
+// Upper bound the admin can set for the platform fee (10%)
+const MAX_PLATFORM_FEE_BPS: u16 = 1_000;
+const MAX_FEE_TIERS: usize = 4;
+// Upper bound for each of a campaign's own curve fees (10%)
+const MAX_CURVE_FEE_BPS: u16 = 1_000;
+// Upper bound for the exit fee a creator pays stakers on each withdrawal (20%)
+const MAX_EXIT_FEE_BPS: u16 = 2_000;
+
+// Byte limits for campaign metadata, matching the #[max_len] of Campaign
+const MAX_NAME_LEN: usize = 50;
+const MAX_DESCRIPTION_LEN: usize = 200;
+const MAX_SYMBOL_LEN: usize = 10;
+const MAX_TOKEN_NAME_LEN: usize = 50;
+
+// Co-creators a campaign can delegate to, and the roles they can be granted
+const MAX_CO_CREATORS: usize = 5;
+const MAX_STRETCH_GOALS: usize = 4;
+const MAX_CONTRIBUTOR_MILESTONES: usize = 4;
+// Backers whose sign-off large withdrawals need
+const WITHDRAWAL_APPROVERS: usize = 3;
+const ROLE_WITHDRAW: u8 = 1 << 0;
+const ROLE_UPDATE_METADATA: u8 = 1 << 1;
+const ROLE_PAUSE: u8 = 1 << 2;
+const ROLE_POST_UPDATES: u8 = 1 << 3;
+const ALL_ROLES: u8 = ROLE_WITHDRAW | ROLE_UPDATE_METADATA | ROLE_PAUSE | ROLE_POST_UPDATES;
+
+// PostedVAA accounts written by the Wormhole core bridge start with this tag
+const POSTED_VAA_MAGIC: &[u8; 3] = b"vaa";
+
+// Token bridge transfer-with-payload messages: the payload id, the fixed
+// transfer fields ahead of the sender's own payload, Solana's Wormhole chain
+// id and the bridge instruction that redeems a wrapped transfer
+const TRANSFER_WITH_PAYLOAD_ID: u8 = 3;
+const TRANSFER_WITH_PAYLOAD_HEADER_LEN: usize = 133;
+const SOLANA_WORMHOLE_CHAIN_ID: u16 = 1;
+const COMPLETE_WRAPPED_WITH_PAYLOAD_IX: u8 = 10;
+
+// Bytes of serialized state carried by each CampaignSnapshotEvent
+const SNAPSHOT_CHUNK_LEN: usize = 256;
+
+// Amount passed to the lending market's withdraw instruction to close out the whole position
+const LENDING_WITHDRAW_ALL: u64 = u64::MAX;
+
+// spl-account-compression and its noop log wrapper, driven by raw CPI
+const ACCOUNT_COMPRESSION_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+const NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+// SPL Governance, and the account-type bytes its realms start with (RealmV1, RealmV2)
+const SPL_GOVERNANCE_ID: Pubkey = pubkey!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
+const REALM_ACCOUNT_TYPES: [u8; 2] = [1, 16];
+
+// Accounts each basket leg passes, in order: campaign, token mint, the
+// contributor's token account, contribution record, price history and price
+// feed (the program id for campaigns without one)
+const BASKET_LEG_ACCOUNTS: usize = 6;
+
+// Campaigns never funded in this long can be closed by anyone, who keeps a
+// slice of the returned rent for doing it
+const STALE_CAMPAIGN_SECS: i64 = 90 * 24 * 60 * 60;
+const STALE_CAMPAIGN_KEEPER_TIP_BPS: u16 = 500;
+
+// Stamped on every event; bumped whenever an event's layout changes so
+// indexers know which decoder a log needs
+const EVENT_SCHEMA_VERSION: u8 = 2;
+
+// Keeper price cranks: at most one per campaign every interval, each paid a
+// small tip out of the campaign's crank budget
+const PRICE_CRANK_INTERVAL_SLOTS: u64 = 150;
+const PRICE_CRANK_TIP_LAMPORTS: u64 = 5_000;
+
+// Sealed-bid closing auction for supply left unsold at graduation: a day to
+// commit bids, a day to reveal them, and a week to settle before bidders can
+// take their deposits back regardless
+const CLOSING_AUCTION_BID_SECS: i64 = 24 * 60 * 60;
+const CLOSING_AUCTION_REVEAL_SECS: i64 = 24 * 60 * 60;
+const CLOSING_AUCTION_SETTLE_SECS: i64 = 7 * 24 * 60 * 60;
+
+// Refund cranks: records settled per transaction, and the slice of each
+// closed record's rent the cranker keeps
+const MAX_REFUND_BATCH: usize = 10;
+const REFUND_CRANK_TIP_BPS: u16 = 1_000;
+
+// The drip cap's rolling window is tracked in this many slices of the epoch
+const DRIP_BUCKETS: usize = 8;
+
+// Price feeds quote lamports per whole token, i.e. SOL at this exponent
+pub const PRICE_FEED_EXPONENT: i32 = -9;
+// Pyth's VerificationLevel::Full, serialized; the program computes the price itself
+pub const PRICE_FEED_VERIFICATION_FULL: u8 = 1;
+// Each trade moves the feed's EMA this fraction of the way to the new price
+pub const PRICE_FEED_EMA_WEIGHT: i64 = 10;
+
+// Seed of the PDA an adapter program signs deposits with, derived under its own id
+pub const DEPOSIT_ADAPTER_SIGNER_SEED: &[u8] = b"launch_deposit_adapter";
+pub const MAX_ADAPTER_NAME_LEN: usize = 32;
+
+pub const MAX_ALLOWLIST_TIERS: u8 = 4;
+// Cap on a tier's extra curve tokens, as a share of what the purchase buys
+pub const MAX_TIER_BONUS_BPS: u16 = 5_000;
+
+pub const MAX_FAQ_ENTRIES: u8 = 32;
+pub const MAX_FAQ_URI_LEN: usize = 200;
+
+// Wallets besides the contributor a contribution's tokens can be split to
+pub const MAX_SPLIT_BENEFICIARIES: usize = 3;
+
+// Revenue snapshots: a day in which the admin can void a bad snapshot before
+// holders claim, then a claim window after which anything unclaimed rolls
+// into the next snapshot
+const REVENUE_CHALLENGE_SECS: i64 = 24 * 60 * 60;
+const REVENUE_CLAIM_WINDOW_SECS: i64 = 90 * 24 * 60 * 60;
+
 #[program]
 pub mod launch_fund {
     use super::*;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_campaign(
         ctx: Context<InitializeCampaign>,
         name: String,
@@ -16,11 +197,194 @@ pub mod launch_fund {
         token_symbol: String,
         token_name: String,
         total_supply: u64,
+        end_time: i64,
+        options: CampaignOptions,
     ) -> Result<()> {
         let campaign = &mut ctx.accounts.campaign;
         let clock = Clock::get()?;
 
+        require!(
+            !name.is_empty() && name.len() <= MAX_NAME_LEN,
+            ErrorCode::InvalidCampaignName
+        );
+        require!(
+            description.len() <= MAX_DESCRIPTION_LEN,
+            ErrorCode::MetadataTooLong
+        );
+        let token_symbol = naming::symbol(&token_symbol)?;
+        let token_name = naming::token_name(&token_name)?;
+        if ctx.accounts.global_config.name_blocklist_enabled {
+            for entry in [
+                &ctx.accounts.symbol_block_entry,
+                &ctx.accounts.name_block_entry,
+            ] {
+                let entry = entry
+                    .as_ref()
+                    .ok_or(ErrorCode::NameBlocklistEntryRequired)?;
+                require!(entry.data_is_empty(), ErrorCode::NameBlocked);
+            }
+        }
+        require!(end_time > clock.unix_timestamp, ErrorCode::InvalidEndTime);
+
+        // An index passed while enforcement is off still claims the symbol, so
+        // it can be released like any other when the campaign ends
+        match ctx.accounts.symbol_index.as_mut() {
+            Some(symbol_index) => {
+                symbol_index.campaign = campaign.key();
+                symbol_index.bump = ctx
+                    .bumps
+                    .symbol_index
+                    .ok_or(ErrorCode::MissingSymbolIndex)?;
+            }
+            None => require!(
+                !ctx.accounts.global_config.enforce_unique_symbols,
+                ErrorCode::MissingSymbolIndex
+            ),
+        }
+
+        let limits = &ctx.accounts.global_config.limits;
+        let duration = end_time - clock.unix_timestamp;
+        require!(
+            duration >= limits.min_duration_secs && duration <= limits.max_duration_secs,
+            ErrorCode::InvalidCampaignDuration
+        );
+        validation::within(
+            target_amount,
+            limits.min_target_amount,
+            limits.max_target_amount,
+            ErrorCode::InvalidTargetAmount,
+        )?;
+
+        // Per-creator policy: a cap on concurrent campaigns and a cooldown after a failure
+        let profile = &mut ctx.accounts.creator_profile;
+        if profile.creator == Pubkey::default() {
+            profile.creator = ctx.accounts.creator.key();
+            profile.bump = ctx.bumps.creator_profile;
+        }
+        require!(
+            limits.max_active_per_creator == 0
+                || profile.active_campaigns < limits.max_active_per_creator,
+            ErrorCode::TooManyActiveCampaigns
+        );
+        require!(
+            profile.last_failure_at == 0
+                || clock.unix_timestamp
+                    >= profile
+                        .last_failure_at
+                        .saturating_add(limits.failure_cooldown_secs),
+            ErrorCode::CreatorCooldownActive
+        );
+        profile.active_campaigns += 1;
+        profile.total_campaigns = profile.total_campaigns.saturating_add(1);
+        require!(
+            options.floor_reserve_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidReserveRatio
+        );
+        require!(
+            options.curve_reserve_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidReserveRatio
+        );
+        require!(
+            options.buy_fee_bps <= MAX_CURVE_FEE_BPS && options.sell_fee_bps <= MAX_CURVE_FEE_BPS,
+            ErrorCode::FeeTooHigh
+        );
+        require!(
+            options.secondary_slope_bps == 0
+                || options.secondary_slope_bps as u64 >= BPS_DENOMINATOR,
+            ErrorCode::InvalidSecondarySlope
+        );
+        require!(
+            options.secondary_fee_bps <= MAX_CURVE_FEE_BPS,
+            ErrorCode::FeeTooHigh
+        );
+        require!(
+            options.exit_fee_bps <= MAX_EXIT_FEE_BPS,
+            ErrorCode::FeeTooHigh
+        );
+        require!(
+            options.hard_cap == 0 || options.hard_cap >= target_amount,
+            ErrorCode::InvalidHardCap
+        );
+        require!(
+            options.circuit_breaker_bps == 0 || options.circuit_breaker_slots > 0,
+            ErrorCode::InvalidCircuitBreaker
+        );
+        // Contributions to a child campaign wait in the pre-deposit queue
+        require!(
+            options.parent_campaign.is_none() || options.pre_deposits,
+            ErrorCode::InvalidParentCampaign
+        );
+        require!(options.team_lockup_secs >= 0, ErrorCode::InvalidLockup);
+        // A hold outlasting the raise would leave nothing to sell into
+        require!(
+            options.min_hold_secs >= 0 && options.min_hold_secs <= duration,
+            ErrorCode::InvalidHoldPeriod
+        );
+        require!(
+            options.drip_cap_bps as u64 <= BPS_DENOMINATOR
+                && (options.drip_cap_bps == 0 || options.drip_epoch_secs >= DRIP_BUCKETS as i64),
+            ErrorCode::InvalidDripCap
+        );
+        options.distribution.validate()?;
+        let curve = options.curve.unwrap_or_default();
+        curve.validate()?;
+        require!(
+            options.stretch_goals.len() <= MAX_STRETCH_GOALS
+                && options
+                    .stretch_goals
+                    .first()
+                    .is_none_or(|first| *first > target_amount)
+                && options
+                    .stretch_goals
+                    .windows(2)
+                    .all(|pair| pair[0] < pair[1]),
+            ErrorCode::InvalidStretchGoals
+        );
+        require!(
+            options.contributor_milestones.len() <= MAX_CONTRIBUTOR_MILESTONES
+                && options
+                    .contributor_milestones
+                    .first()
+                    .is_none_or(|first| *first > 0)
+                && options
+                    .contributor_milestones
+                    .windows(2)
+                    .all(|pair| pair[0] < pair[1]),
+            ErrorCode::InvalidContributorMilestones
+        );
+        require!(
+            options.start_time == 0
+                || (options.start_time >= clock.unix_timestamp && options.start_time < end_time),
+            ErrorCode::InvalidStartTime
+        );
+        // The queue is drained by a crank that can't mint governance tokens, and
+        // a deposit it couldn't fill under an impact cap would block everyone behind it
+        require!(
+            !options.pre_deposits
+                || (!options.governance_token && options.max_price_impact_bps == 0),
+            ErrorCode::PreDepositsUnsupported
+        );
+        require!(
+            options.graduation_threshold == 0 || options.graduation_threshold >= target_amount,
+            ErrorCode::InvalidGraduationThreshold
+        );
+        // With no tokens out there's nothing to redeem, sell back or settle against
+        require!(
+            !options.donation_mode
+                || (!options.governance_token
+                    && !options.uniform_price
+                    && !options.freeze_during_raise
+                    && options.floor_reserve_bps == 0
+                    && options.curve_reserve_bps == 0
+                    && options.secondary_slope_bps == 0
+                    && !options.distribution.is_configured()),
+            ErrorCode::DonationModeUnsupported
+        );
+
         campaign.creator = ctx.accounts.creator.key();
+        // Seeds are capped at 32 bytes, so the PDA is derived from the hash
+        // of the name rather than its raw (possibly multi-byte) encoding
+        campaign.name_hash = hash(name.as_bytes()).to_bytes();
         campaign.name = name;
         campaign.description = description;
         campaign.target_amount = target_amount;
@@ -28,99 +392,642 @@ pub mod launch_fund {
         campaign.token_symbol = token_symbol;
         campaign.token_name = token_name;
         campaign.total_supply = total_supply;
+        let reserved = options
+            .creator_allocation
+            .checked_add(options.liquidity_reserve)
+            .and_then(|reserved| reserved.checked_add(options.airdrop_reserve))
+            .ok_or(ErrorCode::InvalidSupplyAllocation)?;
+        campaign.curve_supply = total_supply
+            .checked_sub(reserved)
+            .filter(|curve_supply| *curve_supply > 0)
+            .ok_or(ErrorCode::InvalidSupplyAllocation)?;
+        campaign.creator_allocation = options.creator_allocation;
+        campaign.liquidity_reserve = options.liquidity_reserve;
+        campaign.airdrop_reserve = options.airdrop_reserve;
         campaign.token_mint = ctx.accounts.token_mint.key();
         campaign.created_at = clock.unix_timestamp;
-        campaign.is_active = true;
+        campaign.end_time = end_time;
+        // Launchpads in review mode hold new campaigns, and a bond, until a reviewer signs off
+        let config = &ctx.accounts.global_config;
+        if config.require_review {
+            campaign.status = CampaignStatus::PendingReview;
+            campaign.review_bond = config.review_bond;
+            if config.review_bond > 0 {
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::Transfer {
+                            from: ctx.accounts.creator.to_account_info(),
+                            to: campaign.to_account_info(),
+                        },
+                    ),
+                    config.review_bond,
+                )?;
+            }
+        } else {
+            campaign.status = CampaignStatus::Active;
+        }
+        campaign.floor_reserve_bps = options.floor_reserve_bps;
+        campaign.floor_reserve = 0;
+        campaign.floor_price = 0;
+        campaign.graduated_at = 0;
         campaign.bump = ctx.bumps.campaign;
+        campaign.freeze_during_raise = options.freeze_during_raise;
+        campaign.partial_refunds = options.partial_refunds;
+        campaign.graduation_threshold = if options.graduation_threshold == 0 {
+            target_amount
+        } else {
+            options.graduation_threshold
+        };
+        campaign.ready_to_graduate = false;
+        campaign.max_price_impact_bps = options.max_price_impact_bps;
+        campaign.curve_reserve_bps = options.curve_reserve_bps;
+        campaign.reserve_balance = 0;
+        campaign.withdrawable_balance = 0;
+        campaign.restricted_regions_bitmap = options.restricted_regions_bitmap;
+        campaign.lent_amount = 0;
+        campaign.yield_to_contributors = options.yield_to_contributors;
+        campaign.refund_buffer = 0;
+        campaign.buy_fee_bps = options.buy_fee_bps;
+        campaign.sell_fee_bps = options.sell_fee_bps;
+        campaign.secondary_slope_bps = options.secondary_slope_bps;
+        campaign.secondary_fee_bps = options.secondary_fee_bps;
+        campaign.secondary_base_price = 0;
+        campaign.secondary_raised = 0;
+        campaign.price_cumulative = 0;
+        campaign.last_trade_price = calculate_token_price(&curve, 0)?;
+        campaign.last_trade_at = clock.unix_timestamp;
+        campaign.start_time = options.start_time.max(clock.unix_timestamp);
+        campaign.pre_deposits = options.pre_deposits;
+        campaign.team_lockup_secs = options.team_lockup_secs;
+        campaign.distribution = options.distribution;
+        campaign.curve = curve;
+        campaign.stretch_goals[..options.stretch_goals.len()]
+            .copy_from_slice(&options.stretch_goals);
+        campaign.stretch_goal_count = options.stretch_goals.len() as u8;
+        campaign.withdrawal_approval_threshold = options.withdrawal_approval_threshold;
+        campaign.unapproved_withdrawn = 0;
+        campaign.contributor_milestones[..options.contributor_milestones.len()]
+            .copy_from_slice(&options.contributor_milestones);
+        campaign.contributor_milestone_count = options.contributor_milestones.len() as u8;
+        campaign.milestone_min_contribution = options.milestone_min_contribution;
+        campaign.exit_fee_bps = options.exit_fee_bps;
+        campaign.hard_cap = options.hard_cap;
+        campaign.circuit_breaker_bps = options.circuit_breaker_bps;
+        campaign.circuit_breaker_slots = options.circuit_breaker_slots;
+        campaign.donation_mode = options.donation_mode;
+        campaign.min_hold_secs = options.min_hold_secs;
+        campaign.drip_cap_bps = options.drip_cap_bps;
+        campaign.drip_epoch_secs = options.drip_epoch_secs;
+        if let Some(parent) = options.parent_campaign {
+            require_keys_neq!(parent, campaign.key(), ErrorCode::InvalidParentCampaign);
+            campaign.parent_campaign = parent;
+            campaign.start_time = i64::MAX;
+        }
+
+        // Uniform-price raises hand out claim tickets and settle tokens at close
+        if options.uniform_price {
+            let ticket_mint = ctx
+                .accounts
+                .ticket_mint
+                .as_ref()
+                .ok_or(ErrorCode::MissingTicketAccounts)?;
+            campaign.ticket_mint = ticket_mint.key();
+        } else {
+            // A stray ticket mint would be left with the campaign as authority
+            // and nothing that ever mints or redeems against it
+            require!(
+                ctx.accounts.ticket_mint.is_none(),
+                ErrorCode::UnexpectedTicketMint
+            );
+        }
+
+        // The mint is created with the campaign as freeze authority. It's kept
+        // until graduation so a failed campaign's token can be invalidated.
+
+        let mut price_history = ctx.accounts.price_history.load_init()?;
+        price_history.campaign = campaign.key();
+        price_history.head = 0;
+        price_history.count = 0;
+        price_history.bump = ctx.bumps.price_history;
+
+        if options.governance_token {
+            require!(
+                options.governance_lockup_secs >= 0,
+                ErrorCode::InvalidLockup
+            );
+            let (Some(governance_mint), Some(governance_authority), Some(token_2022_program)) = (
+                ctx.accounts.governance_mint.as_ref(),
+                ctx.accounts.governance_authority.as_ref(),
+                ctx.accounts.token_2022_program.as_ref(),
+            ) else {
+                return err!(ErrorCode::MissingGovernanceAccounts);
+            };
+
+            let campaign_key = campaign.key();
+            let mint_bump = ctx
+                .bumps
+                .governance_mint
+                .ok_or(ErrorCode::MissingGovernanceAccounts)?;
+            let mint_seeds: &[&[u8]] = &[b"governance_mint", campaign_key.as_ref(), &[mint_bump]];
+
+            // Allocate a Token-2022 mint with the NonTransferable extension
+            let space =
+                ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+                    ExtensionType::NonTransferable,
+                ])?;
+            anchor_lang::system_program::create_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::CreateAccount {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: governance_mint.to_account_info(),
+                    },
+                    &[mint_seeds],
+                ),
+                ctx.accounts.rent.minimum_balance(space),
+                space as u64,
+                &token_2022_program.key(),
+            )?;
+            token_2022_extensions::non_transferable_mint_initialize(CpiContext::new(
+                token_2022_program.to_account_info(),
+                token_2022_extensions::NonTransferableMintInitialize {
+                    token_program_id: token_2022_program.to_account_info(),
+                    mint: governance_mint.to_account_info(),
+                },
+            ))?;
+            token_2022::initialize_mint2(
+                CpiContext::new(
+                    token_2022_program.to_account_info(),
+                    token_2022::InitializeMint2 {
+                        mint: governance_mint.to_account_info(),
+                    },
+                ),
+                9,
+                &governance_authority.key(),
+                Some(&governance_authority.key()),
+            )?;
+
+            campaign.governance_mint = governance_mint.key();
+            campaign.governance_authority_bump = ctx
+                .bumps
+                .governance_authority
+                .ok_or(ErrorCode::MissingGovernanceAccounts)?;
+            campaign.governance_lockup_secs = options.governance_lockup_secs;
+        }
+
+        emit_logged!(CampaignCreatedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            creator: campaign.creator,
+            target_amount,
+            end_time,
+            identity_kind: ctx.accounts.creator_profile.identity_kind,
+            creator_handle: ctx.accounts.creator_profile.identity_handle.clone(),
+        });
 
         Ok(())
     }
 
-    pub fn contribute(ctx: Context<Contribute>, amount: u64) -> Result<()> {
-        require!(ctx.accounts.campaign.is_active, ErrorCode::CampaignNotActive);
-        require!(amount > 0, ErrorCode::InvalidAmount);
+    // `split_bps` optionally shares the minted tokens: the remaining accounts are
+    // (beneficiary ATA, beneficiary contribution record) pairs, `split_bps[i]` is
+    // the share sent to the i-th, and each share's cost basis moves onto that
+    // beneficiary's record so they can refund or sell it. Empty means no split.
+    pub fn contribute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Contribute<'info>>,
+        amount: u64,
+        client_nonce: Option<u64>,
+        split_bps: Vec<u16>,
+    ) -> Result<()> {
+        if !split_bps.is_empty() {
+            let campaign = &ctx.accounts.campaign;
+            // Splits need freely transferable curve tokens to hand out, and no
+            // governance weight, which would stay behind with the contributor
+            require!(
+                campaign.ticket_mint == Pubkey::default()
+                    && campaign.governance_mint == Pubkey::default()
+                    && !campaign.donation_mode
+                    && !campaign.freeze_during_raise,
+                ErrorCode::InvalidContributionSplit
+            );
+            require!(
+                split_bps.len() <= MAX_SPLIT_BENEFICIARIES
+                    && split_bps.len() * 2 == ctx.remaining_accounts.len()
+                    && split_bps.iter().all(|bps| *bps > 0)
+                    && split_bps.iter().map(|bps| *bps as u64).sum::<u64>() <= BPS_DENOMINATOR,
+                ErrorCode::InvalidContributionSplit
+            );
+        }
+        process_contribution(ctx, amount, client_nonce, None, None, &split_bps)?;
+        Ok(())
+    }
 
-        // Calculate tokens to mint based on bonding curve
-        let tokens_to_mint = calculate_tokens_from_sol(amount, ctx.accounts.campaign.raised_amount)?;
+    // Solana Pay transfer requests tag the transaction with a reference key so
+    // the merchant can find it; pass it as the first remaining account and it is
+    // echoed in the ContributionEvent
+    pub fn contribute_with_reference<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Contribute<'info>>,
+        amount: u64,
+        client_nonce: Option<u64>,
+    ) -> Result<()> {
+        let reference = ctx
+            .remaining_accounts
+            .first()
+            .map(|account| account.key())
+            .ok_or(ErrorCode::MissingPaymentReference)?;
+        process_contribution(ctx, amount, client_nonce, Some(reference), None, &[])?;
+        Ok(())
+    }
 
-        // Get campaign data for seeds
-        let campaign_creator = ctx.accounts.campaign.creator;
-        let campaign_name = ctx.accounts.campaign.name.clone();
-        let campaign_bump = ctx.accounts.campaign.bump;
-        let campaign_key = ctx.accounts.campaign.key();
-        let contributor_key = ctx.accounts.contributor.key();
+    // Contribute through an allowlist tier, passed as the first remaining
+    // account. The proof shows the contributor is on the tier's list; the tier's
+    // window and per-wallet cap apply. Its bonus is held on the record and only
+    // minted by claim_tier_bonus after graduation, so it can't be sold to the curve.
+    pub fn contribute_allowlisted<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Contribute<'info>>,
+        amount: u64,
+        client_nonce: Option<u64>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let tier_info = ctx
+            .remaining_accounts
+            .first()
+            .ok_or(ErrorCode::AllowlistTierRequired)?;
+        let mut tier = Account::<AllowlistTier>::try_from(tier_info)?;
+        require_keys_eq!(
+            tier.campaign,
+            ctx.accounts.campaign.key(),
+            ErrorCode::AllowlistTierRequired
+        );
 
-        // Transfer SOL from contributor to campaign
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            anchor_lang::system_program::Transfer {
-                from: ctx.accounts.contributor.to_account_info(),
-                to: ctx.accounts.campaign.to_account_info(),
-            },
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= tier.starts_at && now < tier.ends_at,
+            ErrorCode::AllowlistTierClosed
         );
-        anchor_lang::system_program::transfer(cpi_context, amount)?;
+        let contributor_key = ctx.accounts.contributor.key();
+        let leaf = hashv(&[contributor_key.as_ref()]).to_bytes();
+        require!(
+            verify_merkle_proof(&proof, tier.merkle_root, leaf),
+            ErrorCode::InvalidMerkleProof
+        );
+        // The cap counts the wallet's whole position, however it was built
+        if tier.per_wallet_cap > 0 {
+            let position = ctx
+                .accounts
+                .contribution_record
+                .sol_contributed
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+            require!(
+                position <= tier.per_wallet_cap,
+                ErrorCode::AllowlistCapExceeded
+            );
+        }
 
-        // Mint tokens to contributor
-        let seeds = &[
-            b"campaign",
-            campaign_creator.as_ref(),
-            campaign_name.as_bytes(),
-            &[campaign_bump],
-        ];
-        let signer = &[&seeds[..]];
+        let outcome =
+            process_contribution(ctx, amount, client_nonce, None, Some(tier.bonus_bps), &[])?;
+        tier.total_contributed = tier
+            .total_contributed
+            .checked_add(outcome.sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        tier.contribution_count = tier.contribution_count.saturating_add(1);
+        tier.exit(&crate::ID)?;
 
-        let cpi_accounts = token::MintTo {
-            mint: ctx.accounts.token_mint.to_account_info(),
-            to: ctx.accounts.contributor_token_account.to_account_info(),
-            authority: ctx.accounts.campaign.to_account_info(),
+        emit_logged!(AllowlistContributionEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: tier.campaign,
+            tier: tier.index,
+            contributor: contributor_key,
+            sol_amount: outcome.sol_amount,
+            token_amount: outcome.token_amount,
+            bonus_bps: tier.bonus_bps,
+        });
+
+        Ok(())
+    }
+
+    // Runs the whole contribution, publishes the outcome as return data and then
+    // fails so nothing sticks; frontends call it through simulateTransaction
+    pub fn simulate_contribute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Contribute<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let outcome = process_contribution(ctx, amount, None, None, None, &[])?;
+        set_return_data(&outcome.try_to_vec()?);
+        err!(ErrorCode::SimulationOnly)
+    }
+
+    pub fn contribute_with_signature(
+        ctx: Context<ContributeWithSignature>,
+        amount: u64,
+        nonce: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp <= expiry,
+            ErrorCode::IntentExpired
+        );
+
+        let contributor_key = ctx.accounts.contributor.key();
+        let intent = ContributionIntent {
+            campaign: ctx.accounts.campaign.key(),
+            contributor: contributor_key,
+            amount,
+            nonce,
+            expiry,
         };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-        token::mint_to(cpi_ctx, tokens_to_mint)?;
+        let mut message = CONTRIBUTION_INTENT_DOMAIN.to_vec();
+        intent.serialize(&mut message)?;
+        verify_ed25519_signature(&ctx.accounts.instructions, &contributor_key, &message)?;
 
-        // Update campaign raised amount
-        ctx.accounts.campaign.raised_amount += amount;
+        // Nonces only move forward, so a signed intent can be relayed once
+        require!(
+            nonce > ctx.accounts.contribution_record.relay_nonce,
+            ErrorCode::NonceAlreadyUsed
+        );
 
-        emit!(ContributionEvent {
-            campaign: campaign_key,
+        let accounts = ctx.accounts;
+        check_region_access(
+            &accounts.campaign,
+            accounts.region_attestation.as_ref(),
+            contributor_key,
+        )?;
+        // Whatever the hard cap leaves no room for stays in the signer's escrow
+        let amount = refund_excess(
+            &accounts.campaign,
+            None,
+            &accounts.relay_escrow.to_account_info(),
+            amount,
+        )?;
+        require!(amount > 0, ErrorCode::HardCapExceeded);
+
+        // The signer pays for the contribution, fee included, out of their relay
+        // escrow; the relayer fronts it from there and is left with only the
+        // transaction fee and rent
+        spend_relay_escrow(
+            &accounts.relay_escrow,
+            contributor_key,
+            ctx.bumps.relay_escrow,
+            &accounts.relayer.to_account_info(),
+            &accounts.system_program,
+            amount,
+        )?;
+        settle_contribution(
+            ContributionPayment {
+                campaign: &mut accounts.campaign,
+                global_config: &accounts.global_config,
+                payer: accounts.relayer.to_account_info(),
+                treasury: accounts.treasury.to_account_info(),
+                insurance_pool: accounts.insurance_pool.as_mut(),
+                frontend: accounts.frontend.as_deref(),
+                frontend_payout: accounts
+                    .frontend_payout
+                    .as_ref()
+                    .map(|payout| payout.to_account_info()),
+                matchable: true,
+                match_commitment: accounts.match_commitment.as_deref_mut(),
+                token_mint: accounts.token_mint.to_account_info(),
+                recipient_token_account: accounts.contributor_token_account.to_account_info(),
+                contribution_record: &mut accounts.contribution_record,
+                record_bump: ctx.bumps.contribution_record,
+                price_history: &mut *accounts.price_history.load_mut()?,
+                price_feed: accounts.price_feed.as_deref_mut(),
+                ticket: accounts
+                    .ticket_mint
+                    .as_ref()
+                    .zip(accounts.contributor_ticket_account.as_ref())
+                    .map(|(mint, account)| (mint.to_account_info(), account.to_account_info())),
+                token_program: accounts.token_program.to_account_info(),
+                tier_bonus_bps: None,
+                denylist_entry: accounts.denylist_entry.as_ref(),
+                governance: Some(GovernanceMint {
+                    governance_mint: accounts.governance_mint.as_ref(),
+                    governance_authority: accounts.governance_authority.as_ref(),
+                    holder_account: accounts.contributor_governance_account.as_ref(),
+                    token_2022_program: accounts.token_2022_program.as_ref(),
+                    holder: accounts.contributor.to_account_info(),
+                    payer: accounts.relayer.to_account_info(),
+                    associated_token_program: accounts.associated_token_program.to_account_info(),
+                    system_program: accounts.system_program.to_account_info(),
+                }),
+                leaderboard: accounts.leaderboard.as_ref(),
+                backer_profile: Some(&accounts.backer_profile),
+                system_program: accounts.system_program.to_account_info(),
+                reference: None,
+            },
+            contributor_key,
+            amount,
+        )?;
+        accounts.contribution_record.relay_nonce = nonce;
+
+        emit_logged!(RelayedContributionEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
             contributor: contributor_key,
-            sol_amount: amount,
-            token_amount: tokens_to_mint,
-            new_total: ctx.accounts.campaign.raised_amount,
+            relayer: accounts.relayer.key(),
+            nonce,
         });
 
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
         Ok(())
     }
 
-    pub fn withdraw_funds(ctx: Context<WithdrawFunds>, amount: u64) -> Result<()> {
-        let campaign = &mut ctx.accounts.campaign;
-        
-        require!(campaign.creator == ctx.accounts.creator.key(), ErrorCode::Unauthorized);
-        require!(amount <= campaign.raised_amount, ErrorCode::InsufficientFunds);
+    // Takes SOL back out of a relay escrow. Escrows are plain system accounts at
+    // a PDA, so anyone (an exchange withdrawal, a friend) can fund one with a
+    // transfer, but only the signer it belongs to can empty it.
+    pub fn withdraw_relay_escrow(ctx: Context<WithdrawRelayEscrow>, amount: u64) -> Result<()> {
+        validation::nonzero(amount)?;
+        let contributor_key = ctx.accounts.contributor.key();
+        spend_relay_escrow(
+            &ctx.accounts.relay_escrow,
+            contributor_key,
+            ctx.bumps.relay_escrow,
+            &ctx.accounts.contributor.to_account_info(),
+            &ctx.accounts.system_program,
+            amount,
+        )?;
+
+        emit_logged!(RelayEscrowWithdrawnEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            contributor: contributor_key,
+            amount,
+            remaining: ctx.accounts.relay_escrow.lamports(),
+        });
 
-        let campaign_lamports = campaign.to_account_info().lamports();
-        require!(amount <= campaign_lamports, ErrorCode::InsufficientFunds);
+        Ok(())
+    }
 
-        **campaign.to_account_info().try_borrow_mut_lamports()? -= amount;
-        **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += amount;
+    pub fn withdraw_funds(
+        ctx: Context<WithdrawFunds>,
+        amount: u64,
+        purpose: WithdrawalPurpose,
+        memo_hash: [u8; 32],
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
 
-        campaign.raised_amount -= amount;
+        campaign.authorize(ctx.accounts.creator.key(), ROLE_WITHDRAW)?;
+        release_withdrawal(
+            campaign,
+            &ctx.accounts.global_config,
+            ctx.accounts.denylist_entry.as_ref(),
+            ctx.accounts.withdrawal_approval.as_ref(),
+            &ctx.accounts.destination.to_account_info(),
+            amount,
+        )?;
+        record_withdrawal(
+            campaign,
+            &mut ctx.accounts.receipt,
+            ctx.bumps.receipt,
+            amount,
+            purpose,
+            memo_hash,
+        )?;
 
-        emit!(WithdrawalEvent {
+        emit_logged!(WithdrawalEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
             campaign: campaign.key(),
             creator: ctx.accounts.creator.key(),
             amount,
             remaining: campaign.raised_amount,
         });
 
+        assert_invariants(&ctx.accounts.campaign, None)?;
+
+        Ok(())
+    }
+
+    // Time-weighted price over the last `window` seconds, read from the trade
+    // history. If the history doesn't reach back that far the window is
+    // shortened to the oldest sample kept, which the event reports.
+    pub fn get_twap(ctx: Context<GetTwap>, window: i64) -> Result<u64> {
+        require!(window > 0, ErrorCode::InvalidTwapWindow);
+        let campaign = &ctx.accounts.campaign;
+        let history = ctx.accounts.price_history.load()?;
+        let now = Clock::get()?.unix_timestamp;
+        let window_start = now.saturating_sub(window);
+
+        // Anchor on the last trade at or before the window opens
+        let mut anchor: Option<&PriceSample> = None;
+        let mut oldest: Option<&PriceSample> = None;
+        for sample in history.samples.iter().take(history.count as usize) {
+            if sample.timestamp <= window_start
+                && anchor.is_none_or(|a| sample.timestamp > a.timestamp)
+            {
+                anchor = Some(sample);
+            }
+            if oldest.is_none_or(|o| sample.timestamp < o.timestamp) {
+                oldest = Some(sample);
+            }
+        }
+        // Until the buffer wraps nothing has dropped out, so launch is the fallback
+        if (history.count as usize) < PRICE_HISTORY_LEN {
+            oldest = None;
+        }
+        let (anchor_at, anchor_cumulative, anchor_price) = match anchor.or(oldest) {
+            Some(sample) => (sample.timestamp, sample.price_cumulative(), sample.price),
+            None => (
+                campaign.created_at,
+                0,
+                calculate_token_price(&campaign.curve, 0)?,
+            ),
+        };
+
+        let start = window_start.max(anchor_at);
+        let start_cumulative = anchor_cumulative
+            .checked_add(anchor_price as u128 * (start - anchor_at) as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let twap = if now > start {
+            let elapsed = (now - start) as u128;
+            u64::try_from((campaign.cumulative_price_at(now)? - start_cumulative) / elapsed)
+                .map_err(|_| ErrorCode::MathOverflow)?
+        } else {
+            campaign.last_trade_price
+        };
+
+        emit_logged!(TwapEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            twap,
+            window: now - start,
+        });
+
+        Ok(twap)
+    }
+
+    // Price histories created before the zero-copy layout are Borsh: a 36-byte
+    // header, the same samples and backers, then the bump. Moving the bump into
+    // the header and the body past its padding converts one in place; anyone
+    // can run it, and the payer covers the 3 extra bytes of rent.
+    pub fn migrate_price_history(ctx: Context<MigratePriceHistory>) -> Result<()> {
+        let info = ctx.accounts.price_history.to_account_info();
+        require!(
+            info.data_len() == LEGACY_PRICE_HISTORY_SPACE,
+            ErrorCode::PriceHistoryMigrated
+        );
+        let (campaign, samples) = {
+            let data = info.try_borrow_data()?;
+            require!(
+                data[..8] == *PriceHistory::DISCRIMINATOR,
+                ErrorCode::PriceHistoryMigrated
+            );
+            let campaign =
+                Pubkey::try_from(&data[8..40]).map_err(|_| ErrorCode::PriceHistoryMigrated)?;
+            (campaign, u16::from_le_bytes([data[42], data[43]]))
+        };
+        require_keys_eq!(
+            campaign,
+            ctx.accounts.campaign.key(),
+            ErrorCode::PriceHistoryMigrated
+        );
+
+        let space = 8 + std::mem::size_of::<PriceHistory>();
+        let top_up = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(info.lamports());
+        if top_up > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        info.resize(space)?;
+        {
+            // Discriminator, campaign, head and count already sit where the new layout has them
+            let header = 8 + 32 + 2 + 2;
+            let body = LEGACY_PRICE_HISTORY_SPACE - header - 1;
+            let mut data = info.try_borrow_mut_data()?;
+            let bump = data[header + body];
+            data.copy_within(header..header + body, space - body);
+            data[header] = bump;
+            data[header + 1..space - body].fill(0);
+        }
+
+        emit_logged!(PriceHistoryMigratedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign,
+            samples,
+        });
+
         Ok(())
     }
 
     pub fn get_token_price(ctx: Context<GetTokenPrice>) -> Result<u64> {
         let campaign = &ctx.accounts.campaign;
-        let price = calculate_token_price(campaign.raised_amount)?;
+        let price = calculate_token_price(&campaign.curve, campaign.raised_amount)?;
         
-        emit!(TokenPriceEvent {
+        emit_logged!(TokenPriceEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
             campaign: campaign.key(),
             price,
             raised_amount: campaign.raised_amount,
@@ -128,157 +1035,16954 @@ pub mod launch_fund {
 
         Ok(price)
     }
-}
 
-// Calculate tokens based on bonding curve: tokens = sqrt(sol_amount * 1000000)
-fn calculate_tokens_from_sol(sol_amount: u64, current_raised: u64) -> Result<u64> {
-    let base_tokens = (sol_amount * 1_000_000_000) / 1_000_000; // 1M tokens per SOL base rate
-    let bonus_rate = if current_raised < 10_000_000_000 { 120 } else { 100 }; // 20% bonus early
-    let tokens = (base_tokens * bonus_rate) / 100;
-    Ok(tokens)
-}
+    pub fn propose_extension(ctx: Context<ProposeExtension>, duration: i64) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let clock = Clock::get()?;
 
-// Calculate current token price in lamports
-fn calculate_token_price(raised_amount: u64) -> Result<u64> {
-    let base_price = 1000; // 0.000001 SOL base price
-    let price_multiplier = 1 + (raised_amount / 1_000_000_000); // Price increases with funding
-    Ok(base_price * price_multiplier)
-}
+        require!(
+            campaign.creator == ctx.accounts.creator.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        campaign.ensure_active()?;
+        require!(
+            clock.unix_timestamp < campaign.end_time,
+            ErrorCode::DeadlinePassed
+        );
+        require!(duration > 0, ErrorCode::InvalidExtensionDuration);
+        // The vote has to settle while the campaign is still live, or the
+        // deadline it would move has already passed
+        let voting_ends_at = clock
+            .unix_timestamp
+            .checked_add(EXTENSION_VOTING_PERIOD)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            voting_ends_at < campaign.end_time,
+            ErrorCode::ExtensionVoteTooLate
+        );
 
-#[derive(Accounts)]
-#[instruction(name: String)]
-pub struct InitializeCampaign<'info> {
-    #[account(
-        init,
-        payer = creator,
-        space = 8 + Campaign::INIT_SPACE,
-        seeds = [b"campaign", creator.key().as_ref(), name.as_bytes()],
-        bump
-    )]
-    pub campaign: Account<'info, Campaign>,
-    
-    #[account(
-        init,
-        payer = creator,
-        mint::decimals = 9,
-        mint::authority = campaign,
-    )]
-    pub token_mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>,
-}
+        let proposal = &mut ctx.accounts.extension_proposal;
+        proposal.campaign = campaign.key();
+        proposal.duration = duration;
+        proposal.voting_ends_at = voting_ends_at;
+        proposal.votes_for = 0;
+        proposal.votes_against = 0;
+        proposal.finalized = false;
+        proposal.approved = false;
+        proposal.bump = ctx.bumps.extension_proposal;
 
-#[derive(Accounts)]
-pub struct Contribute<'info> {
-    #[account(
-        mut,
-        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name.as_bytes()],
-        bump = campaign.bump
-    )]
-    pub campaign: Account<'info, Campaign>,
-    
-    #[account(mut)]
-    pub token_mint: Account<'info, Mint>,
-    
-    #[account(
-        init,
-        payer = contributor,
-        associated_token::mint = token_mint,
-        associated_token::authority = contributor
-    )]
-    pub contributor_token_account: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub contributor: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub rent: Sysvar<'info, Rent>,
-}
+        emit_logged!(ExtensionProposedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            duration,
+            voting_ends_at: proposal.voting_ends_at,
+        });
 
-#[derive(Accounts)]
-pub struct WithdrawFunds<'info> {
-    #[account(
-        mut,
-        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name.as_bytes()],
-        bump = campaign.bump
-    )]
-    pub campaign: Account<'info, Campaign>,
-    
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct GetTokenPrice<'info> {
-    #[account(
-        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name.as_bytes()],
-        bump = campaign.bump
-    )]
-    pub campaign: Account<'info, Campaign>,
-}
+    pub fn vote_extension(ctx: Context<VoteExtension>, approve: bool) -> Result<()> {
+        let proposal = &mut ctx.accounts.extension_proposal;
+        let clock = Clock::get()?;
 
-#[account]
-#[derive(InitSpace)]
-pub struct Campaign {
-    pub creator: Pubkey,
-    #[max_len(50)]
-    pub name: String,
-    #[max_len(200)]
-    pub description: String,
-    pub target_amount: u64,
-    pub raised_amount: u64,
-    #[max_len(10)]
-    pub token_symbol: String,
-    #[max_len(50)]
-    pub token_name: String,
-    pub total_supply: u64,
-    pub token_mint: Pubkey,
-    pub created_at: i64,
-    pub is_active: bool,
-    pub bump: u8,
-}
+        require!(!proposal.finalized, ErrorCode::ExtensionAlreadyFinalized);
+        require!(
+            clock.unix_timestamp < proposal.voting_ends_at,
+            ErrorCode::VotingClosed
+        );
 
-#[event]
-pub struct ContributionEvent {
-    pub campaign: Pubkey,
-    pub contributor: Pubkey,
-    pub sol_amount: u64,
-    pub token_amount: u64,
-    pub new_total: u64,
+        // Vote weight is the curve tokens the voter still holds, up to what
+        // they received from the curve. They sit in escrow until voting ends,
+        // so they can't be sold or moved to another wallet to vote again.
+        let weight = ctx
+            .accounts
+            .contribution_record
+            .tokens_received
+            .min(ctx.accounts.voter_token_account.amount);
+        require!(weight > 0, ErrorCode::NoVotingPower);
+        escrow_vote_tokens(
+            &ctx.accounts.campaign,
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.voter_token_account.to_account_info(),
+            ctx.accounts.vote_escrow.to_account_info(),
+            ctx.accounts.voter.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            weight,
+        )?;
+
+        if approve {
+            proposal.votes_for = proposal
+                .votes_for
+                .checked_add(weight)
+                .ok_or(ErrorCode::MathOverflow)?;
+        } else {
+            proposal.votes_against = proposal
+                .votes_against
+                .checked_add(weight)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        let vote = &mut ctx.accounts.extension_vote;
+        vote.proposal = proposal.key();
+        vote.voter = ctx.accounts.voter.key();
+        vote.weight = weight;
+        vote.approve = approve;
+        vote.bump = ctx.bumps.extension_vote;
+
+        emit_logged!(ExtensionVoteEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: proposal.campaign,
+            voter: vote.voter,
+            weight,
+            approve,
+        });
+
+        Ok(())
+    }
+
+    pub fn finalize_extension(ctx: Context<FinalizeExtension>) -> Result<()> {
+        let proposal = &mut ctx.accounts.extension_proposal;
+        let campaign = &mut ctx.accounts.campaign;
+
+        require!(!proposal.finalized, ErrorCode::ExtensionAlreadyFinalized);
+        campaign.ensure_active()?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= proposal.voting_ends_at, ErrorCode::VotingStillOpen);
+        require!(now < campaign.end_time, ErrorCode::DeadlinePassed);
+
+        proposal.finalized = true;
+        proposal.approved = proposal.votes_for > proposal.votes_against;
+
+        if proposal.approved {
+            campaign.end_time = campaign
+                .end_time
+                .checked_add(proposal.duration)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        emit_logged!(ExtensionFinalizedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            approved: proposal.approved,
+            votes_for: proposal.votes_for,
+            votes_against: proposal.votes_against,
+            end_time: campaign.end_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn reclaim_extension_vote(ctx: Context<ReclaimExtensionVote>) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp >= ctx.accounts.extension_proposal.voting_ends_at,
+            ErrorCode::VotingStillOpen
+        );
+
+        let vote = &ctx.accounts.extension_vote;
+        let seeds = &[
+            b"extension_vote".as_ref(),
+            vote.proposal.as_ref(),
+            vote.voter.as_ref(),
+            &[vote.bump],
+        ];
+        release_vote_tokens(
+            &ctx.accounts.campaign,
+            ctx.accounts.token_mint.to_account_info(),
+            &ctx.accounts.vote_escrow,
+            ctx.accounts.voter_token_account.to_account_info(),
+            vote.to_account_info(),
+            ctx.accounts.voter.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            &[&seeds[..]],
+        )
+    }
+
+    pub fn graduate_campaign(ctx: Context<GraduateCampaign>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+
+        require!(
+            campaign.creator == ctx.accounts.creator.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        campaign.ensure_active()?;
+        require!(
+            campaign.raised_amount >= campaign.target_amount,
+            ErrorCode::TargetNotReached
+        );
+        require!(campaign.lent_amount == 0, ErrorCode::FundsStillLent);
+        require!(
+            campaign.exit_queue_head == campaign.exit_queue_tail,
+            ErrorCode::ExitQueueNotEmpty
+        );
+
+        // SOL kept for contributors backs their redemptions once there's nothing to refund
+        if campaign.refund_buffer > 0 {
+            let buffer = campaign.refund_buffer;
+            campaign.refund_buffer = 0;
+            campaign.floor_reserve = campaign
+                .floor_reserve
+                .checked_add(buffer)
+                .ok_or(ErrorCode::MathOverflow)?;
+            campaign.raised_amount = campaign
+                .raised_amount
+                .checked_add(buffer)
+                .ok_or(ErrorCode::MathOverflow)?;
+            campaign.reserve_balance = campaign
+                .reserve_balance
+                .checked_add(buffer)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        // Tickets settle at the rate the curve offers at close
+        let mut supply = ctx.accounts.token_mint.supply;
+        if campaign.ticket_mint != Pubkey::default() {
+            let ticket_mint = ctx
+                .accounts
+                .ticket_mint
+                .as_ref()
+                .ok_or(ErrorCode::MissingTicketAccounts)?;
+            campaign.clearing_raised = campaign.raised_amount;
+            supply = supply
+                .checked_add(calculate_tokens_from_sol(
+                    &campaign.curve,
+                    ticket_mint.supply,
+                    campaign.clearing_raised,
+                )?)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        // Supply is final once the curve closes, so the floor can be fixed now
+        if campaign.floor_reserve > 0 && supply > 0 {
+            campaign.floor_price = u64::try_from(
+                (campaign.floor_reserve as u128)
+                    .checked_mul(TOKEN_PRECISION)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    / supply as u128,
+            )
+            .map_err(|_| ErrorCode::MathOverflow)?;
+        }
+
+        // The secondary curve picks up from the primary's marginal price at the close
+        if campaign.secondary_slope_bps > 0 {
+            campaign.secondary_base_price =
+                calculate_token_price(&campaign.curve, campaign.raised_amount)?;
+        }
+
+        // Only frozen-until-graduation campaigns still need to thaw holders afterwards
+        if !campaign.freeze_during_raise && ctx.accounts.token_mint.freeze_authority.is_some() {
+            let seeds = &[
+                b"campaign",
+                campaign.creator.as_ref(),
+                campaign.name_hash.as_ref(),
+                &[campaign.bump],
+            ];
+            token::set_authority(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::SetAuthority {
+                        current_authority: campaign.to_account_info(),
+                        account_or_mint: ctx.accounts.token_mint.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                AuthorityType::FreezeAccount,
+                None,
+            )?;
+        }
+
+        campaign.closing_price = calculate_token_price(&campaign.curve, campaign.raised_amount)?;
+        campaign.transition(CampaignStatus::Graduated)?;
+        campaign.graduated_at = Clock::get()?.unix_timestamp;
+        campaign.profile_settled = true;
+        let profile = &mut ctx.accounts.creator_profile;
+        profile.active_campaigns = profile.active_campaigns.saturating_sub(1);
+
+        emit_logged!(GraduationEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            raised_amount: campaign.raised_amount,
+            floor_reserve: campaign.floor_reserve,
+            floor_price: campaign.floor_price,
+        });
+
+        assert_invariants(
+            &ctx.accounts.campaign,
+            Some(ctx.accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn redeem_at_floor(ctx: Context<RedeemAtFloor>, token_amount: u64) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        require!(campaign.floor_price > 0, ErrorCode::NoPriceFloor);
+        require!(token_amount > 0, ErrorCode::ZeroAmount);
+
+        let payout = u64::try_from(
+            (token_amount as u128)
+                .checked_mul(campaign.floor_price as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / TOKEN_PRECISION,
+        )
+        .map_err(|_| ErrorCode::MathOverflow)?;
+        require!(payout > 0, ErrorCode::AmountTooSmall);
+        require!(
+            payout <= campaign.floor_reserve,
+            ErrorCode::FloorReserveExhausted
+        );
+
+        let cpi_accounts = token::Burn {
+            mint: ctx.accounts.token_mint.to_account_info(),
+            from: ctx.accounts.holder_token_account.to_account_info(),
+            authority: ctx.accounts.holder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::burn(cpi_ctx, token_amount)?;
+
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.release_supply(SupplyBucket::Curve, token_amount);
+        **campaign.to_account_info().try_borrow_mut_lamports()? -= payout;
+        **ctx
+            .accounts
+            .holder
+            .to_account_info()
+            .try_borrow_mut_lamports()? += payout;
+
+        campaign.floor_reserve -= payout;
+        campaign.raised_amount = campaign.raised_amount.saturating_sub(payout);
+        campaign.debit_balances(payout);
+
+        emit_logged!(FloorRedemptionEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            holder: ctx.accounts.holder.key(),
+            token_amount,
+            sol_amount: payout,
+            remaining_reserve: campaign.floor_reserve,
+        });
+
+        assert_invariants(
+            &ctx.accounts.campaign,
+            Some(ctx.accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn unlock_governance_tokens(ctx: Context<UnlockGovernanceTokens>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let unlocks_at = campaign
+            .end_time
+            .checked_add(campaign.governance_lockup_secs)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            Clock::get()?.unix_timestamp >= unlocks_at,
+            ErrorCode::LockupActive
+        );
+
+        let campaign_key = campaign.key();
+        let seeds = &[
+            b"governance_authority",
+            campaign_key.as_ref(),
+            &[campaign.governance_authority_bump],
+        ];
+        token_2022::thaw_account(CpiContext::new_with_signer(
+            ctx.accounts.token_2022_program.to_account_info(),
+            token_2022::ThawAccount {
+                account: ctx.accounts.holder_governance_account.to_account_info(),
+                mint: ctx.accounts.governance_mint.to_account_info(),
+                authority: ctx.accounts.governance_authority.to_account_info(),
+            },
+            &[&seeds[..]],
+        ))?;
+
+        emit_logged!(GovernanceTokensUnlockedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign_key,
+            holder_governance_account: ctx.accounts.holder_governance_account.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn sell_tokens(
+        ctx: Context<SellTokens>,
+        token_amount: u64,
+        min_sol_out: u64,
+    ) -> Result<()> {
+        let seller_key = ctx.accounts.seller.key();
+        let accounts = ctx.accounts;
+        let basis_before = accounts.contribution_record.sol_contributed;
+
+        let sol_amount = sell_to_curve(
+            CurveSale {
+                campaign: &mut accounts.campaign,
+                token_mint: accounts.token_mint.to_account_info(),
+                seller_token_account: accounts.seller_token_account.to_account_info(),
+                seller: accounts.seller.to_account_info(),
+                contribution_record: &mut accounts.contribution_record,
+                price_history: &mut *accounts.price_history.load_mut()?,
+                price_feed: accounts.price_feed.as_deref_mut(),
+                token_program: accounts.token_program.to_account_info(),
+            },
+            token_amount,
+        )?;
+        require!(sol_amount >= min_sol_out, ErrorCode::SlippageExceeded);
+
+        if accounts.campaign.governance_mint != Pubkey::default() {
+            burn_governance_tokens(
+                &accounts.campaign,
+                GovernanceBurn {
+                    governance_mint: accounts.governance_mint.as_ref(),
+                    governance_authority: accounts.governance_authority.as_ref(),
+                    holder_account: accounts.seller_governance_account.as_ref(),
+                    token_2022_program: accounts.token_2022_program.as_ref(),
+                    holder: accounts.seller.to_account_info(),
+                },
+                token_amount,
+            )?;
+        }
+
+        **accounts
+            .campaign
+            .to_account_info()
+            .try_borrow_mut_lamports()? -= sol_amount;
+        **accounts
+            .seller
+            .to_account_info()
+            .try_borrow_mut_lamports()? += sol_amount;
+
+        let basis_after = accounts.contribution_record.sol_contributed;
+        update_backer_profile(&accounts.backer_profile, |profile| {
+            profile.record_exit(
+                basis_before - basis_after,
+                sol_amount,
+                basis_before > 0 && basis_after == 0,
+            )
+        })?;
+
+        emit_logged!(SellEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            seller: seller_key,
+            token_amount,
+            sol_amount,
+            new_total: accounts.campaign.raised_amount,
+        });
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn sell_and_recontribute(
+        ctx: Context<SellTokens>,
+        token_amount: u64,
+        recontribute_bps: u16,
+        min_sol_out: u64,
+    ) -> Result<()> {
+        require!(
+            recontribute_bps > 0 && recontribute_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidRecontributeRatio
+        );
+        // The rebuy would need governance tokens minted, which this path can't do
+        require!(
+            ctx.accounts.campaign.governance_mint == Pubkey::default(),
+            ErrorCode::GovernanceUnsupported
+        );
+
+        let seller_key = ctx.accounts.seller.key();
+        let record_bump = ctx.accounts.contribution_record.bump;
+        let accounts = ctx.accounts;
+        let basis_before = accounts.contribution_record.sol_contributed;
+
+        let proceeds = sell_to_curve(
+            CurveSale {
+                campaign: &mut accounts.campaign,
+                token_mint: accounts.token_mint.to_account_info(),
+                seller_token_account: accounts.seller_token_account.to_account_info(),
+                seller: accounts.seller.to_account_info(),
+                contribution_record: &mut accounts.contribution_record,
+                price_history: &mut *accounts.price_history.load_mut()?,
+                price_feed: accounts.price_feed.as_deref_mut(),
+                token_program: accounts.token_program.to_account_info(),
+            },
+            token_amount,
+        )?;
+
+        // The recontributed share never leaves the campaign, only the rest is paid
+        // out, along with whatever of it the hard cap leaves no room for
+        let recontributed = calculate_bps_share(proceeds, recontribute_bps)?;
+        let recontributed = refund_excess(
+            &accounts.campaign,
+            None,
+            &accounts.seller.to_account_info(),
+            recontributed,
+        )?;
+        require!(recontributed > 0, ErrorCode::HardCapExceeded);
+        let sol_out = proceeds - recontributed;
+        require!(sol_out >= min_sol_out, ErrorCode::SlippageExceeded);
+        // The sale closes out basis like any other, and the recontributed share
+        // opens it again, so a full sell-through stays one active position
+        let basis_sold = accounts.contribution_record.sol_contributed;
+        update_backer_profile(&accounts.backer_profile, |profile| {
+            profile.record_exit(
+                basis_before - basis_sold,
+                proceeds,
+                basis_before > 0 && basis_sold == 0,
+            )?;
+            profile.record_contribution(false, basis_sold == 0, recontributed)
+        })?;
+
+        let tokens_minted = buy_from_curve(
+            CurvePurchase {
+                campaign: &mut accounts.campaign,
+                token_mint: accounts.token_mint.to_account_info(),
+                recipient_token_account: accounts.seller_token_account.to_account_info(),
+                contribution_record: Some(&mut accounts.contribution_record),
+                record_bump,
+                price_history: &mut *accounts.price_history.load_mut()?,
+                price_feed: accounts.price_feed.as_deref_mut(),
+                ticket: None,
+                token_program: accounts.token_program.to_account_info(),
+                fee_paid: 0,
+                from_queue: false,
+                tier_bonus_bps: None,
+                sponsored: 0,
+                global_config: &accounts.global_config,
+                denylist_entry: accounts.denylist_entry.as_ref(),
+            },
+            seller_key,
+            recontributed,
+        )?;
+
+        if sol_out > 0 {
+            **accounts
+                .campaign
+                .to_account_info()
+                .try_borrow_mut_lamports()? -= sol_out;
+            **accounts
+                .seller
+                .to_account_info()
+                .try_borrow_mut_lamports()? += sol_out;
+        }
+
+        emit_logged!(SellEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            seller: seller_key,
+            token_amount,
+            sol_amount: proceeds,
+            new_total: accounts.campaign.raised_amount - recontributed,
+        });
+        emit_logged!(ContributionEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            contributor: seller_key,
+            sol_amount: recontributed,
+            token_amount: tokens_minted,
+            new_total: accounts.campaign.raised_amount,
+            fee_amount: 0,
+            reference: None,
+        });
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn initialize_global_config(
+        ctx: Context<InitializeGlobalConfig>,
+        treasury: Pubkey,
+        fee_tiers: Vec<FeeTier>,
+        frontend_fee_share_bps: u16,
+        limits: CampaignLimits,
+        enforce_unique_symbols: bool,
+    ) -> Result<()> {
+        require!(
+            frontend_fee_share_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidFeeShare
+        );
+        limits.validate()?;
+
+        let config = &mut ctx.accounts.global_config;
+        config.admin = ctx.accounts.admin.key();
+        config.treasury = treasury;
+        config.set_fee_tiers(&fee_tiers)?;
+        config.frontend_fee_share_bps = frontend_fee_share_bps;
+        config.limits = limits;
+        config.enforce_unique_symbols = enforce_unique_symbols;
+        config.bump = ctx.bumps.global_config;
+
+        emit_config_updated(ctx.accounts.admin.key(), "initialize");
+
+        Ok(())
+    }
+
+    pub fn update_global_config(
+        ctx: Context<UpdateGlobalConfig>,
+        treasury: Pubkey,
+        fee_tiers: Vec<FeeTier>,
+        frontend_fee_share_bps: u16,
+        limits: CampaignLimits,
+        enforce_unique_symbols: bool,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+        require!(
+            frontend_fee_share_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidFeeShare
+        );
+        limits.validate()?;
+
+        config.treasury = treasury;
+        config.set_fee_tiers(&fee_tiers)?;
+        config.frontend_fee_share_bps = frontend_fee_share_bps;
+        config.limits = limits;
+        config.enforce_unique_symbols = enforce_unique_symbols;
+
+        emit_config_updated(ctx.accounts.admin.key(), "fees_and_limits");
+
+        Ok(())
+    }
+
+    pub fn register_frontend(
+        ctx: Context<RegisterFrontend>,
+        domain_hash: [u8; 32],
+        payout: Pubkey,
+    ) -> Result<()> {
+        let frontend = &mut ctx.accounts.frontend;
+        frontend.authority = ctx.accounts.authority.key();
+        frontend.domain_hash = domain_hash;
+        frontend.payout = payout;
+        frontend.bump = ctx.bumps.frontend;
+
+        emit_logged!(FrontendRegisteredEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            frontend: frontend.key(),
+            authority: frontend.authority,
+            domain_hash,
+            payout,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_frontend_payout(ctx: Context<UpdateFrontend>, payout: Pubkey) -> Result<()> {
+        let frontend = &mut ctx.accounts.frontend;
+
+        require!(
+            frontend.authority == ctx.accounts.authority.key(),
+            ErrorCode::Unauthorized
+        );
+        frontend.payout = payout;
+
+        emit_logged!(FrontendPayoutUpdatedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            frontend: frontend.key(),
+            payout,
+        });
+
+        Ok(())
+    }
+
+    pub fn thaw(ctx: Context<ThawHolder>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        require!(campaign.freeze_during_raise, ErrorCode::FreezeNotEnabled);
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+
+        set_token_account_frozen(
+            campaign,
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.holder_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            false,
+        )?;
+
+        emit_logged!(ThawEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            accounts_thawed: 1,
+        });
+
+        Ok(())
+    }
+
+    // Thaw every token account passed as a remaining account
+    pub fn thaw_all<'info>(ctx: Context<'_, '_, 'info, 'info, ThawAll<'info>>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        require!(campaign.freeze_during_raise, ErrorCode::FreezeNotEnabled);
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+
+        for holder_account in ctx.remaining_accounts {
+            require_keys_eq!(
+                *holder_account.owner,
+                token::ID,
+                ErrorCode::InvalidTokenAccount
+            );
+            set_token_account_frozen(
+                campaign,
+                ctx.accounts.token_mint.to_account_info(),
+                holder_account.clone(),
+                ctx.accounts.token_program.to_account_info(),
+                false,
+            )?;
+        }
+
+        emit_logged!(ThawEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            accounts_thawed: ctx.remaining_accounts.len() as u32,
+        });
+
+        Ok(())
+    }
+
+    pub fn redeem_ticket(ctx: Context<RedeemTicket>, ticket_amount: u64) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        require!(ticket_amount > 0, ErrorCode::ZeroAmount);
+
+        // Every ticket converts at the same clearing rate fixed at graduation
+        let token_amount =
+            calculate_tokens_from_sol(&campaign.curve, ticket_amount, campaign.clearing_raised)?;
+        require!(token_amount > 0, ErrorCode::AmountTooSmall);
+        ctx.accounts
+            .campaign
+            .draw_supply(SupplyBucket::Curve, token_amount)?;
+        let campaign = &ctx.accounts.campaign;
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Burn {
+                    mint: ctx.accounts.ticket_mint.to_account_info(),
+                    from: ctx.accounts.holder_ticket_account.to_account_info(),
+                    authority: ctx.accounts.holder.to_account_info(),
+                },
+            ),
+            ticket_amount,
+        )?;
+
+        let seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.holder_token_account.to_account_info(),
+                    authority: campaign.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            token_amount,
+        )?;
+
+        emit_logged!(TicketRedeemedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            holder: ctx.accounts.holder.key(),
+            ticket_amount,
+            token_amount,
+        });
+
+        assert_invariants(
+            &ctx.accounts.campaign,
+            Some(ctx.accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    // An exchange that took in its users' claim tickets redeems them as one
+    // batch into its omnibus account. The per-user split is committed as a
+    // merkle root (leaves are hash(user, token_amount), paired like airdrop
+    // proofs) so each user can check their credit against the chain.
+    pub fn claim_for_exchange(
+        ctx: Context<ClaimForExchange>,
+        batch_id: u64,
+        deposits: Vec<ExchangeDeposit>,
+    ) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        require!(!deposits.is_empty(), ErrorCode::ZeroAmount);
+
+        let mut ticket_amount: u64 = 0;
+        let mut leaves = Vec::with_capacity(deposits.len());
+        for deposit in &deposits {
+            require!(deposit.ticket_amount > 0, ErrorCode::ZeroAmount);
+            ticket_amount = ticket_amount
+                .checked_add(deposit.ticket_amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+            let user_tokens = calculate_tokens_from_sol(
+                &campaign.curve,
+                deposit.ticket_amount,
+                campaign.clearing_raised,
+            )?;
+            leaves.push(hashv(&[deposit.user.as_ref(), &user_tokens.to_le_bytes()]).to_bytes());
+        }
+        // Converting the batch as a whole rounds at most once, so the omnibus
+        // never receives less than the sum of the users' shares
+        let token_amount =
+            calculate_tokens_from_sol(&campaign.curve, ticket_amount, campaign.clearing_raised)?;
+        require!(token_amount > 0, ErrorCode::AmountTooSmall);
+        ctx.accounts
+            .campaign
+            .draw_supply(SupplyBucket::Curve, token_amount)?;
+        let campaign = &ctx.accounts.campaign;
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Burn {
+                    mint: ctx.accounts.ticket_mint.to_account_info(),
+                    from: ctx.accounts.exchange_ticket_account.to_account_info(),
+                    authority: ctx.accounts.exchange.to_account_info(),
+                },
+            ),
+            ticket_amount,
+        )?;
+
+        let seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.omnibus_token_account.to_account_info(),
+                    authority: campaign.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            token_amount,
+        )?;
+
+        let claim = &mut ctx.accounts.exchange_claim;
+        claim.campaign = campaign.key();
+        claim.exchange = ctx.accounts.exchange.key();
+        claim.batch_id = batch_id;
+        claim.claims_root = merkle_root(leaves);
+        claim.deposit_count = deposits.len() as u32;
+        claim.ticket_amount = ticket_amount;
+        claim.token_amount = token_amount;
+        claim.bump = ctx.bumps.exchange_claim;
+
+        emit_logged!(ExchangeClaimEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: claim.campaign,
+            exchange: claim.exchange,
+            batch_id,
+            claims_root: claim.claims_root,
+            deposit_count: claim.deposit_count,
+            ticket_amount,
+            token_amount,
+        });
+
+        assert_invariants(
+            &ctx.accounts.campaign,
+            Some(ctx.accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn refund(ctx: Context<Refund>, amount: u64) -> Result<()> {
+        let accounts = ctx.accounts;
+        let (refund, payout) = burn_for_refund(
+            RefundBurn {
+                campaign: &mut accounts.campaign,
+                record: &mut accounts.contribution_record,
+                mint: accounts.mint.to_account_info(),
+                contributor_token_account: accounts.contributor_token_account.to_account_info(),
+                contributor: accounts.contributor.to_account_info(),
+                destination: accounts.contributor.to_account_info(),
+                token_program: accounts.token_program.to_account_info(),
+            },
+            amount,
+        )?;
+        // Governance tokens were minted 1:1 with the tokens or tickets burned
+        if accounts.campaign.governance_mint != Pubkey::default() {
+            burn_governance_tokens(
+                &accounts.campaign,
+                GovernanceBurn {
+                    governance_mint: accounts.governance_mint.as_ref(),
+                    governance_authority: accounts.governance_authority.as_ref(),
+                    holder_account: accounts.contributor_governance_account.as_ref(),
+                    token_2022_program: accounts.token_2022_program.as_ref(),
+                    holder: accounts.contributor.to_account_info(),
+                },
+                amount,
+            )?;
+        }
+        let closes_position = accounts.contribution_record.sol_contributed == 0;
+        update_backer_profile(&accounts.backer_profile, |profile| {
+            profile.record_exit(refund, payout, closes_position)
+        })?;
+
+        emit_logged!(RefundEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            contributor: accounts.contributor.key(),
+            burned: amount,
+            sol_amount: payout,
+        });
+
+        assert_invariants(&accounts.campaign, None)?;
+
+        Ok(())
+    }
+
+    pub fn configure_airdrop(
+        ctx: Context<ConfigureAirdrop>,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+    ) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        require!(
+            campaign.creator == ctx.accounts.creator.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        campaign.ensure_active()?;
+        require!(
+            total_amount > 0 && total_amount <= campaign.unminted(SupplyBucket::Airdrop),
+            ErrorCode::AirdropExceedsSupply
+        );
+        require!(merkle_root != [0; 32], ErrorCode::InvalidMerkleProof);
+
+        let airdrop = &mut ctx.accounts.airdrop;
+        airdrop.campaign = campaign.key();
+        airdrop.source_mint = ctx.accounts.source_mint.key();
+        airdrop.merkle_root = merkle_root;
+        airdrop.total_amount = total_amount;
+        airdrop.claimed_amount = 0;
+        airdrop.bump = ctx.bumps.airdrop;
+
+        emit_logged!(AirdropConfiguredEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            source_mint: airdrop.source_mint,
+            merkle_root,
+            total_amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let airdrop = &mut ctx.accounts.airdrop;
+
+        // Airdropped tokens were never paid for, so they only arrive once the
+        // curve has stopped buying tokens back
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        require!(amount > 0, ErrorCode::ZeroAmount);
+
+        // Leaves commit to the claimant and their snapshot allocation
+        let claimant = ctx.accounts.claimant.key();
+        let leaf = hashv(&[claimant.as_ref(), &amount.to_le_bytes()]).to_bytes();
+        require!(
+            verify_merkle_proof(&proof, airdrop.merkle_root, leaf),
+            ErrorCode::InvalidMerkleProof
+        );
+
+        airdrop.claimed_amount = airdrop
+            .claimed_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            airdrop.claimed_amount <= airdrop.total_amount,
+            ErrorCode::AirdropExhausted
+        );
+        ctx.accounts
+            .campaign
+            .draw_supply(SupplyBucket::Airdrop, amount)?;
+        let campaign = &ctx.accounts.campaign;
+
+        let receipt = &mut ctx.accounts.claim_receipt;
+        receipt.airdrop = airdrop.key();
+        receipt.claimant = claimant;
+        receipt.amount = amount;
+        receipt.bump = ctx.bumps.claim_receipt;
+
+        if campaign.freeze_during_raise {
+            set_token_account_frozen(
+                campaign,
+                ctx.accounts.token_mint.to_account_info(),
+                ctx.accounts.claimant_token_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                false,
+            )?;
+        }
+
+        let seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.claimant_token_account.to_account_info(),
+                    authority: campaign.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        emit_logged!(AirdropClaimedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            claimant,
+            amount,
+            remaining: airdrop.total_amount - airdrop.claimed_amount,
+        });
+
+        assert_invariants(
+            &ctx.accounts.campaign,
+            Some(ctx.accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn lock_liquidity(
+        ctx: Context<LockLiquidity>,
+        amount: u64,
+        lock_duration: i64,
+    ) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        require!(
+            campaign.creator == ctx.accounts.creator.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        require!(lock_duration > 0, ErrorCode::InvalidLockup);
+
+        // LP tokens are escrowed under the lock PDA so anyone can verify them on-chain
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.creator_lp_account.to_account_info(),
+                    to: ctx.accounts.lp_vault.to_account_info(),
+                    authority: ctx.accounts.creator.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let lock = &mut ctx.accounts.liquidity_lock;
+        lock.campaign = campaign.key();
+        lock.lp_mint = ctx.accounts.lp_mint.key();
+        lock.lp_vault = ctx.accounts.lp_vault.key();
+        lock.amount = amount;
+        lock.lock_duration = lock_duration;
+        lock.locked_at = now;
+        lock.unlocks_at = now
+            .checked_add(lock_duration)
+            .ok_or(ErrorCode::MathOverflow)?;
+        lock.votes_for = 0;
+        lock.votes_against = 0;
+        lock.unlocked = false;
+        lock.bump = ctx.bumps.liquidity_lock;
+
+        emit_logged!(LiquidityLockedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            lp_mint: lock.lp_mint,
+            lp_vault: lock.lp_vault,
+            amount,
+            unlocks_at: lock.unlocks_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn vote_liquidity_unlock(ctx: Context<VoteLiquidityUnlock>, approve: bool) -> Result<()> {
+        let lock = &mut ctx.accounts.liquidity_lock;
+
+        require!(!lock.unlocked, ErrorCode::LiquidityUnlocked);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= lock.unlocks_at, ErrorCode::VotingNotOpen);
+        require!(
+            now < lock.unlocks_at.saturating_add(LIQUIDITY_UNLOCK_VOTE_SECS),
+            ErrorCode::VotingClosed
+        );
+
+        // Weight is the voter's tokens, up to what the curve gave them, held in
+        // escrow until the vote is over so they can't be sold or passed on
+        let weight = ctx
+            .accounts
+            .contribution_record
+            .tokens_received
+            .min(ctx.accounts.voter_token_account.amount);
+        require!(weight > 0, ErrorCode::NoVotingPower);
+        escrow_vote_tokens(
+            &ctx.accounts.campaign,
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.voter_token_account.to_account_info(),
+            ctx.accounts.vote_escrow.to_account_info(),
+            ctx.accounts.voter.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            weight,
+        )?;
+
+        if approve {
+            lock.votes_for = lock
+                .votes_for
+                .checked_add(weight)
+                .ok_or(ErrorCode::MathOverflow)?;
+        } else {
+            lock.votes_against = lock
+                .votes_against
+                .checked_add(weight)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        let vote = &mut ctx.accounts.unlock_vote;
+        vote.lock = lock.key();
+        vote.voter = ctx.accounts.voter.key();
+        vote.weight = weight;
+        vote.approve = approve;
+        vote.bump = ctx.bumps.unlock_vote;
+
+        emit_logged!(LiquidityUnlockVoteEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: lock.campaign,
+            voter: vote.voter,
+            weight,
+            approve,
+        });
+
+        Ok(())
+    }
+
+    pub fn unlock_liquidity(ctx: Context<UnlockLiquidity>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let lock = &mut ctx.accounts.liquidity_lock;
+
+        require!(
+            campaign.creator == ctx.accounts.creator.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        require!(!lock.unlocked, ErrorCode::LiquidityUnlocked);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= lock.unlocks_at, ErrorCode::LockupActive);
+        // Holders must also have approved releasing the liquidity. If nobody
+        // has voted either way by the end of the voting window, there's no
+        // objection and the LP isn't held forever.
+        let unopposed = lock.votes_for == 0
+            && lock.votes_against == 0
+            && now >= lock.unlocks_at.saturating_add(LIQUIDITY_UNLOCK_VOTE_SECS);
+        require!(
+            lock.votes_for > lock.votes_against || unopposed,
+            ErrorCode::UnlockNotApproved
+        );
+
+        let campaign_key = campaign.key();
+        let seeds = &[b"liquidity_lock", campaign_key.as_ref(), &[lock.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.lp_vault.to_account_info(),
+                    to: ctx.accounts.creator_lp_account.to_account_info(),
+                    authority: lock.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            ctx.accounts.lp_vault.amount,
+        )?;
+
+        lock.unlocked = true;
+
+        emit_logged!(LiquidityUnlockedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign_key,
+            amount: ctx.accounts.lp_vault.amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn reclaim_unlock_vote(ctx: Context<ReclaimUnlockVote>) -> Result<()> {
+        let lock = &ctx.accounts.liquidity_lock;
+        let voting_ends_at = lock.unlocks_at.saturating_add(LIQUIDITY_UNLOCK_VOTE_SECS);
+        require!(
+            lock.unlocked || Clock::get()?.unix_timestamp >= voting_ends_at,
+            ErrorCode::VotingStillOpen
+        );
+
+        let vote = &ctx.accounts.unlock_vote;
+        let seeds = &[
+            b"liquidity_vote".as_ref(),
+            vote.lock.as_ref(),
+            vote.voter.as_ref(),
+            &[vote.bump],
+        ];
+        release_vote_tokens(
+            &ctx.accounts.campaign,
+            ctx.accounts.token_mint.to_account_info(),
+            &ctx.accounts.vote_escrow,
+            ctx.accounts.voter_token_account.to_account_info(),
+            vote.to_account_info(),
+            ctx.accounts.voter.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            &[&seeds[..]],
+        )
+    }
+
+    pub fn register_match(
+        ctx: Context<RegisterMatch>,
+        ratio_bps: u16,
+        cap: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+
+        campaign.ensure_active()?;
+        require!(
+            ratio_bps > 0 && ratio_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidMatchTerms
+        );
+        require!(cap > 0, ErrorCode::InvalidMatchTerms);
+        require!(
+            expiry > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidExpiry
+        );
+
+        // The whole cap is escrowed up front so matches can't bounce
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.sponsor.to_account_info(),
+                    to: ctx.accounts.match_commitment.to_account_info(),
+                },
+            ),
+            cap,
+        )?;
+
+        let commitment = &mut ctx.accounts.match_commitment;
+        commitment.campaign = campaign.key();
+        commitment.sponsor = ctx.accounts.sponsor.key();
+        commitment.ratio_bps = ratio_bps;
+        commitment.cap = cap;
+        commitment.matched = 0;
+        commitment.basis = 0;
+        commitment.expiry = expiry;
+        commitment.bump = ctx.bumps.match_commitment;
+        campaign.match_commitment = commitment.key();
+
+        emit_logged!(MatchRegisteredEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            sponsor: commitment.sponsor,
+            ratio_bps,
+            cap,
+            expiry,
+        });
+
+        Ok(())
+    }
+
+    // Ends a match and hands the undrawn escrow back. What was drawn stays on
+    // the commitment as the sponsor's claim if the campaign fails, so the
+    // account is only closed once nothing can ever be refunded.
+    pub fn close_match(ctx: Context<CloseMatch>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        let commitment = &mut ctx.accounts.match_commitment;
+
+        require!(
+            Clock::get()?.unix_timestamp >= commitment.expiry
+                || campaign.status != CampaignStatus::Active,
+            ErrorCode::MatchStillActive
+        );
+
+        campaign.match_commitment = Pubkey::default();
+        if commitment.basis == 0 || campaign.status == CampaignStatus::Graduated {
+            commitment.close(ctx.accounts.sponsor.to_account_info())?;
+        } else {
+            let undrawn = commitment.cap - commitment.matched;
+            commitment.cap = commitment.matched;
+            **commitment.to_account_info().try_borrow_mut_lamports()? -= undrawn;
+            **ctx
+                .accounts
+                .sponsor
+                .to_account_info()
+                .try_borrow_mut_lamports()? += undrawn;
+        }
+
+        emit_logged!(MatchClosedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            sponsor: commitment.sponsor,
+            matched: commitment.matched,
+        });
+
+        Ok(())
+    }
+
+    // A failed campaign returns the sponsor's matched SOL to the sponsor. The
+    // backers' records never included it, so it isn't refunded twice.
+    pub fn refund_match(ctx: Context<RefundMatch>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        let commitment = &ctx.accounts.match_commitment;
+
+        open_refunds(campaign)?;
+        let refund = commitment.basis.min(campaign.raised_amount);
+        require!(refund > 0, ErrorCode::NothingToRefund);
+
+        let campaign_info = campaign.to_account_info();
+        let available = campaign_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(campaign_info.data_len()));
+        require!(refund <= available, ErrorCode::InsufficientCampaignBalance);
+
+        **campaign.to_account_info().try_borrow_mut_lamports()? -= refund;
+        **ctx
+            .accounts
+            .sponsor
+            .to_account_info()
+            .try_borrow_mut_lamports()? += refund;
+        campaign.raised_amount -= refund;
+        campaign.debit_balances(refund);
+        let reserve_share = calculate_bps_share(refund, campaign.floor_reserve_bps)?;
+        campaign.floor_reserve = campaign.floor_reserve.saturating_sub(reserve_share);
+        campaign.match_commitment = Pubkey::default();
+
+        emit_logged!(MatchRefundedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            sponsor: commitment.sponsor,
+            amount: refund,
+        });
+
+        assert_invariants(&ctx.accounts.campaign, None)?;
+
+        Ok(())
+    }
+
+    pub fn initialize_leaderboard(ctx: Context<InitializeLeaderboard>) -> Result<()> {
+        let mut leaderboard = ctx.accounts.leaderboard.load_init()?;
+        leaderboard.by_raised = [LeaderboardEntry::default(); LEADERBOARD_LEN];
+        leaderboard.by_contributors = [LeaderboardEntry::default(); LEADERBOARD_LEN];
+        leaderboard.bump = ctx.bumps.leaderboard;
+
+        emit_logged!(LeaderboardInitializedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            leaderboard: ctx.accounts.leaderboard.key(),
+            payer: ctx.accounts.payer.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn release_symbol(ctx: Context<ReleaseSymbol>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        // Anyone can free the symbol once its campaign is over; the admin can
+        // free it at any time to settle disputes
+        let is_admin = ctx.accounts.authority.key() == ctx.accounts.global_config.admin;
+        require!(
+            is_admin
+                || matches!(
+                    campaign.status,
+                    CampaignStatus::Graduated | CampaignStatus::Failed
+                )
+                || campaign.has_failed(Clock::get()?.unix_timestamp),
+            ErrorCode::CampaignStillActive
+        );
+
+        emit_logged!(SymbolReleasedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            symbol: campaign.token_symbol.clone(),
+        });
+
+        Ok(())
+    }
+
+    pub fn treasury_buy(ctx: Context<TreasuryBuy>, amount: u64) -> Result<()> {
+        let creator_key = ctx.accounts.creator.key();
+        let accounts = ctx.accounts;
+
+        require!(
+            accounts.campaign.creator == creator_key,
+            ErrorCode::NotCampaignCreator
+        );
+        // Whatever the hard cap leaves no room for stays with the creator
+        let amount = refund_excess(
+            &accounts.campaign,
+            None,
+            &accounts.creator.to_account_info(),
+            amount,
+        )?;
+        require!(amount > 0, ErrorCode::HardCapExceeded);
+        // Only SOL the creator has already withdrawn can be cycled back in
+        let bought = accounts
+            .campaign
+            .treasury_bought
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            bought <= accounts.campaign.withdrawn_amount,
+            ErrorCode::TreasuryBuyExceedsWithdrawn
+        );
+        accounts.campaign.treasury_bought = bought;
+
+        // The creator is cycling their own proceeds back in, so no sponsor
+        // matches it and it carries no governance weight or backing
+        let outcome = settle_contribution(
+            ContributionPayment {
+                campaign: &mut accounts.campaign,
+                global_config: &accounts.global_config,
+                payer: accounts.creator.to_account_info(),
+                treasury: accounts.treasury.to_account_info(),
+                insurance_pool: accounts.insurance_pool.as_mut(),
+                frontend: None,
+                frontend_payout: None,
+                matchable: false,
+                match_commitment: None,
+                token_mint: accounts.token_mint.to_account_info(),
+                recipient_token_account: accounts.creator_token_account.to_account_info(),
+                contribution_record: &mut accounts.treasury_record,
+                record_bump: ctx.bumps.treasury_record,
+                price_history: &mut *accounts.price_history.load_mut()?,
+                price_feed: accounts.price_feed.as_deref_mut(),
+                ticket: accounts
+                    .ticket_mint
+                    .as_ref()
+                    .zip(accounts.creator_ticket_account.as_ref())
+                    .map(|(mint, account)| (mint.to_account_info(), account.to_account_info())),
+                token_program: accounts.token_program.to_account_info(),
+                tier_bonus_bps: None,
+                denylist_entry: accounts.denylist_entry.as_ref(),
+                governance: None,
+                leaderboard: accounts.leaderboard.as_ref(),
+                backer_profile: None,
+                system_program: accounts.system_program.to_account_info(),
+                reference: None,
+            },
+            creator_key,
+            amount,
+        )?;
+
+        emit_logged!(TreasuryBuyEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            creator: creator_key,
+            sol_amount: outcome.sol_amount,
+            token_amount: outcome.token_amount,
+            new_total: outcome.new_total,
+            fee_amount: outcome.fee_amount,
+        });
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn initialize_wormhole_config(
+        ctx: Context<InitializeWormholeConfig>,
+        core_bridge: Pubkey,
+        token_bridge: Pubkey,
+        emitter_chain: u16,
+        emitter_address: [u8; 32],
+        lamports_per_usdc: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+        require!(lamports_per_usdc > 0, ErrorCode::InvalidRate);
+
+        let config = &mut ctx.accounts.wormhole_config;
+        config.core_bridge = core_bridge;
+        config.token_bridge = token_bridge;
+        config.emitter_chain = emitter_chain;
+        config.emitter_address = emitter_address;
+        config.usdc_mint = ctx.accounts.usdc_mint.key();
+        config.custody = ctx.accounts.custody.key();
+        config.lamports_per_usdc = lamports_per_usdc;
+        config.bump = ctx.bumps.wormhole_config;
+
+        emit_config_updated(ctx.accounts.admin.key(), "wormhole");
+
+        Ok(())
+    }
+
+    // Redeem a token bridge transfer of USDC sent by the campaign's EVM sender
+    // and credit its SOL value to the beneficiary. The relayer fronts the SOL and
+    // is repaid exactly the USDC this redemption delivered into custody.
+    pub fn contribute_from_wormhole(ctx: Context<ContributeFromWormhole>) -> Result<()> {
+        let accounts = ctx.accounts;
+        let message = parse_posted_vaa(&accounts.posted_vaa, &accounts.wormhole_config)?;
+
+        require_keys_eq!(
+            message.campaign,
+            accounts.campaign.key(),
+            ErrorCode::InvalidWormholeMessage
+        );
+        require_keys_eq!(
+            message.beneficiary,
+            accounts.beneficiary.key(),
+            ErrorCode::InvalidWormholeMessage
+        );
+        check_region_access(
+            &accounts.campaign,
+            accounts.region_attestation.as_ref(),
+            message.beneficiary,
+        )?;
+
+        let received = &mut accounts.received;
+        received.emitter_chain = message.emitter_chain;
+        received.sequence = message.sequence;
+        received.bump = ctx.bumps.received;
+
+        // The bridge mints the transfer into custody, with the config as redeemer,
+        // and refuses a VAA it has already redeemed
+        let custody_before = accounts.custody.amount;
+        redeem_wrapped_transfer(accounts)?;
+        accounts.custody.reload()?;
+        let usdc_amount = accounts.custody.amount.saturating_sub(custody_before);
+        require!(usdc_amount > 0, ErrorCode::InvalidWormholeMessage);
+
+        let sol_amount = u64::try_from(
+            (usdc_amount as u128)
+                .checked_mul(accounts.wormhole_config.lamports_per_usdc as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / 10u128.pow(accounts.usdc_mint.decimals as u32),
+        )
+        .map_err(|_| ErrorCode::MathOverflow)?;
+        require!(sol_amount > 0, ErrorCode::AmountTooSmall);
+
+        // The relayer only fronts SOL for what fits under the hard cap and is
+        // repaid that share of the USDC; the rest goes on to the beneficiary
+        let filled = refund_excess(
+            &accounts.campaign,
+            None,
+            &accounts.beneficiary.to_account_info(),
+            sol_amount,
+        )?;
+        require!(filled > 0, ErrorCode::HardCapExceeded);
+        let relayer_usdc = u64::try_from(usdc_amount as u128 * filled as u128 / sol_amount as u128)
+            .map_err(|_| ErrorCode::MathOverflow)?;
+        let sol_amount = filled;
+
+        let config_seeds = &[
+            b"wormhole_config".as_ref(),
+            &[accounts.wormhole_config.bump],
+        ];
+        token::transfer(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: accounts.custody.to_account_info(),
+                    to: accounts.relayer_usdc_account.to_account_info(),
+                    authority: accounts.wormhole_config.to_account_info(),
+                },
+                &[&config_seeds[..]],
+            ),
+            relayer_usdc,
+        )?;
+        if relayer_usdc < usdc_amount {
+            let beneficiary_usdc_account = accounts
+                .beneficiary_usdc_account
+                .as_ref()
+                .ok_or(ErrorCode::InvalidTokenAccount)?;
+            token::transfer(
+                CpiContext::new_with_signer(
+                    accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: accounts.custody.to_account_info(),
+                        to: beneficiary_usdc_account.to_account_info(),
+                        authority: accounts.wormhole_config.to_account_info(),
+                    },
+                    &[&config_seeds[..]],
+                ),
+                usdc_amount - relayer_usdc,
+            )?;
+        }
+
+        let outcome = settle_contribution(
+            ContributionPayment {
+                campaign: &mut accounts.campaign,
+                global_config: &accounts.global_config,
+                payer: accounts.relayer.to_account_info(),
+                treasury: accounts.treasury.to_account_info(),
+                insurance_pool: accounts.insurance_pool.as_mut(),
+                frontend: None,
+                frontend_payout: None,
+                matchable: true,
+                match_commitment: accounts.match_commitment.as_deref_mut(),
+                token_mint: accounts.token_mint.to_account_info(),
+                recipient_token_account: accounts.beneficiary_token_account.to_account_info(),
+                contribution_record: &mut accounts.contribution_record,
+                record_bump: ctx.bumps.contribution_record,
+                price_history: &mut *accounts.price_history.load_mut()?,
+                price_feed: accounts.price_feed.as_deref_mut(),
+                ticket: accounts
+                    .ticket_mint
+                    .as_ref()
+                    .zip(accounts.beneficiary_ticket_account.as_ref())
+                    .map(|(mint, account)| (mint.to_account_info(), account.to_account_info())),
+                token_program: accounts.token_program.to_account_info(),
+                tier_bonus_bps: None,
+                denylist_entry: accounts.denylist_entry.as_ref(),
+                governance: Some(GovernanceMint {
+                    governance_mint: accounts.governance_mint.as_ref(),
+                    governance_authority: accounts.governance_authority.as_ref(),
+                    holder_account: accounts.beneficiary_governance_account.as_ref(),
+                    token_2022_program: accounts.token_2022_program.as_ref(),
+                    holder: accounts.beneficiary.to_account_info(),
+                    payer: accounts.relayer.to_account_info(),
+                    associated_token_program: accounts.associated_token_program.to_account_info(),
+                    system_program: accounts.system_program.to_account_info(),
+                }),
+                leaderboard: accounts.leaderboard.as_ref(),
+                backer_profile: Some(&accounts.backer_profile),
+                system_program: accounts.system_program.to_account_info(),
+                reference: None,
+            },
+            message.beneficiary,
+            sol_amount,
+        )?;
+
+        emit_logged!(WormholeContributionEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            beneficiary: message.beneficiary,
+            emitter_chain: message.emitter_chain,
+            sequence: message.sequence,
+            usdc_amount,
+            sol_amount: outcome.sol_amount,
+            token_amount: outcome.token_amount,
+            fee_amount: outcome.fee_amount,
+        });
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn configure_rebate_pool(
+        ctx: Context<ConfigureRebatePool>,
+        min_holding_secs: i64,
+        tokens_per_sol: u64,
+        amount: u64,
+    ) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        require!(
+            campaign.creator == ctx.accounts.creator.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        require!(min_holding_secs >= 0, ErrorCode::InvalidLockup);
+        require!(tokens_per_sol > 0, ErrorCode::InvalidRate);
+
+        // Anyone can top the vault up later with a plain token transfer
+        if amount > 0 {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.creator_token_account.to_account_info(),
+                        to: ctx.accounts.rebate_vault.to_account_info(),
+                        authority: ctx.accounts.creator.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+
+        let pool = &mut ctx.accounts.rebate_pool;
+        pool.campaign = campaign.key();
+        pool.vault = ctx.accounts.rebate_vault.key();
+        pool.min_holding_secs = min_holding_secs;
+        pool.tokens_per_sol = tokens_per_sol;
+        pool.distributed = 0;
+        pool.bump = ctx.bumps.rebate_pool;
+
+        emit_logged!(RebatePoolConfiguredEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: pool.campaign,
+            min_holding_secs,
+            tokens_per_sol,
+            deposited: amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim_loyalty_rebate(ctx: Context<ClaimLoyaltyRebate>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let pool = &mut ctx.accounts.rebate_pool;
+        let record = &mut ctx.accounts.contribution_record;
+
+        let held_for = Clock::get()?.unix_timestamp - record.holding_since;
+        require!(
+            held_for >= pool.min_holding_secs,
+            ErrorCode::HoldingPeriodNotMet
+        );
+
+        let unrebated = record.fees_paid - record.rebated_fees;
+        let rebate = u64::try_from(
+            (unrebated as u128)
+                .checked_mul(pool.tokens_per_sol as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / 1_000_000_000,
+        )
+        .map_err(|_| ErrorCode::MathOverflow)?;
+        require!(rebate > 0, ErrorCode::NothingToRebate);
+        require!(
+            rebate <= ctx.accounts.rebate_vault.amount,
+            ErrorCode::RebateVaultExhausted
+        );
+
+        if campaign.freeze_during_raise {
+            set_token_account_frozen(
+                campaign,
+                ctx.accounts.token_mint.to_account_info(),
+                ctx.accounts.holder_token_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                false,
+            )?;
+        }
+
+        let campaign_key = campaign.key();
+        let seeds = &[b"rebate_pool", campaign_key.as_ref(), &[pool.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.rebate_vault.to_account_info(),
+                    to: ctx.accounts.holder_token_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            rebate,
+        )?;
+
+        if campaign.freeze_during_raise && campaign.status == CampaignStatus::Active {
+            set_token_account_frozen(
+                campaign,
+                ctx.accounts.token_mint.to_account_info(),
+                ctx.accounts.holder_token_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                true,
+            )?;
+        }
+
+        record.rebated_fees = record.fees_paid;
+        pool.distributed = pool
+            .distributed
+            .checked_add(rebate)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit_logged!(LoyaltyRebateEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign_key,
+            holder: ctx.accounts.holder.key(),
+            fees_rebated: unrebated,
+            token_amount: rebate,
+        });
+
+        Ok(())
+    }
+
+    pub fn get_campaign_summary(ctx: Context<GetCampaignSummary>) -> Result<CampaignSummary> {
+        let campaign = &ctx.accounts.campaign;
+        let config = &ctx.accounts.global_config;
+        let now = Clock::get()?.unix_timestamp;
+
+        Ok(CampaignSummary {
+            status: campaign.status,
+            raised_amount: campaign.raised_amount,
+            target_amount: campaign.target_amount,
+            current_price: calculate_token_price(&campaign.curve, campaign.raised_amount)?,
+            supply_minted: ctx.accounts.token_mint.supply,
+            time_remaining: (campaign.end_time - now).max(0),
+            platform_fee_bps: config.fee_bps_at(campaign.fee_basis_raised),
+            frontend_fee_share_bps: config.frontend_fee_share_bps,
+            reserve_balance: campaign.reserve_balance,
+            withdrawable_balance: campaign.withdrawable_balance,
+        })
+    }
+
+    // One-call position statement for a backer's wallet or a frontend. Pass
+    // whichever of the record, ATA and stake position exist; missing ones count
+    // as empty.
+    pub fn get_position(
+        ctx: Context<GetPosition>,
+        contributor: Pubkey,
+    ) -> Result<PositionStatement> {
+        let campaign = &ctx.accounts.campaign;
+        let now = Clock::get()?.unix_timestamp;
+
+        let mut statement = PositionStatement {
+            contributor,
+            ..Default::default()
+        };
+        if let Some(account) = ctx.accounts.contributor_token_account.as_ref() {
+            statement.tokens_held = account.amount;
+            statement.tokens_frozen = account.is_frozen();
+        }
+        if let Some(record) = ctx.accounts.contribution_record.as_ref() {
+            statement.cost_basis = record.sol_contributed;
+            statement.tokens_received = record.tokens_received;
+            statement.avg_entry_price = record.avg_entry_price;
+            statement.hold_remaining_secs = record
+                .last_buy_at
+                .saturating_add(campaign.min_hold_secs)
+                .saturating_sub(now)
+                .max(0);
+            let entitled = if campaign.ticket_mint != Pubkey::default() || campaign.donation_mode {
+                record.sol_contributed
+            } else {
+                record.tokens_received
+            };
+            statement.refund_eligible =
+                campaign.has_failed(now) && !campaign.paused && entitled > 0;
+            if statement.refund_eligible {
+                statement.refund_amount = record
+                    .sol_contributed
+                    .checked_add(refund_bonus(campaign, record.sol_contributed)?)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+        if let Some(stake) = ctx.accounts.stake_position.as_ref() {
+            // Settle a copy so the statement shows what claiming would pay now
+            let mut stake = (**stake).clone();
+            stake.settle(campaign.exit_rewards_per_token_q64)?;
+            statement.tokens_staked = stake.amount;
+            statement.claimable_rewards = stake.rewards_owed;
+        }
+
+        Ok(statement)
+    }
+
+    pub fn set_attestation_oracle(ctx: Context<UpdateGlobalConfig>, oracle: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+
+        config.attestation_oracle = oracle;
+
+        emit_config_updated(ctx.accounts.admin.key(), "attestation_oracle");
+
+        Ok(())
+    }
+
+    pub fn set_identity_verifier(ctx: Context<UpdateGlobalConfig>, verifier: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+
+        config.identity_verifier = verifier;
+
+        emit_config_updated(ctx.accounts.admin.key(), "identity_verifier");
+
+        Ok(())
+    }
+
+    pub fn set_denylist(
+        ctx: Context<UpdateGlobalConfig>,
+        enabled: bool,
+        authority: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+
+        config.denylist_enabled = enabled;
+        config.denylist_authority = authority;
+
+        emit_config_updated(ctx.accounts.admin.key(), "denylist");
+
+        Ok(())
+    }
+
+    pub fn deny_address(
+        ctx: Context<DenyAddress>,
+        address: Pubkey,
+        reason_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts
+            .global_config
+            .check_denylist_authority(ctx.accounts.authority.key())?;
+
+        let entry = &mut ctx.accounts.denylist_entry;
+        entry.address = address;
+        entry.reason_hash = reason_hash;
+        entry.listed_at = Clock::get()?.unix_timestamp;
+        entry.bump = ctx.bumps.denylist_entry;
+
+        emit_logged!(AddressDeniedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            address,
+            listed_by: ctx.accounts.authority.key(),
+            reason_hash,
+        });
+
+        Ok(())
+    }
+
+    // Closing the entry is what clears the address
+    pub fn allow_address(ctx: Context<AllowAddress>) -> Result<()> {
+        ctx.accounts
+            .global_config
+            .check_denylist_authority(ctx.accounts.authority.key())?;
+
+        emit_logged!(AddressAllowedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            address: ctx.accounts.denylist_entry.address,
+            cleared_by: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn set_name_blocklist(ctx: Context<UpdateGlobalConfig>, enabled: bool) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+
+        config.name_blocklist_enabled = enabled;
+
+        emit_config_updated(ctx.accounts.admin.key(), "name_blocklist");
+
+        Ok(())
+    }
+
+    // Entries are keyed by skeleton hash, so one covers every lookalike spelling
+    pub fn block_name(ctx: Context<BlockName>, skeleton_hash: [u8; 32]) -> Result<()> {
+        require!(
+            ctx.accounts.global_config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+
+        let entry = &mut ctx.accounts.blocked_name;
+        entry.skeleton_hash = skeleton_hash;
+        entry.blocked_at = Clock::get()?.unix_timestamp;
+        entry.bump = ctx.bumps.blocked_name;
+
+        emit_logged!(NameBlockedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            skeleton_hash,
+            blocked: true,
+        });
+
+        Ok(())
+    }
+
+    // Closing the entry is what unblocks the name
+    pub fn unblock_name(ctx: Context<UnblockName>) -> Result<()> {
+        require!(
+            ctx.accounts.global_config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+
+        emit_logged!(NameBlockedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            skeleton_hash: ctx.accounts.blocked_name.skeleton_hash,
+            blocked: false,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_region_attestor(ctx: Context<UpdateGlobalConfig>, attestor: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+
+        config.region_attestor = attestor;
+
+        emit_config_updated(ctx.accounts.admin.key(), "region_attestor");
+
+        Ok(())
+    }
+
+    // The attestor vouches for where a wallet's owner resides; re-attesting
+    // overwrites the previous region
+    pub fn attest_region(ctx: Context<AttestRegion>, region: u8) -> Result<()> {
+        require!((region as u32) < u64::BITS, ErrorCode::InvalidRegion);
+
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.holder = ctx.accounts.holder.key();
+        attestation.region = region;
+        attestation.attested_at = Clock::get()?.unix_timestamp;
+        attestation.bump = ctx.bumps.attestation;
+
+        emit_logged!(RegionAttestedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            holder: attestation.holder,
+            attestor: ctx.accounts.attestor.key(),
+            region,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_lending_market(
+        ctx: Context<UpdateGlobalConfig>,
+        market: LendingMarket,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+
+        config.lending = market;
+
+        emit_config_updated(ctx.accounts.admin.key(), "lending_market");
+
+        Ok(())
+    }
+
+    // Idle withdrawable SOL is wrapped in the campaign's yield vault and handed
+    // to the admin's lending market, with the campaign signing its deposit
+    pub fn deposit_idle_sol(ctx: Context<DepositIdleSol>, amount: u64) -> Result<()> {
+        let accounts = ctx.accounts;
+        let campaign = &mut accounts.campaign;
+
+        require!(
+            campaign.creator == accounts.creator.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        campaign.ensure_active()?;
+        require!(
+            accounts.global_config.lending.program != Pubkey::default(),
+            ErrorCode::LendingNotConfigured
+        );
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        // Only the creator's share is put to work; the curve reserve stays liquid for sells
+        require!(
+            amount <= campaign.withdrawable_balance,
+            ErrorCode::CurveReserveLocked
+        );
+        // SOL handed to the lending market is out of the campaign too, so it
+        // counts against the drip cap like a withdrawal
+        draw_drip_allowance(campaign, amount)?;
+
+        **campaign.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **accounts
+            .yield_vault
+            .to_account_info()
+            .try_borrow_mut_lamports()? += amount;
+        token::sync_native(CpiContext::new(
+            accounts.token_program.to_account_info(),
+            token::SyncNative {
+                account: accounts.yield_vault.to_account_info(),
+            },
+        ))?;
+
+        let discriminator = accounts.global_config.lending.deposit_discriminator;
+        invoke_lending(
+            campaign,
+            LendingCpi {
+                program: accounts.lending_program.to_account_info(),
+                reserve: accounts.lending_reserve.to_account_info(),
+                liquidity_supply: accounts.lending_liquidity_supply.to_account_info(),
+                position: accounts.lending_position.to_account_info(),
+                yield_vault: accounts.yield_vault.to_account_info(),
+                token_program: accounts.token_program.to_account_info(),
+            },
+            discriminator,
+            amount,
+        )?;
+
+        accounts.yield_vault.reload()?;
+        require!(
+            accounts.yield_vault.amount == 0,
+            ErrorCode::LendingCallFailed
+        );
+
+        campaign.withdrawable_balance -= amount;
+        campaign.lent_amount = campaign
+            .lent_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit_logged!(LendingDepositEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            amount,
+            lent_amount: campaign.lent_amount,
+        });
+
+        assert_invariants(&accounts.campaign, None)?;
+
+        Ok(())
+    }
+
+    // Pulls everything back from the lending program into the yield vault and
+    // unwraps it into the campaign. The creator can recall at any time; once
+    // the campaign has failed anyone can, so refunds are never held up.
+    pub fn recall_lent_sol(ctx: Context<RecallLentSol>) -> Result<()> {
+        let accounts = ctx.accounts;
+        let campaign = &mut accounts.campaign;
+
+        let is_creator = accounts.caller.key() == campaign.creator;
+        require!(
+            is_creator || campaign.has_failed(Clock::get()?.unix_timestamp),
+            ErrorCode::NotCampaignCreator
+        );
+        require!(campaign.lent_amount > 0, ErrorCode::NothingLent);
+
+        let discriminator = accounts.global_config.lending.withdraw_discriminator;
+        invoke_lending(
+            campaign,
+            LendingCpi {
+                program: accounts.lending_program.to_account_info(),
+                reserve: accounts.lending_reserve.to_account_info(),
+                liquidity_supply: accounts.lending_liquidity_supply.to_account_info(),
+                position: accounts.lending_position.to_account_info(),
+                yield_vault: accounts.yield_vault.to_account_info(),
+                token_program: accounts.token_program.to_account_info(),
+            },
+            discriminator,
+            LENDING_WITHDRAW_ALL,
+        )?;
+
+        accounts.yield_vault.reload()?;
+        let returned = accounts.yield_vault.amount;
+        let principal = campaign.lent_amount;
+        // The principal is owed to the raise, so a short return is rejected outright
+        require!(returned >= principal, ErrorCode::LendingCallFailed);
+
+        // Closing unwraps the SOL into the campaign; the vault's rent goes back
+        // to the creator who paid it
+        let vault_rent = accounts.yield_vault.to_account_info().lamports() - returned;
+        let seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        token::close_account(CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            token::CloseAccount {
+                account: accounts.yield_vault.to_account_info(),
+                destination: campaign.to_account_info(),
+                authority: campaign.to_account_info(),
+            },
+            &[&seeds[..]],
+        ))?;
+        **campaign.to_account_info().try_borrow_mut_lamports()? -= vault_rent;
+        **accounts
+            .creator
+            .to_account_info()
+            .try_borrow_mut_lamports()? += vault_rent;
+
+        campaign.lent_amount = 0;
+        let yield_amount = returned - principal;
+        campaign.withdrawable_balance += principal;
+        if campaign.yield_to_contributors {
+            campaign.refund_buffer = campaign
+                .refund_buffer
+                .checked_add(yield_amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+        } else if yield_amount > 0 {
+            **campaign.to_account_info().try_borrow_mut_lamports()? -= yield_amount;
+            **accounts
+                .creator
+                .to_account_info()
+                .try_borrow_mut_lamports()? += yield_amount;
+        }
+
+        emit_logged!(LendingRecallEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            principal,
+            returned,
+            yield_amount,
+            to_contributors: campaign.yield_to_contributors,
+        });
+
+        assert_invariants(&accounts.campaign, None)?;
+
+        Ok(())
+    }
+
+    // Makes an SPL Governance account the admin. Proposal transactions are
+    // signed by that governance PDA, so every admin-gated update afterwards
+    // needs an approved proposal on the realm.
+    pub fn transfer_admin_to_governance(
+        ctx: Context<TransferAdminToGovernance>,
+        governance_seed: Pubkey,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let config = &mut accounts.global_config;
+
+        require!(config.admin == accounts.admin.key(), ErrorCode::NotAdmin);
+        require!(
+            is_realm(&accounts.realm)?,
+            ErrorCode::InvalidGovernanceAccount
+        );
+
+        let (expected, _) = Pubkey::find_program_address(
+            &[
+                b"account-governance",
+                accounts.realm.key().as_ref(),
+                governance_seed.as_ref(),
+            ],
+            &accounts.governance_program.key(),
+        );
+        require_keys_eq!(
+            accounts.governance.key(),
+            expected,
+            ErrorCode::InvalidGovernanceAccount
+        );
+
+        config.admin = expected;
+        config.governance_program = accounts.governance_program.key();
+        config.realm = accounts.realm.key();
+
+        emit_logged!(AdminTransferredToGovernanceEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            previous_admin: accounts.admin.key(),
+            governance: expected,
+            realm: config.realm,
+            governance_program: config.governance_program,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_expected_upgrade_authority(
+        ctx: Context<UpdateGlobalConfig>,
+        authority: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+
+        config.expected_upgrade_authority = authority;
+        // A new expectation invalidates whatever was verified against the old one
+        config.upgrade_authority_status = UpgradeAuthorityStatus::Unverified;
+
+        emit_config_updated(ctx.accounts.admin.key(), "expected_upgrade_authority");
+
+        Ok(())
+    }
+
+    // Permissionless: anyone can refresh the recorded upgrade authority status
+    pub fn verify_deployment(ctx: Context<VerifyDeployment>) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+        let authority = ctx.accounts.program_data.upgrade_authority_address;
+
+        config.upgrade_authority_status = match authority {
+            None => UpgradeAuthorityStatus::Renounced,
+            Some(key) if key == config.expected_upgrade_authority => {
+                UpgradeAuthorityStatus::ExpectedAuthority
+            }
+            Some(_) => UpgradeAuthorityStatus::UnexpectedAuthority,
+        };
+        config.deployment_verified_at = Clock::get()?.unix_timestamp;
+
+        emit_logged!(DeploymentVerifiedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            upgrade_authority: authority.unwrap_or_default(),
+            status: config.upgrade_authority_status,
+            verified_at: config.deployment_verified_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_lp_mint_authority(
+        ctx: Context<UpdateGlobalConfig>,
+        authority: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+
+        config.lp_mint_authority = authority;
+
+        emit_config_updated(ctx.accounts.admin.key(), "lp_mint_authority");
+
+        Ok(())
+    }
+
+    pub fn set_payout_swap(
+        ctx: Context<UpdateGlobalConfig>,
+        swap_program: Pubkey,
+        stable_mint: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+
+        config.swap_program = swap_program;
+        config.stable_mint = stable_mint;
+
+        emit_config_updated(ctx.accounts.admin.key(), "payout_swap");
+
+        Ok(())
+    }
+
+    // The route (e.g. a Jupiter swap built off-chain) arrives as remaining accounts
+    // plus its instruction data; only the USDC actually received is trusted
+    pub fn withdraw_as_stablecoin<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawAsStablecoin<'info>>,
+        amount: u64,
+        min_stable_out: u64,
+        swap_data: Vec<u8>,
+        purpose: WithdrawalPurpose,
+        memo_hash: [u8; 32],
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+
+        accounts
+            .campaign
+            .authorize(accounts.creator.key(), ROLE_WITHDRAW)?;
+        require!(
+            accounts.global_config.swap_program != Pubkey::default(),
+            ErrorCode::SwapNotConfigured
+        );
+
+        // Withdrawn SOL lands as wrapped SOL in the signer's account, ready to swap
+        let wsol_before = accounts.creator_wsol_account.amount;
+        let released = release_withdrawal(
+            &mut accounts.campaign,
+            &accounts.global_config,
+            accounts.denylist_entry.as_ref(),
+            accounts.withdrawal_approval.as_deref(),
+            &accounts.creator_wsol_account.to_account_info(),
+            amount,
+        )?;
+        record_withdrawal(
+            &mut accounts.campaign,
+            &mut accounts.receipt,
+            ctx.bumps.receipt,
+            amount,
+            purpose,
+            memo_hash,
+        )?;
+        token::sync_native(CpiContext::new(
+            accounts.token_program.to_account_info(),
+            token::SyncNative {
+                account: accounts.creator_wsol_account.to_account_info(),
+            },
+        ))?;
+
+        let balance_before = accounts.creator_stable_account.amount;
+        let swap_ix = Instruction {
+            program_id: accounts.swap_program.key(),
+            accounts: ctx
+                .remaining_accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.key(),
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: swap_data,
+        };
+        let mut swap_accounts = ctx.remaining_accounts.to_vec();
+        swap_accounts.push(accounts.swap_program.to_account_info());
+        invoke(&swap_ix, &swap_accounts)?;
+
+        // All of it has to be swapped into the creator's stable account, not left with the signer
+        accounts.creator_wsol_account.reload()?;
+        require!(
+            accounts.creator_wsol_account.amount <= wsol_before,
+            ErrorCode::InvalidWithdrawalDestination
+        );
+        accounts.creator_stable_account.reload()?;
+        let received = accounts
+            .creator_stable_account
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(ErrorCode::SlippageExceeded)?;
+        require!(received >= min_stable_out, ErrorCode::SlippageExceeded);
+
+        emit_logged!(StablecoinWithdrawalEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            creator: accounts.creator.key(),
+            sol_amount: released,
+            stable_amount: received,
+            remaining: accounts.campaign.raised_amount,
+        });
+
+        assert_invariants(&accounts.campaign, None)?;
+
+        Ok(())
+    }
+
+    // The tree account is allocated by the client (its size depends on the
+    // depth and buffer chosen) and handed to the campaign as its authority
+    pub fn enable_compressed_records(
+        ctx: Context<EnableCompressedRecords>,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+
+        require!(
+            campaign.creator == ctx.accounts.creator.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        campaign.ensure_active()?;
+        require!(
+            campaign.record_tree == Pubkey::default(),
+            ErrorCode::CompressionAlreadyEnabled
+        );
+        // Governance tokens are tied to per-contributor accounts the compressed path doesn't carry
+        require!(
+            campaign.governance_mint == Pubkey::default(),
+            ErrorCode::CompressionUnsupported
+        );
+
+        let mut args = max_depth.to_le_bytes().to_vec();
+        args.extend_from_slice(&max_buffer_size.to_le_bytes());
+        invoke_compression(
+            campaign,
+            &ctx.accounts.compression_program,
+            &ctx.accounts.merkle_tree,
+            &ctx.accounts.noop_program,
+            "init_empty_merkle_tree",
+            &args,
+            &[],
+        )?;
+
+        campaign.record_tree = ctx.accounts.merkle_tree.key();
+
+        emit_logged!(CompressedRecordsEnabledEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            merkle_tree: campaign.record_tree,
+            max_depth,
+            max_buffer_size,
+        });
+
+        Ok(())
+    }
+
+    // Like contribute, but the receipt is a leaf in the campaign's record tree
+    // rather than a ContributionRecord PDA
+    pub fn contribute_compressed(ctx: Context<ContributeCompressed>, amount: u64) -> Result<()> {
+        let contributor_key = ctx.accounts.contributor.key();
+        let accounts = ctx.accounts;
+
+        require!(
+            accounts.campaign.record_tree != Pubkey::default(),
+            ErrorCode::CompressionNotEnabled
+        );
+        check_region_access(
+            &accounts.campaign,
+            accounts.region_attestation.as_ref(),
+            contributor_key,
+        )?;
+        // Whatever the hard cap leaves no room for stays with the contributor
+        let amount = refund_excess(
+            &accounts.campaign,
+            None,
+            &accounts.contributor.to_account_info(),
+            amount,
+        )?;
+        require!(amount > 0, ErrorCode::HardCapExceeded);
+
+        let fee = collect_platform_fee(
+            PlatformFee {
+                config: &accounts.global_config,
+                campaign: &mut accounts.campaign,
+                payer: accounts.contributor.to_account_info(),
+                treasury: accounts.treasury.to_account_info(),
+                insurance_pool: accounts.insurance_pool.as_mut(),
+                frontend: None,
+                frontend_payout: None,
+                system_program: accounts.system_program.to_account_info(),
+            },
+            amount,
+        )?;
+        let amount = amount - fee.total;
+        let matched = draw_match(
+            &accounts.campaign,
+            accounts.match_commitment.as_deref_mut(),
+            amount,
+        )?;
+        let gross = amount + matched;
+        // What the curve keeps of the contributor's own SOL after its fee is
+        // what a refund pays back; the match is refunded to its sponsor
+        let curve_amount = amount - calculate_bps_share(amount, accounts.campaign.buy_fee_bps)?;
+
+        let tokens_to_mint = buy_from_curve(
+            CurvePurchase {
+                campaign: &mut accounts.campaign,
+                token_mint: accounts.token_mint.to_account_info(),
+                recipient_token_account: accounts.contributor_token_account.to_account_info(),
+                contribution_record: None,
+                record_bump: 0,
+                price_history: &mut *accounts.price_history.load_mut()?,
+                price_feed: accounts.price_feed.as_deref_mut(),
+                ticket: accounts
+                    .ticket_mint
+                    .as_ref()
+                    .zip(accounts.contributor_ticket_account.as_ref())
+                    .map(|(mint, account)| (mint.to_account_info(), account.to_account_info())),
+                token_program: accounts.token_program.to_account_info(),
+                fee_paid: fee.total,
+                from_queue: false,
+                tier_bonus_bps: None,
+                sponsored: 0,
+                global_config: &accounts.global_config,
+                denylist_entry: accounts.denylist_entry.as_ref(),
+            },
+            contributor_key,
+            gross,
+        )?;
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: accounts.contributor.to_account_info(),
+                    to: accounts.campaign.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let campaign = &mut accounts.campaign;
+        let leaf_index = campaign.compressed_record_count;
+        let leaf = compressed_record_leaf(
+            campaign.key(),
+            contributor_key,
+            curve_amount,
+            tokens_to_mint,
+            leaf_index,
+        );
+        invoke_compression(
+            campaign,
+            &accounts.compression_program,
+            &accounts.merkle_tree,
+            &accounts.noop_program,
+            "append",
+            &leaf,
+            &[],
+        )?;
+        campaign.compressed_record_count += 1;
+        // Receipts can't tell repeat backers apart, so each one over the
+        // minimum counts on its own; that costs a sybil the same either way
+        if campaign.qualifies_for_milestones(curve_amount) {
+            adjust_milestone_backers(campaign, true);
+        }
+
+        emit_logged!(CompressedContributionEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            contributor: contributor_key,
+            leaf_index,
+            sol_amount: curve_amount,
+            token_amount: tokens_to_mint,
+            new_total: campaign.raised_amount,
+        });
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    // Refunds a whole compressed receipt. The leaf is proven and nulled in one
+    // replace_leaf call; the proof nodes come in as remaining accounts.
+    pub fn refund_compressed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundCompressed<'info>>,
+        root: [u8; 32],
+        leaf_index: u64,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let campaign = &mut accounts.campaign;
+
+        require!(
+            campaign.has_failed(Clock::get()?.unix_timestamp),
+            ErrorCode::CampaignNotFailed
+        );
+        campaign.transition(CampaignStatus::Failed)?;
+        require!(campaign.lent_amount == 0, ErrorCode::FundsStillLent);
+
+        let ticket_mode = campaign.ticket_mint != Pubkey::default();
+        let (expected_mint, burn_amount) = if ticket_mode {
+            (campaign.ticket_mint, sol_amount)
+        } else {
+            (campaign.token_mint, token_amount)
+        };
+        require_keys_eq!(
+            accounts.mint.key(),
+            expected_mint,
+            ErrorCode::InvalidTokenMint
+        );
+        require!(sol_amount > 0, ErrorCode::NothingToRefund);
+
+        let leaf = compressed_record_leaf(
+            campaign.key(),
+            accounts.contributor.key(),
+            sol_amount,
+            token_amount,
+            leaf_index,
+        );
+        let index = u32::try_from(leaf_index).map_err(|_| ErrorCode::MathOverflow)?;
+        let mut args = root.to_vec();
+        args.extend_from_slice(&leaf);
+        args.extend_from_slice(&[0u8; 32]);
+        args.extend_from_slice(&index.to_le_bytes());
+        invoke_compression(
+            campaign,
+            &accounts.compression_program,
+            &accounts.merkle_tree,
+            &accounts.noop_program,
+            "replace_leaf",
+            &args,
+            ctx.remaining_accounts,
+        )?;
+
+        let bonus = u64::try_from(
+            (campaign.refund_buffer as u128)
+                .checked_mul(sol_amount as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / campaign.raised_amount.max(1) as u128,
+        )
+        .map_err(|_| ErrorCode::MathOverflow)?
+        .min(campaign.refund_buffer);
+        let payout = sol_amount
+            .checked_add(bonus)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let campaign_info = campaign.to_account_info();
+        let available = campaign_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(campaign_info.data_len()));
+        require!(payout <= available, ErrorCode::InsufficientCampaignBalance);
+
+        let refreeze =
+            (campaign.freeze_during_raise || campaign.tokens_invalidated) && !ticket_mode;
+        if refreeze {
+            set_token_account_frozen(
+                campaign,
+                accounts.mint.to_account_info(),
+                accounts.contributor_token_account.to_account_info(),
+                accounts.token_program.to_account_info(),
+                false,
+            )?;
+        }
+        token::burn(
+            CpiContext::new(
+                accounts.token_program.to_account_info(),
+                token::Burn {
+                    mint: accounts.mint.to_account_info(),
+                    from: accounts.contributor_token_account.to_account_info(),
+                    authority: accounts.contributor.to_account_info(),
+                },
+            ),
+            burn_amount,
+        )?;
+        if refreeze {
+            set_token_account_frozen(
+                campaign,
+                accounts.mint.to_account_info(),
+                accounts.contributor_token_account.to_account_info(),
+                accounts.token_program.to_account_info(),
+                true,
+            )?;
+        }
+        if !ticket_mode {
+            campaign.release_supply(SupplyBucket::Curve, burn_amount);
+        }
+
+        **campaign.to_account_info().try_borrow_mut_lamports()? -= payout;
+        **accounts
+            .contributor
+            .to_account_info()
+            .try_borrow_mut_lamports()? += payout;
+        campaign.refund_buffer -= bonus;
+        campaign.raised_amount = campaign.raised_amount.saturating_sub(sol_amount);
+        campaign.debit_balances(sol_amount);
+        let reserve_share = calculate_bps_share(sol_amount, campaign.floor_reserve_bps)?;
+        campaign.floor_reserve = campaign.floor_reserve.saturating_sub(reserve_share);
+        if campaign.qualifies_for_milestones(sol_amount) {
+            adjust_milestone_backers(campaign, false);
+        }
+
+        emit_logged!(RefundEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            contributor: accounts.contributor.key(),
+            burned: burn_amount,
+            sol_amount: payout,
+        });
+
+        assert_invariants(&accounts.campaign, None)?;
+
+        Ok(())
+    }
+
+    // Moves a compressed receipt onto the backer's ContributionRecord, so the
+    // claims, sells and votes that read a record are open to it too. The leaf
+    // is proven and nulled like a refund, so it can only be claimed once.
+    pub fn claim_compressed_record<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimCompressedRecord<'info>>,
+        root: [u8; 32],
+        leaf_index: u64,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        let contributor_key = ctx.accounts.contributor.key();
+        let accounts = ctx.accounts;
+        require!(sol_amount > 0, ErrorCode::ZeroAmount);
+        require!(
+            accounts.campaign.record_tree != Pubkey::default(),
+            ErrorCode::CompressionNotEnabled
+        );
+
+        let campaign = &mut accounts.campaign;
+        let leaf = compressed_record_leaf(
+            campaign.key(),
+            contributor_key,
+            sol_amount,
+            token_amount,
+            leaf_index,
+        );
+        let index = u32::try_from(leaf_index).map_err(|_| ErrorCode::MathOverflow)?;
+        let mut args = root.to_vec();
+        args.extend_from_slice(&leaf);
+        args.extend_from_slice(&[0u8; 32]);
+        args.extend_from_slice(&index.to_le_bytes());
+        invoke_compression(
+            campaign,
+            &accounts.compression_program,
+            &accounts.merkle_tree,
+            &accounts.noop_program,
+            "replace_leaf",
+            &args,
+            ctx.remaining_accounts,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let record = &mut accounts.contribution_record;
+        let first_contribution = record.contributor == Pubkey::default();
+        let opens_position = record.sol_contributed == 0;
+        if first_contribution {
+            record.campaign = campaign.key();
+            record.contributor = contributor_key;
+            record.bump = ctx.bumps.contribution_record;
+            campaign.contributor_count = campaign.contributor_count.saturating_add(1);
+        }
+        if opens_position {
+            record.holding_since = now;
+        }
+        record.last_buy_at = hold_start(record, now, token_amount);
+        record.sol_contributed = record
+            .sol_contributed
+            .checked_add(sol_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        record.tokens_received = record
+            .tokens_received
+            .checked_add(token_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        record.refresh_entry_price()?;
+        // The receipt counted on its own; from here the record counts instead
+        if campaign.qualifies_for_milestones(sol_amount) {
+            adjust_milestone_backers(campaign, false);
+        }
+        count_milestone_backer(campaign, record);
+        rank_entry(
+            &mut accounts.price_history.load_mut()?.top_backers,
+            contributor_key,
+            record.sol_contributed,
+        );
+        update_backer_profile(&accounts.backer_profile, |profile| {
+            profile.record_contribution(first_contribution, opens_position, sol_amount)
+        })?;
+
+        emit_logged!(CompressedRecordClaimedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            contributor: contributor_key,
+            leaf_index,
+            sol_amount,
+            token_amount,
+        });
+
+        Ok(())
+    }
+
+    // Failure is otherwise only noticed lazily; settling it frees the creator's
+    // active slot and starts their cooldown. Anyone can call it.
+    pub fn settle_failed_campaign(ctx: Context<SettleFailedCampaign>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(campaign.has_failed(now), ErrorCode::CampaignNotFailed);
+        require!(!campaign.profile_settled, ErrorCode::CampaignAlreadySettled);
+        campaign.transition(CampaignStatus::Failed)?;
+        campaign.profile_settled = true;
+
+        let profile = &mut ctx.accounts.creator_profile;
+        profile.active_campaigns = profile.active_campaigns.saturating_sub(1);
+        // Measured from when the campaign ended, not from whenever someone settles it
+        profile.last_failure_at = profile.last_failure_at.max(campaign.end_time.min(now));
+
+        emit_logged!(CampaignSettledEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            creator: campaign.creator,
+            active_campaigns: profile.active_campaigns,
+        });
+
+        assert_invariants(&ctx.accounts.campaign, None)?;
+
+        Ok(())
+    }
+
+    pub fn snapshot_campaign(ctx: Context<SnapshotCampaign>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let slot = Clock::get()?.slot;
+
+        // Borsh encoding of the full account; indexers concatenate chunks in
+        // order and decode it as a Campaign
+        let data = campaign.try_to_vec()?;
+        let chunk_count = data.len().div_ceil(SNAPSHOT_CHUNK_LEN) as u16;
+        for (index, chunk) in data.chunks(SNAPSHOT_CHUNK_LEN).enumerate() {
+            emit_logged!(CampaignSnapshotEvent {
+                schema_version: EVENT_SCHEMA_VERSION,
+                campaign: campaign.key(),
+                slot,
+                chunk_index: index as u16,
+                chunk_count,
+                data: chunk.to_vec(),
+            });
+        }
+
+        Ok(())
+    }
+
+    // For contributors that are PDAs of other programs: the calling program signs
+    // for `contributor` with invoke_signed while `funder` supplies SOL and rent,
+    // since program-owned accounts can't pay through the system program
+    pub fn contribute_as_program(ctx: Context<ContributeAsProgram>, amount: u64) -> Result<()> {
+        let contributor_key = ctx.accounts.contributor.key();
+        let accounts = ctx.accounts;
+        check_region_access(
+            &accounts.campaign,
+            accounts.region_attestation.as_ref(),
+            contributor_key,
+        )?;
+        // Whatever the hard cap leaves no room for stays with the funder
+        let amount = refund_excess(
+            &accounts.campaign,
+            None,
+            &accounts.funder.to_account_info(),
+            amount,
+        )?;
+        require!(amount > 0, ErrorCode::HardCapExceeded);
+
+        settle_contribution(
+            ContributionPayment {
+                campaign: &mut accounts.campaign,
+                global_config: &accounts.global_config,
+                payer: accounts.funder.to_account_info(),
+                treasury: accounts.treasury.to_account_info(),
+                insurance_pool: accounts.insurance_pool.as_mut(),
+                frontend: None,
+                frontend_payout: None,
+                matchable: true,
+                match_commitment: accounts.match_commitment.as_deref_mut(),
+                token_mint: accounts.token_mint.to_account_info(),
+                recipient_token_account: accounts.contributor_token_account.to_account_info(),
+                contribution_record: &mut accounts.contribution_record,
+                record_bump: ctx.bumps.contribution_record,
+                price_history: &mut *accounts.price_history.load_mut()?,
+                price_feed: accounts.price_feed.as_deref_mut(),
+                ticket: accounts
+                    .ticket_mint
+                    .as_ref()
+                    .zip(accounts.contributor_ticket_account.as_ref())
+                    .map(|(mint, account)| (mint.to_account_info(), account.to_account_info())),
+                token_program: accounts.token_program.to_account_info(),
+                tier_bonus_bps: None,
+                denylist_entry: accounts.denylist_entry.as_ref(),
+                governance: Some(GovernanceMint {
+                    governance_mint: accounts.governance_mint.as_ref(),
+                    governance_authority: accounts.governance_authority.as_ref(),
+                    holder_account: accounts.contributor_governance_account.as_ref(),
+                    token_2022_program: accounts.token_2022_program.as_ref(),
+                    holder: accounts.contributor.to_account_info(),
+                    payer: accounts.funder.to_account_info(),
+                    associated_token_program: accounts.associated_token_program.to_account_info(),
+                    system_program: accounts.system_program.to_account_info(),
+                }),
+                leaderboard: accounts.leaderboard.as_ref(),
+                backer_profile: Some(&accounts.backer_profile),
+                system_program: accounts.system_program.to_account_info(),
+                reference: None,
+            },
+            contributor_key,
+            amount,
+        )?;
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn claim_badge(ctx: Context<ClaimBadge>, kind: BadgeKind) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let record = &ctx.accounts.contribution_record;
+        let holder_key = ctx.accounts.holder.key();
+
+        let earned = match kind {
+            BadgeKind::FirstContribution => {
+                record.sol_contributed > 0 || record.tokens_received > 0
+            }
+            BadgeKind::TopBacker => ctx
+                .accounts
+                .price_history
+                .load()?
+                .top_backers
+                .iter()
+                .any(|entry| entry.key == holder_key && entry.value > 0),
+            BadgeKind::Graduate => campaign.status == CampaignStatus::Graduated && !record.has_sold,
+        };
+        require!(earned, ErrorCode::BadgeNotEarned);
+
+        let campaign_key = campaign.key();
+        let campaign_seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        let badge_mint = &ctx.accounts.badge_mint;
+        let token_2022_program = &ctx.accounts.token_2022_program;
+
+        // Each badge kind gets its own soulbound mint, created by the first claimer
+        if badge_mint.data_is_empty() {
+            let mint_seeds: &[&[u8]] = &[
+                b"badge_mint",
+                campaign_key.as_ref(),
+                &[kind as u8],
+                &[ctx.bumps.badge_mint],
+            ];
+            let space =
+                ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+                    ExtensionType::NonTransferable,
+                ])?;
+            anchor_lang::system_program::create_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::CreateAccount {
+                        from: ctx.accounts.holder.to_account_info(),
+                        to: badge_mint.to_account_info(),
+                    },
+                    &[mint_seeds],
+                ),
+                Rent::get()?.minimum_balance(space),
+                space as u64,
+                &token_2022_program.key(),
+            )?;
+            token_2022_extensions::non_transferable_mint_initialize(CpiContext::new(
+                token_2022_program.to_account_info(),
+                token_2022_extensions::NonTransferableMintInitialize {
+                    token_program_id: token_2022_program.to_account_info(),
+                    mint: badge_mint.to_account_info(),
+                },
+            ))?;
+            token_2022::initialize_mint2(
+                CpiContext::new(
+                    token_2022_program.to_account_info(),
+                    token_2022::InitializeMint2 {
+                        mint: badge_mint.to_account_info(),
+                    },
+                ),
+                0,
+                &campaign_key,
+                None,
+            )?;
+        }
+
+        let expected = associated_token::get_associated_token_address_with_program_id(
+            &holder_key,
+            &badge_mint.key(),
+            &token_2022_program.key(),
+        );
+        require_keys_eq!(
+            ctx.accounts.holder_badge_account.key(),
+            expected,
+            ErrorCode::InvalidTokenAccount
+        );
+        associated_token::create_idempotent(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.holder.to_account_info(),
+                associated_token: ctx.accounts.holder_badge_account.to_account_info(),
+                authority: ctx.accounts.holder.to_account_info(),
+                mint: badge_mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: token_2022_program.to_account_info(),
+            },
+        ))?;
+
+        token_2022::mint_to(
+            CpiContext::new_with_signer(
+                token_2022_program.to_account_info(),
+                token_2022::MintTo {
+                    mint: badge_mint.to_account_info(),
+                    to: ctx.accounts.holder_badge_account.to_account_info(),
+                    authority: campaign.to_account_info(),
+                },
+                &[&campaign_seeds[..]],
+            ),
+            1,
+        )?;
+
+        let claim = &mut ctx.accounts.badge_claim;
+        claim.campaign = campaign_key;
+        claim.holder = holder_key;
+        claim.kind = kind;
+        claim.bump = ctx.bumps.badge_claim;
+
+        emit_logged!(BadgeClaimedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign_key,
+            holder: holder_key,
+            kind,
+        });
+
+        Ok(())
+    }
+
+    // After graduation, campaigns with a secondary curve keep trading on-program
+    // instead of migrating to an AMM. The curve is steeper than the primary and
+    // its fee goes to the creator rather than back to contributors.
+    pub fn buy_secondary(
+        ctx: Context<TradeSecondary>,
+        amount: u64,
+        min_tokens_out: u64,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let campaign = &accounts.campaign;
+
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        require!(
+            campaign.secondary_slope_bps > 0,
+            ErrorCode::SecondaryCurveDisabled
+        );
+        require!(amount > 0, ErrorCode::ZeroAmount);
+
+        let fee = calculate_bps_share(amount, campaign.secondary_fee_bps)?;
+        let net_amount = amount - fee;
+        let new_raised = campaign
+            .secondary_raised
+            .checked_add(net_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        // Filled at the price after the order, so splitting it never pays less
+        let price = calculate_secondary_price(campaign, new_raised)?;
+        let token_amount = u64::try_from(net_amount as u128 * TOKEN_PRECISION / price as u128)
+            .map_err(|_| ErrorCode::MathOverflow)?;
+        require!(token_amount > 0, ErrorCode::AmountTooSmall);
+        require!(token_amount >= min_tokens_out, ErrorCode::SlippageExceeded);
+        accounts
+            .campaign
+            .draw_supply(SupplyBucket::Curve, token_amount)?;
+        let campaign = &accounts.campaign;
+
+        if fee > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: accounts.trader.to_account_info(),
+                        to: accounts.creator.to_account_info(),
+                    },
+                ),
+                fee,
+            )?;
+        }
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: accounts.trader.to_account_info(),
+                    to: campaign.to_account_info(),
+                },
+            ),
+            net_amount,
+        )?;
+
+        let seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: accounts.token_mint.to_account_info(),
+                    to: accounts.trader_token_account.to_account_info(),
+                    authority: campaign.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            token_amount,
+        )?;
+
+        let campaign = &mut accounts.campaign;
+        campaign.secondary_raised = new_raised;
+        publish_price_feed(campaign, accounts.price_feed.as_deref_mut(), price)?;
+
+        emit_logged!(SecondaryTradeEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            trader: accounts.trader.key(),
+            is_buy: true,
+            sol_amount: net_amount,
+            token_amount,
+            fee,
+            price,
+        });
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn sell_secondary(
+        ctx: Context<TradeSecondary>,
+        token_amount: u64,
+        min_sol_out: u64,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let campaign = &accounts.campaign;
+
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        require!(
+            campaign.secondary_slope_bps > 0,
+            ErrorCode::SecondaryCurveDisabled
+        );
+        require!(token_amount > 0, ErrorCode::ZeroAmount);
+
+        campaign.check_team_embargo(accounts.trader.key(), Clock::get()?.unix_timestamp)?;
+
+        let sol_amount = calculate_secondary_sale(campaign, token_amount)?;
+        require!(sol_amount > 0, ErrorCode::AmountTooSmall);
+        // Only SOL paid into this curve backs it; the primary raise is the creator's
+        require!(
+            sol_amount <= campaign.secondary_raised,
+            ErrorCode::InsufficientCurveLiquidity
+        );
+        let fee = calculate_bps_share(sol_amount, campaign.secondary_fee_bps)?;
+        let payout = sol_amount - fee;
+        require!(payout >= min_sol_out, ErrorCode::SlippageExceeded);
+
+        token::burn(
+            CpiContext::new(
+                accounts.token_program.to_account_info(),
+                token::Burn {
+                    mint: accounts.token_mint.to_account_info(),
+                    from: accounts.trader_token_account.to_account_info(),
+                    authority: accounts.trader.to_account_info(),
+                },
+            ),
+            token_amount,
+        )?;
+
+        let campaign = &mut accounts.campaign;
+        campaign.release_supply(SupplyBucket::Curve, token_amount);
+        campaign.secondary_raised -= sol_amount;
+        **campaign.to_account_info().try_borrow_mut_lamports()? -= sol_amount;
+        **accounts
+            .trader
+            .to_account_info()
+            .try_borrow_mut_lamports()? += payout;
+        **accounts
+            .creator
+            .to_account_info()
+            .try_borrow_mut_lamports()? += fee;
+
+        let price = calculate_secondary_price(campaign, campaign.secondary_raised)?;
+        publish_price_feed(campaign, accounts.price_feed.as_deref_mut(), price)?;
+
+        emit_logged!(SecondaryTradeEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            trader: accounts.trader.key(),
+            is_buy: false,
+            sol_amount: payout,
+            token_amount,
+            fee,
+            price,
+        });
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    // Admin switch for incidents. Pausing halts trading, refunds and
+    // withdrawals, and fixes the price queued exits will be paid at.
+    pub fn set_campaign_paused(ctx: Context<SetCampaignPaused>, paused: bool) -> Result<()> {
+        require!(
+            ctx.accounts.global_config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+        let campaign = &mut ctx.accounts.campaign;
+        if paused && !campaign.paused {
+            campaign.paused_raised = campaign.raised_amount;
+        }
+        campaign.paused = paused;
+
+        emit_logged!(CampaignPausedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            paused,
+            protected_raised: campaign.paused_raised,
+        });
+
+        Ok(())
+    }
+
+    // While a campaign is paused, contributors can burn their tokens into the
+    // exit queue at the pre-incident price rather than waiting it out
+    pub fn request_exit(ctx: Context<RequestExit>, token_amount: u64) -> Result<()> {
+        let accounts = ctx.accounts;
+        let campaign = &mut accounts.campaign;
+
+        require!(campaign.paused, ErrorCode::CampaignNotPaused);
+        require!(
+            campaign.ticket_mint == Pubkey::default(),
+            ErrorCode::TicketModeActive
+        );
+        require!(token_amount > 0, ErrorCode::ZeroAmount);
+        campaign.check_team_embargo(accounts.holder.key(), Clock::get()?.unix_timestamp)?;
+
+        let sol_owed =
+            calculate_sol_from_tokens(&campaign.curve, token_amount, campaign.paused_raised)?;
+        require!(sol_owed > 0, ErrorCode::AmountTooSmall);
+        let pending_exit_sol = campaign
+            .pending_exit_sol
+            .checked_add(sol_owed)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            pending_exit_sol
+                <= campaign
+                    .raised_amount
+                    .saturating_sub(campaign.floor_reserve)
+                    .saturating_sub(campaign.lent_amount),
+            ErrorCode::InsufficientCurveLiquidity
+        );
+
+        if campaign.freeze_during_raise {
+            set_token_account_frozen(
+                campaign,
+                accounts.token_mint.to_account_info(),
+                accounts.holder_token_account.to_account_info(),
+                accounts.token_program.to_account_info(),
+                false,
+            )?;
+        }
+        token::burn(
+            CpiContext::new(
+                accounts.token_program.to_account_info(),
+                token::Burn {
+                    mint: accounts.token_mint.to_account_info(),
+                    from: accounts.holder_token_account.to_account_info(),
+                    authority: accounts.holder.to_account_info(),
+                },
+            ),
+            token_amount,
+        )?;
+        if campaign.freeze_during_raise {
+            set_token_account_frozen(
+                campaign,
+                accounts.token_mint.to_account_info(),
+                accounts.holder_token_account.to_account_info(),
+                accounts.token_program.to_account_info(),
+                true,
+            )?;
+        }
+        campaign.release_supply(SupplyBucket::Curve, token_amount);
+
+        // Exited tokens stop counting towards refunds and voting weight, as in a sale
+        let record = &mut accounts.contribution_record;
+        let exited = token_amount.min(record.tokens_received);
+        if exited > 0 {
+            let basis = (record.sol_contributed as u128) * (exited as u128)
+                / (record.tokens_received as u128);
+            record.sol_contributed -= basis as u64;
+        }
+        record.tokens_received -= exited;
+        record.refresh_entry_price()?;
+        count_milestone_backer(campaign, record);
+
+        let request = &mut accounts.exit_request;
+        request.campaign = campaign.key();
+        request.holder = accounts.holder.key();
+        request.index = campaign.exit_queue_tail;
+        request.token_amount = token_amount;
+        request.sol_owed = sol_owed;
+        request.requested_at = Clock::get()?.unix_timestamp;
+        request.bump = ctx.bumps.exit_request;
+
+        campaign.exit_queue_tail += 1;
+        campaign.pending_exit_sol = pending_exit_sol;
+
+        emit_logged!(ExitRequestedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: request.campaign,
+            holder: request.holder,
+            index: request.index,
+            token_amount,
+            sol_owed,
+        });
+
+        if accounts.campaign.governance_mint != Pubkey::default() {
+            burn_governance_tokens(
+                &accounts.campaign,
+                GovernanceBurn {
+                    governance_mint: accounts.governance_mint.as_ref(),
+                    governance_authority: accounts.governance_authority.as_ref(),
+                    holder_account: accounts.holder_governance_account.as_ref(),
+                    token_2022_program: accounts.token_2022_program.as_ref(),
+                    holder: accounts.holder.to_account_info(),
+                },
+                token_amount,
+            )?;
+        }
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    // Permissionless crank paying out the oldest queued exit once the campaign is unpaused
+    pub fn process_exit(ctx: Context<ProcessExit>) -> Result<()> {
+        let accounts = ctx.accounts;
+        let campaign = &mut accounts.campaign;
+        let request = &accounts.exit_request;
+
+        require!(!campaign.paused, ErrorCode::CampaignPaused);
+
+        let sol_owed = request.sol_owed;
+        **campaign.to_account_info().try_borrow_mut_lamports()? -= sol_owed;
+        **accounts
+            .holder
+            .to_account_info()
+            .try_borrow_mut_lamports()? += sol_owed;
+
+        campaign.raised_amount = campaign.raised_amount.saturating_sub(sol_owed);
+        campaign.debit_balances(sol_owed);
+        let reserve_share = calculate_bps_share(sol_owed, campaign.floor_reserve_bps)?;
+        campaign.floor_reserve = campaign.floor_reserve.saturating_sub(reserve_share);
+        campaign.pending_exit_sol -= sol_owed;
+        campaign.exit_queue_head += 1;
+
+        emit_logged!(ExitProcessedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            holder: request.holder,
+            index: request.index,
+            sol_amount: sol_owed,
+        });
+
+        assert_invariants(&accounts.campaign, None)?;
+
+        Ok(())
+    }
+
+    // Escrow SOL ahead of start_time. Deposits convert in the order they were
+    // made once the campaign opens, before the curve takes any other buys.
+    pub fn pre_deposit(ctx: Context<PreDeposit>, amount: u64) -> Result<()> {
+        let depositor_key = ctx.accounts.depositor.key();
+        let accounts = ctx.accounts;
+
+        require!(
+            accounts.campaign.pre_deposits,
+            ErrorCode::PreDepositsDisabled
+        );
+        // Nothing is escrowed for a campaign the reviewer hasn't approved yet
+        accounts.campaign.ensure_active()?;
+        require!(
+            Clock::get()?.unix_timestamp < accounts.campaign.start_time,
+            ErrorCode::CampaignAlreadyStarted
+        );
+        validation::contribution(amount)?;
+        check_region_access(
+            &accounts.campaign,
+            accounts.region_attestation.as_ref(),
+            depositor_key,
+        )?;
+
+        // The platform fee is taken now so the escrow holds exactly what converts
+        let fee = collect_platform_fee(
+            PlatformFee {
+                config: &accounts.global_config,
+                campaign: &mut accounts.campaign,
+                payer: accounts.depositor.to_account_info(),
+                treasury: accounts.treasury.to_account_info(),
+                insurance_pool: accounts.insurance_pool.as_mut(),
+                frontend: None,
+                frontend_payout: None,
+                system_program: accounts.system_program.to_account_info(),
+            },
+            amount,
+        )?;
+        let amount = amount - fee.total;
+        require!(amount > 0, ErrorCode::AmountTooSmall);
+
+        // The whole queue converts at the opening price, so it has to fit under
+        // the hard cap and within the curve's supply from the start
+        let campaign = &accounts.campaign;
+        validation::within_cap(campaign.pre_deposit_total, amount, campaign.hard_cap)?;
+        let queue_total = campaign
+            .pre_deposit_total
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            calculate_tokens_from_sol(&campaign.curve, queue_total, campaign.raised_amount)?
+                <= campaign.unminted(SupplyBucket::Curve),
+            ErrorCode::SupplyBucketExhausted
+        );
+
+        let rent = Rent::get()?;
+        let rent_allowance = rent.minimum_balance(TokenAccount::LEN)
+            + rent.minimum_balance(8 + ContributionRecord::INIT_SPACE);
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: accounts.depositor.to_account_info(),
+                    to: accounts.deposit.to_account_info(),
+                },
+            ),
+            amount + rent_allowance,
+        )?;
+
+        let campaign = &mut accounts.campaign;
+        let deposit = &mut accounts.deposit;
+        deposit.campaign = campaign.key();
+        deposit.depositor = depositor_key;
+        deposit.index = campaign.pre_deposit_tail;
+        deposit.amount = amount;
+        deposit.fee_paid = fee.total;
+        deposit.rent_allowance = rent_allowance;
+        deposit.bump = ctx.bumps.deposit;
+
+        campaign.pre_deposit_tail += 1;
+        campaign.pre_deposit_total = campaign
+            .pre_deposit_total
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit_logged!(PreDepositEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            depositor: depositor_key,
+            index: deposit.index,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // Take back a deposit that hasn't converted yet. Allowed at any time, so a
+    // queue the crank can't get through never holds anyone's SOL.
+    pub fn cancel_pre_deposit(ctx: Context<CancelPreDeposit>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        let deposit = &mut ctx.accounts.deposit;
+
+        require!(deposit.amount > 0, ErrorCode::ZeroAmount);
+
+        // The deposit stays in place, emptied, so the queue indices don't shift
+        let amount = deposit.amount;
+        let returned = amount + deposit.rent_allowance;
+        deposit.amount = 0;
+        deposit.rent_allowance = 0;
+        **deposit.to_account_info().try_borrow_mut_lamports()? -= returned;
+        **ctx
+            .accounts
+            .depositor
+            .to_account_info()
+            .try_borrow_mut_lamports()? += returned;
+        campaign.pre_deposit_total -= amount;
+
+        emit_logged!(PreDepositCancelledEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            depositor: deposit.depositor,
+            index: deposit.index,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless crank converting the oldest queued deposit once the campaign
+    // opens. The queue converts as one block at the opening price, fixed when
+    // its first deposit is processed. A deposit that can't convert any more,
+    // because its depositor has since been denylisted or the curve can't cover
+    // it, is refunded instead so it doesn't hold up the queue.
+    pub fn process_pre_deposit(ctx: Context<ProcessPreDeposit>) -> Result<()> {
+        let accounts = ctx.accounts;
+        let deposit = &accounts.deposit;
+        let queued = deposit.amount;
+        let depositor_key = deposit.depositor;
+        let rent = Rent::get()?;
+        let mut rent_used = if accounts.contribution_record.contributor == Pubkey::default() {
+            accounts.contribution_record.to_account_info().lamports()
+        } else {
+            0
+        };
+
+        let campaign = &mut accounts.campaign;
+        if campaign.queue_sol == 0 && campaign.pre_deposit_total > 0 {
+            campaign.queue_sol = campaign.pre_deposit_total;
+            campaign.queue_tokens = calculate_tokens_from_sol(
+                &campaign.curve,
+                campaign.queue_sol,
+                campaign.raised_amount,
+            )?;
+        }
+
+        let denied = accounts.global_config.denylist_enabled
+            && !accounts
+                .denylist_entry
+                .as_ref()
+                .ok_or(ErrorCode::DenylistEntryRequired)?
+                .data_is_empty();
+        let convertible =
+            !denied && campaign.queued_tokens(queued)? <= campaign.unminted(SupplyBucket::Curve);
+
+        // Whatever the hard cap leaves no room for goes back to the depositor
+        let amount = if convertible {
+            refund_excess(
+                &accounts.campaign,
+                Some(&deposit.to_account_info()),
+                &accounts.depositor.to_account_info(),
+                queued,
+            )?
+        } else {
+            **deposit.to_account_info().try_borrow_mut_lamports()? -= queued;
+            **accounts
+                .depositor
+                .to_account_info()
+                .try_borrow_mut_lamports()? += queued;
+            emit_logged!(PreDepositCancelledEvent {
+                schema_version: EVENT_SCHEMA_VERSION,
+                campaign: campaign.key(),
+                depositor: depositor_key,
+                index: deposit.index,
+                amount: queued,
+            });
+            0
+        };
+
+        let mut tokens_to_mint = 0;
+        if amount > 0 {
+            let campaign = &accounts.campaign;
+            if campaign.ticket_mint == Pubkey::default()
+                && !campaign.donation_mode
+                && accounts.depositor_token_account.data_is_empty()
+            {
+                associated_token::create_idempotent(CpiContext::new(
+                    accounts.associated_token_program.to_account_info(),
+                    associated_token::Create {
+                        payer: accounts.cranker.to_account_info(),
+                        associated_token: accounts.depositor_token_account.to_account_info(),
+                        authority: accounts.depositor.to_account_info(),
+                        mint: accounts.token_mint.to_account_info(),
+                        system_program: accounts.system_program.to_account_info(),
+                        token_program: accounts.token_program.to_account_info(),
+                    },
+                ))?;
+                rent_used += rent.minimum_balance(TokenAccount::LEN);
+            }
+
+            **deposit.to_account_info().try_borrow_mut_lamports()? -= amount;
+            **accounts
+                .campaign
+                .to_account_info()
+                .try_borrow_mut_lamports()? += amount;
+
+            tokens_to_mint = buy_from_curve(
+                CurvePurchase {
+                    campaign: &mut accounts.campaign,
+                    token_mint: accounts.token_mint.to_account_info(),
+                    recipient_token_account: accounts.depositor_token_account.to_account_info(),
+                    contribution_record: Some(&mut accounts.contribution_record),
+                    record_bump: ctx.bumps.contribution_record,
+                    price_history: &mut *accounts.price_history.load_mut()?,
+                    price_feed: accounts.price_feed.as_deref_mut(),
+                    ticket: accounts
+                        .ticket_mint
+                        .as_ref()
+                        .zip(accounts.depositor_ticket_account.as_ref())
+                        .map(|(mint, account)| (mint.to_account_info(), account.to_account_info())),
+                    token_program: accounts.token_program.to_account_info(),
+                    fee_paid: deposit.fee_paid,
+                    from_queue: true,
+                    tier_bonus_bps: None,
+                    sponsored: 0,
+                    global_config: &accounts.global_config,
+                    denylist_entry: accounts.denylist_entry.as_ref(),
+                },
+                depositor_key,
+                amount,
+            )?;
+        }
+
+        // The cranker gets back the rent for what it had to create
+        let reimbursed = rent_used.min(deposit.rent_allowance);
+        **deposit.to_account_info().try_borrow_mut_lamports()? -= reimbursed;
+        **accounts
+            .cranker
+            .to_account_info()
+            .try_borrow_mut_lamports()? += reimbursed;
+
+        let campaign = &mut accounts.campaign;
+        campaign.pre_deposit_head += 1;
+        campaign.pre_deposit_total -= queued;
+
+        emit_logged!(PreDepositProcessedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            depositor: depositor_key,
+            index: deposit.index,
+            sol_amount: amount,
+            token_amount: tokens_to_mint,
+        });
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    // Grant, change or (with roles = 0) revoke a co-creator's permissions.
+    // Only the creator manages the team; co-creators can't delegate further.
+    pub fn set_co_creator(
+        ctx: Context<CampaignAuthority>,
+        member: Pubkey,
+        roles: u8,
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        require!(
+            campaign.creator == ctx.accounts.authority.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        require!(
+            campaign.governance == Pubkey::default(),
+            ErrorCode::AuthorityHeldByGovernance
+        );
+        require!(roles & !ALL_ROLES == 0, ErrorCode::InvalidCreatorRoles);
+        require!(member != campaign.creator, ErrorCode::InvalidCreatorRoles);
+
+        let count = campaign.co_creator_count as usize;
+        let existing = campaign.co_creators[..count]
+            .iter()
+            .position(|c| c.key == member);
+        match (existing, roles) {
+            (Some(i), 0) => {
+                campaign.co_creators[i] = campaign.co_creators[count - 1];
+                campaign.co_creators[count - 1] = CoCreator::default();
+                campaign.co_creator_count -= 1;
+            }
+            (Some(i), _) => campaign.co_creators[i].roles = roles,
+            (None, 0) => return err!(ErrorCode::NotCoCreator),
+            (None, _) => {
+                require!(count < MAX_CO_CREATORS, ErrorCode::TooManyCoCreators);
+                campaign.co_creators[count] = CoCreator { key: member, roles };
+                campaign.co_creator_count += 1;
+            }
+        }
+
+        emit_logged!(CoCreatorUpdatedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            member,
+            roles,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_campaign_metadata(
+        ctx: Context<CampaignAuthority>,
+        description: String,
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.authorize(ctx.accounts.authority.key(), ROLE_UPDATE_METADATA)?;
+        require!(
+            description.len() <= MAX_DESCRIPTION_LEN,
+            ErrorCode::MetadataTooLong
+        );
+        campaign.description = description;
+
+        emit_logged!(CampaignMetadataUpdatedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            updated_by: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    // The team's own brake on new contributions. Unlike the admin pause it
+    // leaves sells, refunds and withdrawals alone, so it can't lock backers in.
+    pub fn set_contributions_paused(ctx: Context<CampaignAuthority>, paused: bool) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.authorize(ctx.accounts.authority.key(), ROLE_PAUSE)?;
+        campaign.contributions_paused = paused;
+
+        emit_logged!(ContributionsPausedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            paused,
+            updated_by: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    // Add or replace the FAQ answer for a question. Questions and answers are
+    // kept off-chain at `uri`; the hashes let readers verify what they fetched.
+    pub fn set_faq_entry(
+        ctx: Context<SetFaqEntry>,
+        question_hash: [u8; 32],
+        answer_hash: [u8; 32],
+        uri: String,
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.authorize(ctx.accounts.authority.key(), ROLE_UPDATE_METADATA)?;
+        require!(
+            answer_hash != [0; 32] && uri.len() <= MAX_FAQ_URI_LEN,
+            ErrorCode::InvalidFaqEntry
+        );
+
+        let entry = &mut ctx.accounts.faq_entry;
+        if entry.campaign == Pubkey::default() {
+            require!(
+                campaign.faq_count < MAX_FAQ_ENTRIES,
+                ErrorCode::TooManyFaqEntries
+            );
+            campaign.faq_count += 1;
+            entry.campaign = campaign.key();
+            entry.question_hash = question_hash;
+            entry.bump = ctx.bumps.faq_entry;
+        }
+        entry.answer_hash = answer_hash;
+        entry.uri = uri;
+        entry.updated_at = Clock::get()?.unix_timestamp;
+
+        emit_logged!(FaqEntryUpdatedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            question_hash,
+            answer_hash,
+            uri: entry.uri.clone(),
+            removed: false,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_faq_entry(ctx: Context<RemoveFaqEntry>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.authorize(ctx.accounts.authority.key(), ROLE_UPDATE_METADATA)?;
+        campaign.faq_count -= 1;
+
+        let entry = &ctx.accounts.faq_entry;
+        emit_logged!(FaqEntryUpdatedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            question_hash: entry.question_hash,
+            answer_hash: entry.answer_hash,
+            uri: entry.uri.clone(),
+            removed: true,
+        });
+
+        Ok(())
+    }
+
+    // Progress updates live off chain; the campaign numbers them and commits to their hash
+    pub fn post_update(ctx: Context<CampaignAuthority>, content_hash: [u8; 32]) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.authorize(ctx.accounts.authority.key(), ROLE_POST_UPDATES)?;
+
+        emit_logged!(CampaignUpdatePostedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            author: ctx.accounts.authority.key(),
+            index: campaign.update_count,
+            content_hash,
+        });
+        campaign.update_count += 1;
+
+        Ok(())
+    }
+
+    // Review mode: reviewer key and the bond each new campaign posts until approved
+    pub fn set_review_mode(
+        ctx: Context<UpdateGlobalConfig>,
+        require_review: bool,
+        reviewer: Pubkey,
+        review_bond: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+        config.require_review = require_review;
+        config.reviewer = reviewer;
+        config.review_bond = review_bond;
+        emit_config_updated(ctx.accounts.admin.key(), "review_mode");
+        Ok(())
+    }
+
+    pub fn approve_campaign(ctx: Context<ApproveCampaign>) -> Result<()> {
+        require!(
+            ctx.accounts.global_config.reviewer == ctx.accounts.reviewer.key(),
+            ErrorCode::NotReviewer
+        );
+        let campaign = &mut ctx.accounts.campaign;
+        require!(
+            campaign.status == CampaignStatus::PendingReview,
+            ErrorCode::CampaignNotPendingReview
+        );
+        campaign.transition(CampaignStatus::Active)?;
+
+        let bond = campaign.review_bond;
+        campaign.review_bond = 0;
+        **campaign.to_account_info().try_borrow_mut_lamports()? -= bond;
+        **ctx
+            .accounts
+            .creator
+            .to_account_info()
+            .try_borrow_mut_lamports()? += bond;
+
+        emit_logged!(CampaignReviewedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            reviewer: ctx.accounts.reviewer.key(),
+            approved: true,
+            reason_hash: [0; 32],
+        });
+
+        Ok(())
+    }
+
+    // Closing the campaign hands its rent and bond back to the creator. Anything
+    // others escrowed against it goes back first: a sponsor match, and queued
+    // deposits passed as remaining deposit and depositor pairs.
+    pub fn reject_campaign<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RejectCampaign<'info>>,
+        reason_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_config.reviewer == ctx.accounts.reviewer.key(),
+            ErrorCode::NotReviewer
+        );
+        let campaign = &mut ctx.accounts.campaign;
+        require!(
+            campaign.status == CampaignStatus::PendingReview,
+            ErrorCode::CampaignNotPendingReview
+        );
+        return_escrows(
+            campaign,
+            ctx.accounts.match_commitment.as_ref(),
+            ctx.accounts.sponsor.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+
+        let profile = &mut ctx.accounts.creator_profile;
+        profile.active_campaigns = profile.active_campaigns.saturating_sub(1);
+
+        emit_logged!(CampaignReviewedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            reviewer: ctx.accounts.reviewer.key(),
+            approved: false,
+            reason_hash,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless crank freezing a failed campaign's token accounts (passed
+    // as remaining accounts) so the dead token can't change hands. Refunds
+    // still work; they thaw, burn and refreeze like frozen-raise campaigns.
+    pub fn invalidate_tokens<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InvalidateTokens<'info>>,
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+
+        require!(
+            campaign.has_failed(Clock::get()?.unix_timestamp),
+            ErrorCode::CampaignNotFailed
+        );
+        require!(
+            campaign.ticket_mint == Pubkey::default(),
+            ErrorCode::TicketModeActive
+        );
+        require!(
+            Option::from(ctx.accounts.token_mint.freeze_authority) == Some(campaign.key()),
+            ErrorCode::FreezeAuthorityRevoked
+        );
+        campaign.transition(CampaignStatus::Failed)?;
+        let first = !campaign.tokens_invalidated;
+        campaign.tokens_invalidated = true;
+
+        for holder_account in ctx.remaining_accounts {
+            require_keys_eq!(
+                *holder_account.owner,
+                token::ID,
+                ErrorCode::InvalidTokenAccount
+            );
+            set_token_account_frozen(
+                campaign,
+                ctx.accounts.token_mint.to_account_info(),
+                holder_account.clone(),
+                ctx.accounts.token_program.to_account_info(),
+                true,
+            )?;
+        }
+
+        // Wallets listen for the first one to grey the token out
+        emit_logged!(TokensInvalidatedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            token_mint: campaign.token_mint,
+            first,
+            accounts_frozen: ctx.remaining_accounts.len() as u32,
+        });
+
+        Ok(())
+    }
+
+    // Spread one deposit over several campaigns in a single transaction. Each
+    // leg's accounts come in as remaining accounts (see BASKET_LEG_ACCOUNTS)
+    // and either every leg lands or none do. Legs must use plain curve tokens;
+    // ticket and governance campaigns need accounts a leg doesn't carry.
+    pub fn contribute_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ContributeBasket<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let contributor_key = accounts.contributor.key();
+        require!(!amounts.is_empty(), ErrorCode::ZeroAmount);
+        require!(
+            ctx.remaining_accounts.len() == amounts.len() * BASKET_LEG_ACCOUNTS,
+            ErrorCode::InvalidBasketLeg
+        );
+
+        let mut seen: Vec<Pubkey> = Vec::with_capacity(amounts.len());
+        let mut total_amount: u64 = 0;
+        let mut total_fee: u64 = 0;
+        for (leg, &amount) in ctx
+            .remaining_accounts
+            .chunks(BASKET_LEG_ACCOUNTS)
+            .zip(amounts.iter())
+        {
+            let [campaign_info, mint_info, token_account_info, record_info, history_info, feed_info] =
+                leg
+            else {
+                return err!(ErrorCode::InvalidBasketLeg);
+            };
+
+            // Two legs on one campaign would each write back their own copy of it
+            require!(
+                !seen.contains(campaign_info.key),
+                ErrorCode::InvalidBasketLeg
+            );
+            seen.push(campaign_info.key());
+
+            let mut campaign = Account::<Campaign>::try_from(campaign_info)?;
+            require!(
+                campaign.ticket_mint == Pubkey::default()
+                    && campaign.governance_mint == Pubkey::default(),
+                ErrorCode::BasketUnsupported
+            );
+            require_keys_eq!(
+                mint_info.key(),
+                campaign.token_mint,
+                ErrorCode::InvalidTokenMint
+            );
+            {
+                require_keys_eq!(
+                    *token_account_info.owner,
+                    token::ID,
+                    ErrorCode::InvalidTokenAccount
+                );
+                let data = token_account_info.try_borrow_data()?;
+                let token_account = spl_token::state::Account::unpack(&data)?;
+                require!(
+                    token_account.owner == contributor_key
+                        && token_account.mint == campaign.token_mint,
+                    ErrorCode::InvalidTokenAccount
+                );
+            }
+            let history_loader = AccountLoader::<PriceHistory>::try_from(history_info)?;
+            let mut price_history = history_loader.load_mut()?;
+            require_keys_eq!(
+                price_history.campaign,
+                campaign.key(),
+                ErrorCode::InvalidBasketLeg
+            );
+            let mut price_feed = if campaign.price_feed == Pubkey::default() {
+                require_keys_eq!(feed_info.key(), crate::ID, ErrorCode::InvalidBasketLeg);
+                None
+            } else {
+                require_keys_eq!(
+                    feed_info.key(),
+                    campaign.price_feed,
+                    ErrorCode::InvalidBasketLeg
+                );
+                Some(Box::new(Account::<PriceFeed>::try_from(feed_info)?))
+            };
+            check_region_access(
+                &campaign,
+                accounts.region_attestation.as_ref(),
+                contributor_key,
+            )?;
+
+            let (record_key, record_bump) = Pubkey::find_program_address(
+                &[
+                    b"contribution",
+                    campaign.key().as_ref(),
+                    contributor_key.as_ref(),
+                ],
+                &crate::ID,
+            );
+            require_keys_eq!(record_info.key(), record_key, ErrorCode::InvalidBasketLeg);
+            let mut record = if record_info.data_is_empty() {
+                let space = 8 + ContributionRecord::INIT_SPACE;
+                let campaign_key = campaign.key();
+                anchor_lang::system_program::create_account(
+                    CpiContext::new_with_signer(
+                        accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::CreateAccount {
+                            from: accounts.contributor.to_account_info(),
+                            to: record_info.clone(),
+                        },
+                        &[&[
+                            b"contribution",
+                            campaign_key.as_ref(),
+                            contributor_key.as_ref(),
+                            &[record_bump],
+                        ]],
+                    ),
+                    Rent::get()?.minimum_balance(space),
+                    space as u64,
+                    &crate::ID,
+                )?;
+                Box::new(Account::<ContributionRecord>::try_from_unchecked(
+                    record_info,
+                )?)
+            } else {
+                Box::new(Account::<ContributionRecord>::try_from(record_info)?)
+            };
+
+            // Whatever the hard cap leaves no room for stays with the contributor
+            let amount = refund_excess(
+                &campaign,
+                None,
+                &accounts.contributor.to_account_info(),
+                amount,
+            )?;
+            require!(amount > 0, ErrorCode::HardCapExceeded);
+
+            let fee = collect_platform_fee(
+                PlatformFee {
+                    config: &accounts.global_config,
+                    campaign: &mut campaign,
+                    payer: accounts.contributor.to_account_info(),
+                    treasury: accounts.treasury.to_account_info(),
+                    insurance_pool: accounts.insurance_pool.as_mut(),
+                    frontend: None,
+                    frontend_payout: None,
+                    system_program: accounts.system_program.to_account_info(),
+                },
+                amount,
+            )?;
+            let net_amount = amount - fee.total;
+
+            let first_contribution = record.contributor == Pubkey::default();
+            let opens_position = record.sol_contributed == 0;
+            let tokens_to_mint = buy_from_curve(
+                CurvePurchase {
+                    campaign: &mut campaign,
+                    token_mint: mint_info.clone(),
+                    recipient_token_account: token_account_info.clone(),
+                    contribution_record: Some(&mut record),
+                    record_bump,
+                    price_history: &mut price_history,
+                    price_feed: price_feed.as_deref_mut(),
+                    ticket: None,
+                    token_program: accounts.token_program.to_account_info(),
+                    fee_paid: fee.total,
+                    from_queue: false,
+                    tier_bonus_bps: None,
+                    sponsored: 0,
+                    global_config: &accounts.global_config,
+                    denylist_entry: accounts.denylist_entry.as_ref(),
+                },
+                contributor_key,
+                net_amount,
+            )?;
+            update_backer_profile(&accounts.backer_profile, |profile| {
+                profile.record_contribution(first_contribution, opens_position, amount)
+            })?;
+
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: accounts.contributor.to_account_info(),
+                        to: campaign_info.clone(),
+                    },
+                ),
+                net_amount,
+            )?;
+
+            emit_logged!(ContributionEvent {
+                schema_version: EVENT_SCHEMA_VERSION,
+                campaign: campaign.key(),
+                contributor: contributor_key,
+                sol_amount: net_amount,
+                token_amount: tokens_to_mint,
+                new_total: campaign.raised_amount,
+                fee_amount: fee.total,
+                reference: None,
+            });
+            assert_invariants(&campaign, Some(mint_info.clone()))?;
+
+            // These were loaded by hand, so nothing else writes them back (the
+            // zero-copy price history is written in place)
+            campaign.exit(&crate::ID)?;
+            record.exit(&crate::ID)?;
+            if let Some(feed) = &price_feed {
+                feed.exit(&crate::ID)?;
+            }
+
+            total_amount = total_amount
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+            total_fee = total_fee
+                .checked_add(fee.total)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        emit_logged!(BasketContributionEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            contributor: contributor_key,
+            campaigns: seen,
+            total_amount,
+            total_fee,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_insurance_pool(
+        ctx: Context<InitializeInsurancePool>,
+        fee_share_bps: u16,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+        require!(
+            fee_share_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidFeeShare
+        );
+        config.insurance_fee_share_bps = fee_share_bps;
+
+        let pool = &mut ctx.accounts.insurance_pool;
+        pool.accrued = 0;
+        pool.reserved = 0;
+        pool.paid_out = 0;
+        pool.bump = ctx.bumps.insurance_pool;
+        emit_config_updated(ctx.accounts.admin.key(), "insurance_pool");
+        Ok(())
+    }
+
+    pub fn set_insurance_fee_share(
+        ctx: Context<UpdateGlobalConfig>,
+        fee_share_bps: u16,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.global_config;
+        require!(
+            config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+        require!(
+            fee_share_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidFeeShare
+        );
+        config.insurance_fee_share_bps = fee_share_bps;
+        emit_config_updated(ctx.accounts.admin.key(), "insurance_fee_share");
+        Ok(())
+    }
+
+    // Governance (the config admin, usually an SPL Governance account) rules a
+    // campaign fraudulent and sets aside `compensation` from the pool. The
+    // claims root commits to hash(contributor, basis) leaves, paired like the
+    // airdrop tree; each contributor is paid compensation * basis / total_basis.
+    pub fn rule_campaign_fraudulent(
+        ctx: Context<RuleCampaignFraudulent>,
+        claims_root: [u8; 32],
+        total_basis: u64,
+        compensation: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+        require!(claims_root != [0; 32], ErrorCode::InvalidMerkleProof);
+        require!(total_basis > 0 && compensation > 0, ErrorCode::ZeroAmount);
+
+        let pool = &mut ctx.accounts.insurance_pool;
+        require!(
+            compensation <= pool.available()?,
+            ErrorCode::InsufficientInsuranceFunds
+        );
+        pool.reserved = pool
+            .reserved
+            .checked_add(compensation)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let ruling = &mut ctx.accounts.ruling;
+        ruling.campaign = ctx.accounts.campaign.key();
+        ruling.claims_root = claims_root;
+        ruling.total_basis = total_basis;
+        ruling.claimed_basis = 0;
+        ruling.compensation = compensation;
+        ruling.paid_out = 0;
+        ruling.ruled_at = Clock::get()?.unix_timestamp;
+        ruling.bump = ctx.bumps.ruling;
+
+        emit_logged!(FraudRulingEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: ruling.campaign,
+            claims_root,
+            total_basis,
+            compensation,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim_insurance(
+        ctx: Context<ClaimInsurance>,
+        basis: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(basis > 0, ErrorCode::ZeroAmount);
+
+        let ruling = &mut ctx.accounts.ruling;
+        let claimant = ctx.accounts.claimant.key();
+        let leaf = hashv(&[claimant.as_ref(), &basis.to_le_bytes()]).to_bytes();
+        require!(
+            verify_merkle_proof(&proof, ruling.claims_root, leaf),
+            ErrorCode::InvalidMerkleProof
+        );
+
+        ruling.claimed_basis = ruling
+            .claimed_basis
+            .checked_add(basis)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            ruling.claimed_basis <= ruling.total_basis,
+            ErrorCode::InsuranceClaimsExhausted
+        );
+
+        let payout = (ruling.compensation as u128)
+            .checked_mul(basis as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(ruling.total_basis as u128)
+            .ok_or(ErrorCode::MathOverflow)? as u64;
+        ruling.paid_out = ruling
+            .paid_out
+            .checked_add(payout)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let pool = &mut ctx.accounts.insurance_pool;
+        pool.reserved = pool
+            .reserved
+            .checked_sub(payout)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool.paid_out = pool
+            .paid_out
+            .checked_add(payout)
+            .ok_or(ErrorCode::MathOverflow)?;
+        **pool.to_account_info().try_borrow_mut_lamports()? -= payout;
+        **ctx
+            .accounts
+            .claimant
+            .to_account_info()
+            .try_borrow_mut_lamports()? += payout;
+
+        let receipt = &mut ctx.accounts.claim_receipt;
+        receipt.ruling = ruling.key();
+        receipt.claimant = claimant;
+        receipt.basis = basis;
+        receipt.payout = payout;
+        receipt.bump = ctx.bumps.claim_receipt;
+
+        emit_logged!(InsuranceClaimEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: ruling.campaign,
+            claimant,
+            basis,
+            payout,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless garbage collection of campaigns the reviewer never got to
+    // or nobody ever backed; both the campaign and its price history close to
+    // the creator, less the keeper's tip, once a sponsor match and queued
+    // deposits (as remaining deposit and depositor pairs) have gone back
+    pub fn expire_stale_campaign<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExpireStaleCampaign<'info>>,
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        require!(
+            campaign.is_stale(Clock::get()?.unix_timestamp),
+            ErrorCode::CampaignNotStale
+        );
+        return_escrows(
+            campaign,
+            ctx.accounts.match_commitment.as_ref(),
+            ctx.accounts.sponsor.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        // Tokens handed out any other way (airdrops, exchange claims) count as activity
+        require!(
+            ctx.accounts.token_mint.supply == 0,
+            ErrorCode::CampaignNotStale
+        );
+
+        let reclaimed = campaign
+            .to_account_info()
+            .lamports()
+            .checked_add(ctx.accounts.price_history.to_account_info().lamports())
+            .ok_or(ErrorCode::MathOverflow)?;
+        let keeper_tip = calculate_bps_share(reclaimed, STALE_CAMPAIGN_KEEPER_TIP_BPS)?;
+        **campaign.to_account_info().try_borrow_mut_lamports()? -= keeper_tip;
+        **ctx
+            .accounts
+            .keeper
+            .to_account_info()
+            .try_borrow_mut_lamports()? += keeper_tip;
+
+        let profile = &mut ctx.accounts.creator_profile;
+        profile.active_campaigns = profile.active_campaigns.saturating_sub(1);
+
+        emit_logged!(StaleCampaignExpiredEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            creator: campaign.creator,
+            keeper: ctx.accounts.keeper.key(),
+            keeper_tip,
+        });
+
+        Ok(())
+    }
+
+    // Hand the creator's withdraw, metadata, pause and update powers to a
+    // governance PDA of a realm whose community mint is the campaign token.
+    // One way: the founder team can't take them back.
+    pub fn transfer_authority_to_governance(
+        ctx: Context<TransferCampaignAuthority>,
+        governance_seed: Pubkey,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let campaign = &mut accounts.campaign;
+
+        require!(
+            campaign.creator == accounts.creator.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        require!(
+            campaign.governance == Pubkey::default(),
+            ErrorCode::AuthorityHeldByGovernance
+        );
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+
+        // Realm layout: account type byte, then the community mint
+        require!(
+            is_realm(&accounts.realm)?,
+            ErrorCode::InvalidGovernanceAccount
+        );
+        let realm_data = accounts.realm.try_borrow_data()?;
+        require!(
+            realm_data[1..33] == campaign.token_mint.to_bytes(),
+            ErrorCode::InvalidGovernanceAccount
+        );
+        drop(realm_data);
+
+        let (expected, _) = Pubkey::find_program_address(
+            &[
+                b"account-governance",
+                accounts.realm.key().as_ref(),
+                governance_seed.as_ref(),
+            ],
+            &accounts.governance_program.key(),
+        );
+        require_keys_eq!(
+            accounts.governance.key(),
+            expected,
+            ErrorCode::InvalidGovernanceAccount
+        );
+
+        campaign.governance = expected;
+
+        emit_logged!(CampaignAuthorityTransferredEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            previous_authority: accounts.creator.key(),
+            governance: expected,
+            realm: accounts.realm.key(),
+        });
+
+        Ok(())
+    }
+
+    // Emits the event registry for indexers matching logs to decoders
+    pub fn get_event_schema(_ctx: Context<GetEventSchema>) -> Result<()> {
+        // A registry rather than an outcome, so it gets no log marker
+        emit!(EventSchemaEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            events: event_schema(),
+        });
+        Ok(())
+    }
+
+    // Permissionless health check: every mint draws from a supply bucket
+    // first, so the buckets' minted counts bound the mint's supply from above,
+    // and less what has been drawn but not yet delivered (tier bonuses, an
+    // auction lot) from below. Holders burning outside the program read as a
+    // shortfall. Returns the signed distance from the band, zero when supply
+    // is inside it.
+    pub fn audit_curve(ctx: Context<AuditCurve>) -> Result<i64> {
+        let campaign = &ctx.accounts.campaign;
+
+        let expected_max = [
+            campaign.curve_minted,
+            campaign.creator_minted,
+            campaign.liquidity_minted,
+            campaign.airdrop_minted,
+        ]
+        .iter()
+        .map(|&minted| minted as u128)
+        .sum::<u128>();
+        let expected_min = expected_max.saturating_sub(campaign.curve_undelivered as u128);
+
+        let actual_supply = ctx.accounts.token_mint.supply as u128;
+        let discrepancy = if actual_supply < expected_min {
+            -i64::try_from(expected_min - actual_supply).map_err(|_| ErrorCode::MathOverflow)?
+        } else if actual_supply > expected_max {
+            i64::try_from(actual_supply - expected_max).map_err(|_| ErrorCode::MathOverflow)?
+        } else {
+            0
+        };
+
+        if discrepancy != 0 {
+            emit_logged!(CurveDiscrepancyEvent {
+                schema_version: EVENT_SCHEMA_VERSION,
+                campaign: campaign.key(),
+                expected_min: u64::try_from(expected_min).unwrap_or(u64::MAX),
+                expected_max: u64::try_from(expected_max).unwrap_or(u64::MAX),
+                actual_supply: actual_supply as u64,
+                discrepancy,
+            });
+        }
+
+        Ok(discrepancy)
+    }
+
+    // Executes the distribution committed at initialization in one go: the
+    // liquidity share is minted to the chosen account, the treasury share into
+    // a vesting vault, and the burn share is never minted at all
+    pub fn finalize_distribution(ctx: Context<FinalizeDistribution>) -> Result<()> {
+        let accounts = ctx.accounts;
+        let campaign = &mut accounts.campaign;
+
+        campaign.authorize(accounts.authority.key(), ROLE_WITHDRAW)?;
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        require!(
+            campaign.distribution.is_configured(),
+            ErrorCode::DistributionNotConfigured
+        );
+        require!(
+            !campaign.distribution_finalized,
+            ErrorCode::DistributionAlreadyFinalized
+        );
+
+        let unsold = campaign.unminted(SupplyBucket::Curve);
+        let liquidity = calculate_bps_share(unsold, campaign.distribution.liquidity_bps)?;
+        let treasury = calculate_bps_share(unsold, campaign.distribution.treasury_bps)?;
+        // Rounding dust goes to the burn
+        let burned = unsold - liquidity - treasury;
+
+        let seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        for (to, amount) in [
+            (accounts.liquidity_account.to_account_info(), liquidity),
+            (accounts.treasury_vault.to_account_info(), treasury),
+        ] {
+            if amount > 0 {
+                token::mint_to(
+                    CpiContext::new_with_signer(
+                        accounts.token_program.to_account_info(),
+                        token::MintTo {
+                            mint: accounts.token_mint.to_account_info(),
+                            to,
+                            authority: campaign.to_account_info(),
+                        },
+                        &[&seeds[..]],
+                    ),
+                    amount,
+                )?;
+            }
+        }
+
+        campaign.draw_supply(SupplyBucket::Curve, liquidity + treasury)?;
+        campaign.total_supply -= burned;
+        campaign.curve_supply -= burned;
+        campaign.distribution_finalized = true;
+        campaign.treasury_vesting_start = Clock::get()?.unix_timestamp;
+        campaign.treasury_vesting_total = treasury;
+
+        emit_logged!(DistributionFinalizedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            unsold,
+            liquidity,
+            treasury,
+            burned,
+        });
+
+        Ok(())
+    }
+
+    pub fn release_vested_treasury(ctx: Context<ReleaseVestedTreasury>) -> Result<()> {
+        let accounts = ctx.accounts;
+        let campaign = &mut accounts.campaign;
+
+        campaign.authorize(accounts.authority.key(), ROLE_WITHDRAW)?;
+        require!(
+            campaign.distribution_finalized,
+            ErrorCode::DistributionNotConfigured
+        );
+
+        let elapsed = Clock::get()?
+            .unix_timestamp
+            .saturating_sub(campaign.treasury_vesting_start)
+            .max(0);
+        let duration = campaign.distribution.treasury_vesting_secs;
+        let vested = if elapsed >= duration {
+            campaign.treasury_vesting_total
+        } else {
+            ((campaign.treasury_vesting_total as u128 * elapsed as u128) / duration as u128) as u64
+        };
+        // Contributor milestones gate the vest tranche by tranche
+        let vested = if campaign.contributor_milestone_count > 0 {
+            ((vested as u128 * campaign.contributor_milestones_reached as u128)
+                / campaign.contributor_milestone_count as u128) as u64
+        } else {
+            vested
+        };
+        let amount = vested.saturating_sub(campaign.treasury_vesting_released);
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        campaign.treasury_vesting_released += amount;
+
+        let seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        token::transfer(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: accounts.treasury_vault.to_account_info(),
+                    to: accounts.destination.to_account_info(),
+                    authority: campaign.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        emit_logged!(VestedTreasuryReleasedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            amount,
+            remaining: campaign.treasury_vesting_total - campaign.treasury_vesting_released,
+        });
+
+        Ok(())
+    }
+
+    // The creator puts the next withdrawal up for sign-off, snapshotting the top
+    // backers who have to approve it so later shifts in the ranking can't change
+    // the set mid-vote. Proposing again replaces the amount and starts over.
+    pub fn propose_withdrawal(ctx: Context<ProposeWithdrawal>, amount: u64) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        campaign.authorize(ctx.accounts.authority.key(), ROLE_WITHDRAW)?;
+        require!(
+            campaign.needs_withdrawal_approval(amount),
+            ErrorCode::WithdrawalApprovalsDisabled
+        );
+
+        let approval = &mut ctx.accounts.withdrawal_approval;
+        approval.campaign = campaign.key();
+        approval.withdrawal_index = campaign.withdrawal_count;
+        approval.amount = amount;
+        approval.required = [Pubkey::default(); WITHDRAWAL_APPROVERS];
+        approval.required_count = 0;
+        approval.approved_mask = 0;
+        for entry in ctx
+            .accounts
+            .price_history
+            .load()?
+            .top_backers
+            .iter()
+            .filter(|entry| entry.value > 0)
+            .take(WITHDRAWAL_APPROVERS)
+        {
+            let slot = approval.required_count as usize;
+            approval.required[slot] = entry.key;
+            approval.required_count += 1;
+        }
+        approval.bump = ctx.bumps.withdrawal_approval;
+
+        emit_logged!(WithdrawalProposedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            withdrawal_index: approval.withdrawal_index,
+            amount,
+            approvers: approval.required[..approval.required_count as usize].to_vec(),
+        });
+
+        Ok(())
+    }
+
+    // Withdraws a proposal before it's used, returning its rent
+    pub fn cancel_withdrawal_proposal(ctx: Context<CancelWithdrawalProposal>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        campaign.authorize(ctx.accounts.authority.key(), ROLE_WITHDRAW)?;
+
+        emit_logged!(WithdrawalProposalCancelledEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            withdrawal_index: ctx.accounts.withdrawal_approval.withdrawal_index,
+            amount: ctx.accounts.withdrawal_approval.amount,
+        });
+
+        Ok(())
+    }
+
+    // One of the top backers snapshotted by the proposal signs off on it. The
+    // amount is restated so a re-proposal can't reuse approvals of another one.
+    pub fn approve_withdrawal(ctx: Context<ApproveWithdrawal>, amount: u64) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let approver = ctx.accounts.approver.key();
+        let approval = &mut ctx.accounts.withdrawal_approval;
+        require!(
+            approval.amount == amount,
+            ErrorCode::WithdrawalApprovalMismatch
+        );
+
+        let slot = approval.required[..approval.required_count as usize]
+            .iter()
+            .position(|key| *key == approver)
+            .ok_or(ErrorCode::NotTopBacker)?;
+        approval.approved_mask |= 1 << slot;
+
+        emit_logged!(WithdrawalApprovedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            withdrawal_index: approval.withdrawal_index,
+            approver,
+            amount,
+            complete: approval.is_complete(),
+        });
+
+        Ok(())
+    }
+
+    // Buys on the curve with the tokens minted straight into the campaign's
+    // staking vault, so backers locking anyway skip the ATA and a second transaction
+    pub fn contribute_and_stake(ctx: Context<ContributeAndStake>, amount: u64) -> Result<()> {
+        let contributor_key = ctx.accounts.contributor.key();
+        let accounts = ctx.accounts;
+        require!(
+            !accounts.campaign.freeze_during_raise
+                && !accounts.campaign.donation_mode
+                && accounts.campaign.ticket_mint == Pubkey::default()
+                && accounts.campaign.governance_mint == Pubkey::default(),
+            ErrorCode::StakingUnsupported
+        );
+        check_region_access(
+            &accounts.campaign,
+            accounts.region_attestation.as_ref(),
+            contributor_key,
+        )?;
+        // Whatever the hard cap leaves no room for stays with the contributor
+        let amount = refund_excess(
+            &accounts.campaign,
+            None,
+            &accounts.contributor.to_account_info(),
+            amount,
+        )?;
+        require!(amount > 0, ErrorCode::HardCapExceeded);
+
+        let fee = collect_platform_fee(
+            PlatformFee {
+                config: &accounts.global_config,
+                campaign: &mut accounts.campaign,
+                payer: accounts.contributor.to_account_info(),
+                treasury: accounts.treasury.to_account_info(),
+                insurance_pool: accounts.insurance_pool.as_mut(),
+                frontend: None,
+                frontend_payout: None,
+                system_program: accounts.system_program.to_account_info(),
+            },
+            amount,
+        )?;
+        let net_amount = amount - fee.total;
+        let first_contribution = accounts.contribution_record.contributor == Pubkey::default();
+        let opens_position = accounts.contribution_record.sol_contributed == 0;
+
+        let tokens_to_mint = buy_from_curve(
+            CurvePurchase {
+                campaign: &mut accounts.campaign,
+                token_mint: accounts.token_mint.to_account_info(),
+                recipient_token_account: accounts.stake_vault.to_account_info(),
+                contribution_record: Some(&mut accounts.contribution_record),
+                record_bump: ctx.bumps.contribution_record,
+                price_history: &mut *accounts.price_history.load_mut()?,
+                price_feed: accounts.price_feed.as_deref_mut(),
+                ticket: None,
+                token_program: accounts.token_program.to_account_info(),
+                fee_paid: fee.total,
+                from_queue: false,
+                tier_bonus_bps: None,
+                sponsored: 0,
+                global_config: &accounts.global_config,
+                denylist_entry: accounts.denylist_entry.as_ref(),
+            },
+            contributor_key,
+            net_amount,
+        )?;
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: accounts.contributor.to_account_info(),
+                    to: accounts.campaign.to_account_info(),
+                },
+            ),
+            net_amount,
+        )?;
+
+        let position = &mut accounts.stake_position;
+        let opens_stake = position.amount == 0;
+        position.campaign = accounts.campaign.key();
+        position.staker = contributor_key;
+        position.bump = ctx.bumps.stake_position;
+        position.settle(accounts.campaign.exit_rewards_per_token_q64)?;
+        position.add(tokens_to_mint)?;
+        update_backer_profile(&accounts.backer_profile, |profile| {
+            profile.record_contribution(first_contribution, opens_position, amount)?;
+            profile.record_stake(opens_stake, false);
+            Ok(())
+        })?;
+        accounts.campaign.total_staked = accounts
+            .campaign
+            .total_staked
+            .checked_add(tokens_to_mint)
+            .ok_or(ErrorCode::MathOverflow)?;
+        accounts.campaign.release_held_exit_rewards()?;
+
+        emit_logged!(ContributionEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            contributor: contributor_key,
+            sol_amount: net_amount,
+            token_amount: tokens_to_mint,
+            new_total: accounts.campaign.raised_amount,
+            fee_amount: fee.total,
+            reference: None,
+        });
+        emit_logged!(StakeEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            staker: contributor_key,
+            amount: tokens_to_mint,
+            staked: position.amount,
+        });
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    // Stake tokens already held. Open after graduation too, so exit fees
+    // charged while nobody is staked have someone to go to.
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        validation::nonzero(amount)?;
+        let staker_key = ctx.accounts.staker.key();
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.staker_token_account.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.staker.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let campaign = &mut ctx.accounts.campaign;
+        let position = &mut ctx.accounts.stake_position;
+        let opens_stake = position.amount == 0;
+        position.campaign = campaign.key();
+        position.staker = staker_key;
+        position.bump = ctx.bumps.stake_position;
+        position.settle(campaign.exit_rewards_per_token_q64)?;
+        position.add(amount)?;
+        update_backer_profile(&ctx.accounts.backer_profile, |profile| {
+            profile.record_stake(opens_stake, false);
+            Ok(())
+        })?;
+        campaign.total_staked = campaign
+            .total_staked
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        campaign.release_held_exit_rewards()?;
+
+        emit_logged!(StakeEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            staker: staker_key,
+            amount,
+            staked: position.amount,
+        });
+
+        Ok(())
+    }
+
+    // Tokens can leave at any time. Once the raise is over the exit fees earned
+    // so far stay claimable; leaving during it forfeits them to the stakers who
+    // remain, so staking just for one withdrawal's fee doesn't pay.
+    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        let early = campaign.status != CampaignStatus::Graduated
+            && !campaign.has_failed(Clock::get()?.unix_timestamp);
+
+        let position = &mut ctx.accounts.stake_position;
+        position.settle(campaign.exit_rewards_per_token_q64)?;
+        position.amount = position
+            .amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientStake)?;
+        campaign.total_staked -= amount;
+        let forfeited = if early {
+            std::mem::take(&mut position.rewards_owed)
+        } else {
+            0
+        };
+        if forfeited > 0 {
+            // Already in the pool, so it's handed on rather than added again
+            campaign.exit_rewards_pool -= forfeited;
+            campaign.accrue_exit_fee(forfeited)?;
+        }
+        let closes_stake = position.amount == 0;
+        update_backer_profile(&ctx.accounts.backer_profile, |profile| {
+            profile.record_stake(false, closes_stake);
+            Ok(())
+        })?;
+
+        let seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.stake_vault.to_account_info(),
+                    to: ctx.accounts.staker_token_account.to_account_info(),
+                    authority: campaign.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        emit_logged!(UnstakeEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            staker: position.staker,
+            amount,
+            staked: position.amount,
+            forfeited_rewards: forfeited,
+        });
+
+        Ok(())
+    }
+
+    // Anyone can relay a fact the attestation oracle signed about a campaign.
+    // The signed message binds the campaign and its next attestation nonce, so
+    // a signature can't be replayed onto another campaign or posted twice.
+    pub fn post_attestation(
+        ctx: Context<PostAttestation>,
+        kind: u16,
+        data_hash: [u8; 32],
+    ) -> Result<()> {
+        let oracle = ctx.accounts.global_config.attestation_oracle;
+        require!(
+            oracle != Pubkey::default(),
+            ErrorCode::AttestationOracleNotSet
+        );
+
+        let campaign = &mut ctx.accounts.campaign;
+        let statement = AttestationStatement {
+            campaign: campaign.key(),
+            kind,
+            data_hash,
+            nonce: campaign.attestation_nonce,
+        };
+        let mut message = CAMPAIGN_ATTESTATION_DOMAIN.to_vec();
+        statement.serialize(&mut message)?;
+        verify_ed25519_signature(&ctx.accounts.instructions, &oracle, &message)?;
+
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.campaign = campaign.key();
+        attestation.oracle = oracle;
+        attestation.kind = kind;
+        attestation.data_hash = data_hash;
+        attestation.nonce = statement.nonce;
+        attestation.posted_at = Clock::get()?.unix_timestamp;
+        attestation.bump = ctx.bumps.attestation;
+        campaign.attestation_nonce += 1;
+
+        emit_logged!(AttestationPostedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            oracle,
+            kind,
+            data_hash,
+            nonce: statement.nonce,
+        });
+
+        Ok(())
+    }
+
+    // Anyone (usually the creator) tops up the lamports price cranks are tipped from
+    pub fn fund_price_crank(ctx: Context<FundPriceCrank>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        let crank = &mut ctx.accounts.price_crank;
+        crank.campaign = ctx.accounts.campaign.key();
+        crank.bump = ctx.bumps.price_crank;
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: crank.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit_logged!(PriceCrankFundedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: ctx.accounts.campaign.key(),
+            funder: ctx.accounts.funder.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // Rate-limited get_token_price for keepers keeping charts fresh; the tip
+    // comes from the crank budget and is skipped once that runs dry
+    pub fn crank_price(ctx: Context<CrankPrice>) -> Result<u64> {
+        let campaign = &ctx.accounts.campaign;
+        let crank = &mut ctx.accounts.price_crank;
+        let slot = Clock::get()?.slot;
+        require!(
+            crank.last_slot == 0
+                || slot >= crank.last_slot.saturating_add(PRICE_CRANK_INTERVAL_SLOTS),
+            ErrorCode::PriceCrankTooSoon
+        );
+        crank.last_slot = slot;
+        crank.crank_count += 1;
+
+        let price = calculate_token_price(&campaign.curve, campaign.raised_amount)?;
+        emit_logged!(TokenPriceEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            price,
+            raised_amount: campaign.raised_amount,
+        });
+
+        let info = crank.to_account_info();
+        let spare = info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(info.data_len()));
+        let tip = spare.min(PRICE_CRANK_TIP_LAMPORTS);
+        if tip > 0 {
+            **info.try_borrow_mut_lamports()? -= tip;
+            **ctx
+                .accounts
+                .keeper
+                .to_account_info()
+                .try_borrow_mut_lamports()? += tip;
+        }
+
+        Ok(price)
+    }
+
+    // Pays a staker their share of the exit fees creators have left behind
+    pub fn claim_staking_rewards(ctx: Context<ClaimStakingRewards>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        let position = &mut ctx.accounts.stake_position;
+        position.settle(campaign.exit_rewards_per_token_q64)?;
+
+        let amount = position.rewards_owed;
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        position.rewards_owed = 0;
+        campaign.exit_rewards_pool = campaign
+            .exit_rewards_pool
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InvariantViolated)?;
+
+        **campaign.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx
+            .accounts
+            .staker
+            .to_account_info()
+            .try_borrow_mut_lamports()? += amount;
+
+        emit_logged!(StakingRewardsClaimedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            staker: position.staker,
+            amount,
+        });
+
+        assert_invariants(campaign, None)?;
+
+        Ok(())
+    }
+
+    // The platform's launch calendar splits time into fixed windows, each of
+    // which takes a limited number of campaign launches
+    pub fn initialize_launch_calendar(
+        ctx: Context<InitializeLaunchCalendar>,
+        window_secs: i64,
+        capacity_per_window: u8,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+        require!(
+            window_secs > 0 && capacity_per_window > 0,
+            ErrorCode::InvalidLaunchCalendar
+        );
+
+        let calendar = &mut ctx.accounts.launch_calendar;
+        calendar.window_secs = window_secs;
+        calendar.capacity_per_window = capacity_per_window;
+        calendar.bump = ctx.bumps.launch_calendar;
+        emit_config_updated(ctx.accounts.admin.key(), "launch_calendar");
+        Ok(())
+    }
+
+    // First come, first served. Booking holds the campaign closed (pre-deposits
+    // still open) until anyone calls launch_campaign inside the window.
+    pub fn reserve_launch_slot(ctx: Context<ReserveLaunchSlot>, window_index: u64) -> Result<()> {
+        let calendar = &ctx.accounts.launch_calendar;
+        let campaign = &mut ctx.accounts.campaign;
+        require!(
+            campaign.creator == ctx.accounts.creator.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        require!(
+            !campaign.launch_slot_reserved,
+            ErrorCode::LaunchSlotAlreadyReserved
+        );
+        // A child campaign opens when its parent graduates, not on a schedule
+        require!(
+            campaign.parent_campaign == Pubkey::default(),
+            ErrorCode::InvalidParentCampaign
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < campaign.start_time, ErrorCode::CampaignAlreadyStarted);
+        let window_start = (window_index as i64)
+            .checked_mul(calendar.window_secs)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let window_end = window_start
+            .checked_add(calendar.window_secs)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(window_start > now, ErrorCode::LaunchWindowPassed);
+        require!(window_end <= campaign.end_time, ErrorCode::InvalidStartTime);
+
+        let slot = &mut ctx.accounts.launch_slot;
+        require!(
+            slot.booked < calendar.capacity_per_window,
+            ErrorCode::LaunchSlotFull
+        );
+        slot.window_index = window_index;
+        slot.booked = slot.booked.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        slot.bump = ctx.bumps.launch_slot;
+
+        campaign.launch_window = window_index;
+        campaign.launch_slot_reserved = true;
+        campaign.booked_start_time = campaign.start_time;
+        campaign.start_time = i64::MAX;
+
+        emit_logged!(LaunchSlotReservedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            window_index,
+            window_start,
+            booked: slot.booked,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless, so a booked campaign opens on time whether or not its
+    // creator is around for the window
+    pub fn launch_campaign(ctx: Context<LaunchCampaign>) -> Result<()> {
+        let calendar = &ctx.accounts.launch_calendar;
+        let campaign = &mut ctx.accounts.campaign;
+        require!(campaign.launch_slot_reserved, ErrorCode::NoLaunchSlot);
+        require!(
+            campaign.start_time == i64::MAX,
+            ErrorCode::CampaignAlreadyStarted
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let window_start = i64::try_from(campaign.launch_window)
+            .ok()
+            .and_then(|index| index.checked_mul(calendar.window_secs))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let window_end = window_start
+            .checked_add(calendar.window_secs)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            now >= window_start && now < window_end,
+            ErrorCode::NotInLaunchWindow
+        );
+        campaign.start_time = now;
+
+        emit_logged!(CampaignLaunchedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            window_index: campaign.launch_window,
+            launched_at: now,
+        });
+
+        Ok(())
+    }
+
+    // Gives an unlaunched booking back to its window. The creator can cancel
+    // theirs, which puts the campaign back on the start time it had; anyone
+    // can free the slot of a campaign that failed before launching.
+    pub fn release_launch_slot(ctx: Context<ReleaseLaunchSlot>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        require!(campaign.launch_slot_reserved, ErrorCode::NoLaunchSlot);
+        require!(
+            campaign.start_time == i64::MAX,
+            ErrorCode::CampaignAlreadyStarted
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        if !campaign.has_failed(now) {
+            require!(
+                campaign.creator == ctx.accounts.authority.key(),
+                ErrorCode::NotCampaignCreator
+            );
+            campaign.start_time = campaign.booked_start_time.max(now);
+        }
+        campaign.launch_slot_reserved = false;
+
+        let slot = &mut ctx.accounts.launch_slot;
+        slot.booked = slot
+            .booked
+            .checked_sub(1)
+            .ok_or(ErrorCode::InvariantViolated)?;
+
+        emit_logged!(LaunchSlotReleasedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            window_index: slot.window_index,
+            booked: slot.booked,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless: once the parent campaign has graduated the child opens and
+    // its escrowed deposits convert through process_pre_deposit; if the parent
+    // failed, the child fails with it and depositors cancel for a full refund.
+    // A parent that was closed (rejected or expired before launch) never
+    // graduated, so it counts as failed.
+    pub fn resolve_parent_campaign(ctx: Context<ResolveParentCampaign>) -> Result<()> {
+        let parent_info = ctx.accounts.parent_campaign.to_account_info();
+        let parent = if parent_info.owner == &crate::ID && !parent_info.data_is_empty() {
+            Some(Campaign::try_deserialize(
+                &mut &parent_info.try_borrow_data()?[..],
+            )?)
+        } else {
+            None
+        };
+        let campaign = &mut ctx.accounts.campaign;
+        // A child that failed with its parent keeps its unset start time
+        require!(
+            campaign.start_time == i64::MAX && campaign.status != CampaignStatus::Failed,
+            ErrorCode::ParentCampaignAlreadyResolved
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let parent_succeeded = match &parent {
+            Some(parent) if parent.status == CampaignStatus::Graduated => {
+                campaign.start_time = now;
+                true
+            }
+            Some(parent) if !parent.has_failed(now) => {
+                return err!(ErrorCode::ParentCampaignUnresolved)
+            }
+            _ => {
+                campaign.transition(CampaignStatus::Failed)?;
+                false
+            }
+        };
+
+        emit_logged!(ParentCampaignResolvedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            parent: parent_info.key(),
+            parent_succeeded,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_content_threshold(ctx: Context<CampaignAuthority>, threshold: u64) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.authorize(ctx.accounts.authority.key(), ROLE_POST_UPDATES)?;
+        campaign.content_threshold = threshold;
+        emit_logged!(ContentThresholdSetEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            threshold,
+        });
+        Ok(())
+    }
+
+    // Gated content is stored encrypted off chain; the registry commits to the
+    // payload hash and the key epoch it was encrypted to, so a backer can tell
+    // which re-encryption covers them
+    pub fn post_gated_content(
+        ctx: Context<PostGatedContent>,
+        payload_hash: [u8; 32],
+        key_epoch: u64,
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.authorize(ctx.accounts.authority.key(), ROLE_POST_UPDATES)?;
+        require!(
+            campaign.content_threshold > 0,
+            ErrorCode::ContentGatingDisabled
+        );
+
+        let content = &mut ctx.accounts.gated_content;
+        content.campaign = campaign.key();
+        content.index = campaign.gated_content_count;
+        content.payload_hash = payload_hash;
+        content.key_epoch = key_epoch;
+        content.posted_at = Clock::get()?.unix_timestamp;
+        content.bump = ctx.bumps.gated_content;
+        campaign.gated_content_count += 1;
+
+        emit_logged!(GatedContentPostedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            index: content.index,
+            payload_hash,
+            key_epoch,
+        });
+
+        Ok(())
+    }
+
+    // Backers over the threshold register (or rotate) the key the creator's
+    // content key gets re-encrypted to
+    pub fn register_content_key(
+        ctx: Context<RegisterContentKey>,
+        encryption_key: [u8; 32],
+    ) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        require!(
+            campaign.content_threshold > 0,
+            ErrorCode::ContentGatingDisabled
+        );
+        require!(
+            ctx.accounts.contribution_record.sol_contributed >= campaign.content_threshold,
+            ErrorCode::BelowContentThreshold
+        );
+
+        let registration = &mut ctx.accounts.content_key;
+        registration.campaign = campaign.key();
+        registration.backer = ctx.accounts.backer.key();
+        registration.encryption_key = encryption_key;
+        registration.registered_at = Clock::get()?.unix_timestamp;
+        registration.bump = ctx.bumps.content_key;
+
+        emit_logged!(ContentKeyRegisteredEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            backer: registration.backer,
+            encryption_key,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless: once a backer's remaining contribution drops below the
+    // threshold (a sell or refund lowers it, or the creator raises the bar) their
+    // key is closed, so the creator stops re-encrypting to it
+    pub fn revoke_content_key(ctx: Context<RevokeContentKey>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        require!(
+            campaign.content_threshold == 0
+                || ctx.accounts.contribution_record.sol_contributed < campaign.content_threshold,
+            ErrorCode::ContentKeyStillEligible
+        );
+
+        emit_logged!(ContentKeyRevokedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            backer: ctx.accounts.backer.key(),
+            sol_contributed: ctx.accounts.contribution_record.sol_contributed,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless once a campaign graduates with supply left over. The
+    // remainder is sold as one lot to the highest sealed bid at or above the
+    // closing curve price; campaigns with a distribution plan use that instead.
+    // Ticket campaigns still owe their unminted supply to unredeemed tickets and
+    // secondary curves keep selling it, so neither can auction it. The lot is
+    // drawn from the curve bucket here, so nothing else can mint it meanwhile.
+    pub fn open_closing_auction(ctx: Context<OpenClosingAuction>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        require!(
+            !campaign.distribution.is_configured()
+                && campaign.ticket_mint == Pubkey::default()
+                && campaign.secondary_slope_bps == 0,
+            ErrorCode::ClosingAuctionUnavailable
+        );
+        let supply = campaign.unminted(SupplyBucket::Curve);
+        require!(supply > 0, ErrorCode::ClosingAuctionUnavailable);
+        campaign.draw_supply(SupplyBucket::Curve, supply)?;
+        campaign.curve_undelivered = campaign
+            .curve_undelivered
+            .checked_add(supply)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let auction = &mut ctx.accounts.closing_auction;
+        auction.campaign = campaign.key();
+        auction.supply = supply;
+        auction.reserve_price = campaign.closing_price;
+        auction.bid_end = now + CLOSING_AUCTION_BID_SECS;
+        auction.reveal_end = auction.bid_end + CLOSING_AUCTION_REVEAL_SECS;
+        auction.best_bidder = Pubkey::default();
+        auction.best_price = 0;
+        auction.settled = false;
+        auction.bump = ctx.bumps.closing_auction;
+
+        emit_logged!(ClosingAuctionOpenedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            supply,
+            reserve_price: auction.reserve_price,
+            bid_end: auction.bid_end,
+            reveal_end: auction.reveal_end,
+        });
+
+        Ok(())
+    }
+
+    // The commitment is hash(price per whole token LE, salt, bidder). The
+    // deposit hides the bid only as well as it overshoots it, and must cover
+    // the whole lot at the bid price when revealed.
+    pub fn place_bid(ctx: Context<PlaceBid>, commitment: [u8; 32], deposit: u64) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp < ctx.accounts.closing_auction.bid_end,
+            ErrorCode::AuctionBiddingClosed
+        );
+        validation::nonzero(deposit)?;
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.bidder.to_account_info(),
+                    to: ctx.accounts.closing_bid.to_account_info(),
+                },
+            ),
+            deposit,
+        )?;
+
+        let bid = &mut ctx.accounts.closing_bid;
+        bid.auction = ctx.accounts.closing_auction.key();
+        bid.bidder = ctx.accounts.bidder.key();
+        bid.commitment = commitment;
+        bid.deposit = deposit;
+        bid.price = 0;
+        bid.bump = ctx.bumps.closing_bid;
+
+        emit_logged!(ClosingBidPlacedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            auction: bid.auction,
+            bidder: bid.bidder,
+            deposit,
+        });
+
+        Ok(())
+    }
+
+    pub fn reveal_bid(ctx: Context<RevealBid>, price: u64, salt: [u8; 32]) -> Result<()> {
+        let auction = &mut ctx.accounts.closing_auction;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= auction.bid_end && now < auction.reveal_end,
+            ErrorCode::AuctionNotRevealing
+        );
+
+        let bid = &mut ctx.accounts.closing_bid;
+        require!(bid.price == 0, ErrorCode::BidAlreadyRevealed);
+        let commitment = hashv(&[&price.to_le_bytes(), &salt, bid.bidder.as_ref()]).to_bytes();
+        require!(
+            commitment == bid.commitment,
+            ErrorCode::BidCommitmentMismatch
+        );
+        require!(price >= auction.reserve_price, ErrorCode::BidBelowReserve);
+        require!(
+            auction.lot_cost(price)? <= bid.deposit,
+            ErrorCode::BidDepositTooSmall
+        );
+        bid.price = price;
+
+        // Ties go to whoever revealed first
+        if price > auction.best_price {
+            auction.best_price = price;
+            auction.best_bidder = bid.bidder;
+        }
+
+        emit_logged!(ClosingBidRevealedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            auction: auction.key(),
+            bidder: bid.bidder,
+            price,
+        });
+
+        Ok(())
+    }
+
+    // Mints the lot to the winner and moves their payment into the campaign
+    // vault. Without a valid bid the lot goes back to the curve bucket, as it
+    // does when the winner took their deposit back after the settle window.
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        let accounts = ctx.accounts;
+        let auction = &mut accounts.closing_auction;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= auction.reveal_end, ErrorCode::AuctionNotEnded);
+        require!(!auction.settled, ErrorCode::AuctionAlreadySettled);
+        auction.settled = true;
+        accounts.campaign.curve_undelivered = accounts
+            .campaign
+            .curve_undelivered
+            .saturating_sub(auction.supply);
+
+        let winner = match (
+            accounts.winning_bid.as_mut(),
+            accounts.winner_token_account.as_ref(),
+        ) {
+            _ if auction.best_bidder == Pubkey::default() => None,
+            (Some(bid), Some(winner_token_account)) => Some((bid, winner_token_account)),
+            _ if auction.settle_expired(now) => None,
+            _ => return err!(ErrorCode::MissingWinningBid),
+        };
+
+        let mut proceeds = 0;
+        if let Some((bid, winner_token_account)) = winner {
+            require_keys_eq!(
+                bid.bidder,
+                auction.best_bidder,
+                ErrorCode::MissingWinningBid
+            );
+            require_keys_eq!(
+                winner_token_account.owner,
+                auction.best_bidder,
+                ErrorCode::InvalidTokenAccount
+            );
+
+            let campaign = &mut accounts.campaign;
+            proceeds = auction.lot_cost(auction.best_price)?;
+            bid.deposit -= proceeds;
+            **bid.to_account_info().try_borrow_mut_lamports()? -= proceeds;
+            **campaign.to_account_info().try_borrow_mut_lamports()? += proceeds;
+            campaign.raised_amount = campaign
+                .raised_amount
+                .checked_add(proceeds)
+                .ok_or(ErrorCode::MathOverflow)?;
+            campaign.withdrawable_balance = campaign
+                .withdrawable_balance
+                .checked_add(proceeds)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            let seeds = &[
+                b"campaign",
+                campaign.creator.as_ref(),
+                campaign.name_hash.as_ref(),
+                &[campaign.bump],
+            ];
+            token::mint_to(
+                CpiContext::new_with_signer(
+                    accounts.token_program.to_account_info(),
+                    token::MintTo {
+                        mint: accounts.token_mint.to_account_info(),
+                        to: winner_token_account.to_account_info(),
+                        authority: campaign.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                auction.supply,
+            )?;
+        } else {
+            accounts
+                .campaign
+                .release_supply(SupplyBucket::Curve, auction.supply);
+        }
+
+        emit_logged!(ClosingAuctionSettledEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            winner: auction.best_bidder,
+            price: auction.best_price,
+            supply: auction.supply,
+            proceeds,
+        });
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    // Losing, unrevealed and leftover winning deposits come back after
+    // settlement, and every deposit does once the settle window has passed
+    pub fn reclaim_bid(ctx: Context<ReclaimBid>) -> Result<()> {
+        let auction = &ctx.accounts.closing_auction;
+        require!(
+            auction.settled || auction.settle_expired(Clock::get()?.unix_timestamp),
+            ErrorCode::AuctionNotEnded
+        );
+        emit_logged!(ClosingBidReclaimedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            auction: auction.key(),
+            bidder: ctx.accounts.bidder.key(),
+            deposit: ctx.accounts.closing_bid.deposit,
+        });
+        Ok(())
+    }
+
+    // Opt-in portfolio account; contribute, sell and refund keep it current when passed
+    pub fn initialize_backer_profile(ctx: Context<InitializeBackerProfile>) -> Result<()> {
+        let profile = &mut ctx.accounts.backer_profile;
+        profile.wallet = ctx.accounts.wallet.key();
+        profile.bump = ctx.bumps.backer_profile;
+        emit_logged!(BackerProfileInitializedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            wallet: profile.wallet,
+        });
+        Ok(())
+    }
+
+    // Refund a failed campaign position straight into another live campaign:
+    // the old units are burned at cost and the payout is contributed to the
+    // target as if the contributor had sent it, with the same minimum, fee,
+    // hard cap and portfolio bookkeeping as contribute
+    pub fn redirect_refund(
+        ctx: Context<RedirectRefund>,
+        amount: u64,
+        min_tokens_out: u64,
+    ) -> Result<()> {
+        let contributor_key = ctx.accounts.contributor.key();
+        let accounts = ctx.accounts;
+
+        require_keys_neq!(
+            accounts.campaign.key(),
+            accounts.target_campaign.key(),
+            ErrorCode::InvalidRedirectTarget
+        );
+        // Governance and ticket mints need accounts this path doesn't carry
+        require!(
+            accounts.target_campaign.governance_mint == Pubkey::default()
+                && accounts.target_campaign.ticket_mint == Pubkey::default(),
+            ErrorCode::InvalidRedirectTarget
+        );
+        require!(
+            accounts.campaign.governance_mint == Pubkey::default(),
+            ErrorCode::GovernanceUnsupported
+        );
+        check_region_access(
+            &accounts.target_campaign,
+            accounts.region_attestation.as_ref(),
+            contributor_key,
+        )?;
+
+        let (refund, payout) = burn_for_refund(
+            RefundBurn {
+                campaign: &mut accounts.campaign,
+                record: &mut accounts.contribution_record,
+                mint: accounts.mint.to_account_info(),
+                contributor_token_account: accounts.contributor_token_account.to_account_info(),
+                contributor: accounts.contributor.to_account_info(),
+                destination: accounts.contributor.to_account_info(),
+                token_program: accounts.token_program.to_account_info(),
+            },
+            amount,
+        )?;
+        let closes_position = accounts.contribution_record.sol_contributed == 0;
+        update_backer_profile(&accounts.backer_profile, |profile| {
+            profile.record_exit(refund, payout, closes_position)
+        })?;
+
+        // From here the payout is an ordinary contribution from the contributor's wallet
+        let paid = refund_excess(
+            &accounts.target_campaign,
+            None,
+            &accounts.contributor.to_account_info(),
+            payout,
+        )?;
+        require!(paid > 0, ErrorCode::HardCapExceeded);
+        let first_contribution =
+            accounts.target_contribution_record.contributor == Pubkey::default();
+        let opens_position = accounts.target_contribution_record.sol_contributed == 0;
+
+        let fee = collect_platform_fee(
+            PlatformFee {
+                config: &accounts.global_config,
+                campaign: &mut accounts.target_campaign,
+                payer: accounts.contributor.to_account_info(),
+                treasury: accounts.treasury.to_account_info(),
+                insurance_pool: accounts.insurance_pool.as_mut(),
+                frontend: None,
+                frontend_payout: None,
+                system_program: accounts.system_program.to_account_info(),
+            },
+            paid,
+        )?;
+        let filled = paid - fee.total;
+
+        let tokens_minted = buy_from_curve(
+            CurvePurchase {
+                campaign: &mut accounts.target_campaign,
+                token_mint: accounts.target_token_mint.to_account_info(),
+                recipient_token_account: accounts.target_token_account.to_account_info(),
+                contribution_record: Some(&mut accounts.target_contribution_record),
+                record_bump: ctx.bumps.target_contribution_record,
+                price_history: &mut *accounts.target_price_history.load_mut()?,
+                price_feed: accounts.target_price_feed.as_deref_mut(),
+                ticket: None,
+                token_program: accounts.token_program.to_account_info(),
+                fee_paid: fee.total,
+                from_queue: false,
+                tier_bonus_bps: None,
+                sponsored: 0,
+                global_config: &accounts.global_config,
+                denylist_entry: accounts.denylist_entry.as_ref(),
+            },
+            contributor_key,
+            filled,
+        )?;
+        require!(tokens_minted >= min_tokens_out, ErrorCode::SlippageExceeded);
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: accounts.contributor.to_account_info(),
+                    to: accounts.target_campaign.to_account_info(),
+                },
+            ),
+            filled,
+        )?;
+        update_backer_profile(&accounts.backer_profile, |profile| {
+            profile.record_contribution(first_contribution, opens_position, paid)
+        })?;
+
+        emit_logged!(RefundEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            contributor: contributor_key,
+            burned: amount,
+            sol_amount: payout,
+        });
+        emit_logged!(ContributionEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.target_campaign.key(),
+            contributor: contributor_key,
+            sol_amount: filled,
+            token_amount: tokens_minted,
+            new_total: accounts.target_campaign.raised_amount,
+            fee_amount: fee.total,
+            reference: None,
+        });
+
+        assert_invariants(&accounts.campaign, None)?;
+        assert_invariants(
+            &accounts.target_campaign,
+            Some(accounts.target_token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    // Points a revenue source (an external program's PDA or a wallet) at a
+    // distributor for the campaign's token holders. The platform's attestation
+    // oracle publishes the holder balance snapshots each tranche is claimed
+    // against. Passing a mint and vault makes it an SPL stream paid in that mint.
+    pub fn register_revenue_stream(
+        ctx: Context<RegisterRevenueStream>,
+        source: Pubkey,
+    ) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        campaign.authorize(ctx.accounts.authority.key(), ROLE_WITHDRAW)?;
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+
+        let (revenue_mint, vault) = match (&ctx.accounts.revenue_mint, &ctx.accounts.revenue_vault)
+        {
+            (Some(mint), Some(vault)) => (mint.key(), vault.key()),
+            (None, None) => (Pubkey::default(), Pubkey::default()),
+            _ => return err!(ErrorCode::InvalidRevenueAccounts),
+        };
+
+        let stream = &mut ctx.accounts.revenue_stream;
+        stream.campaign = campaign.key();
+        stream.source = source;
+        stream.revenue_mint = revenue_mint;
+        stream.vault = vault;
+        stream.pending = 0;
+        stream.total_deposited = 0;
+        stream.total_claimed = 0;
+        stream.epoch_count = 0;
+        stream.bump = ctx.bumps.revenue_stream;
+
+        emit_logged!(RevenueStreamRegisteredEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            source,
+            revenue_mint,
+        });
+
+        Ok(())
+    }
+
+    // Revenue waits in the distributor (or its vault, for SPL streams) until
+    // the next snapshot
+    pub fn deposit_revenue(ctx: Context<DepositRevenue>, amount: u64) -> Result<()> {
+        validation::nonzero(amount)?;
+        let stream = &mut ctx.accounts.revenue_stream;
+        require_keys_eq!(
+            ctx.accounts.source.key(),
+            stream.source,
+            ErrorCode::Unauthorized
+        );
+
+        if stream.revenue_mint == Pubkey::default() {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.source.to_account_info(),
+                        to: stream.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        } else {
+            let (Some(from), Some(vault)) = (
+                &ctx.accounts.source_token_account,
+                &ctx.accounts.revenue_vault,
+            ) else {
+                return err!(ErrorCode::InvalidRevenueAccounts);
+            };
+            require_keys_eq!(
+                from.mint,
+                stream.revenue_mint,
+                ErrorCode::InvalidRevenueAccounts
+            );
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: from.to_account_info(),
+                        to: vault.to_account_info(),
+                        authority: ctx.accounts.source.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+        stream.pending = stream
+            .pending
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        stream.total_deposited = stream
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit_logged!(RevenueDepositedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: stream.campaign,
+            amount,
+            pending: stream.pending,
+        });
+
+        Ok(())
+    }
+
+    // Seals the pending revenue into an epoch claimable against a merkle root
+    // of hash(holder, balance) leaves summing to total_balance. Only the
+    // platform's attestation oracle publishes snapshots, and claims wait out a
+    // challenge window in which the admin can void a bad one.
+    pub fn snapshot_revenue(
+        ctx: Context<SnapshotRevenue>,
+        holders_root: [u8; 32],
+        total_balance: u64,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.oracle.key(),
+            ctx.accounts.global_config.attestation_oracle,
+            ErrorCode::Unauthorized
+        );
+        let stream = &mut ctx.accounts.revenue_stream;
+        validation::nonzero(total_balance)?;
+        require!(stream.pending > 0, ErrorCode::NoPendingRevenue);
+
+        let epoch = &mut ctx.accounts.revenue_epoch;
+        epoch.stream = stream.key();
+        epoch.index = stream.epoch_count;
+        epoch.holders_root = holders_root;
+        epoch.total_balance = total_balance;
+        epoch.amount = stream.pending;
+        epoch.claimed = 0;
+        epoch.published_at = Clock::get()?.unix_timestamp;
+        epoch.closed = false;
+        epoch.bump = ctx.bumps.revenue_epoch;
+        stream.pending = 0;
+        stream.epoch_count += 1;
+
+        emit_logged!(RevenueSnapshotEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: stream.campaign,
+            epoch: epoch.index,
+            amount: epoch.amount,
+            total_balance,
+        });
+
+        Ok(())
+    }
+
+    // Voids a snapshot inside its challenge window; its revenue goes back to
+    // pending for the oracle to snapshot again
+    pub fn void_revenue_snapshot(ctx: Context<VoidRevenueSnapshot>) -> Result<()> {
+        require!(
+            ctx.accounts.global_config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+        let epoch = &mut ctx.accounts.revenue_epoch;
+        require!(!epoch.closed, ErrorCode::RevenueEpochClosed);
+        require!(
+            Clock::get()?.unix_timestamp < epoch.published_at + REVENUE_CHALLENGE_SECS,
+            ErrorCode::RevenueChallengeClosed
+        );
+
+        let stream = &mut ctx.accounts.revenue_stream;
+        stream.pending = stream
+            .pending
+            .checked_add(epoch.amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        epoch.closed = true;
+
+        emit_logged!(RevenueEpochClosedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: stream.campaign,
+            epoch: epoch.index,
+            returned: epoch.amount,
+            voided: true,
+        });
+
+        Ok(())
+    }
+
+    // Once an epoch's claim window ends, anyone can roll what holders left
+    // unclaimed into the next snapshot
+    pub fn sweep_revenue_epoch(ctx: Context<SweepRevenueEpoch>) -> Result<()> {
+        let epoch = &mut ctx.accounts.revenue_epoch;
+        require!(!epoch.closed, ErrorCode::RevenueEpochClosed);
+        require!(
+            Clock::get()?.unix_timestamp >= epoch.published_at + REVENUE_CLAIM_WINDOW_SECS,
+            ErrorCode::RevenueClaimWindowOpen
+        );
+
+        let unclaimed = epoch.amount - epoch.claimed;
+        let stream = &mut ctx.accounts.revenue_stream;
+        stream.pending = stream
+            .pending
+            .checked_add(unclaimed)
+            .ok_or(ErrorCode::MathOverflow)?;
+        epoch.closed = true;
+
+        emit_logged!(RevenueEpochClosedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: stream.campaign,
+            epoch: epoch.index,
+            returned: unclaimed,
+            voided: false,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim_revenue(
+        ctx: Context<ClaimRevenue>,
+        balance: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        validation::nonzero(balance)?;
+        let holder = ctx.accounts.holder.key();
+        let epoch = &mut ctx.accounts.revenue_epoch;
+        let now = Clock::get()?.unix_timestamp;
+        require!(!epoch.closed, ErrorCode::RevenueEpochClosed);
+        require!(
+            now >= epoch.published_at + REVENUE_CHALLENGE_SECS,
+            ErrorCode::RevenueClaimsNotOpen
+        );
+        require!(
+            now < epoch.published_at + REVENUE_CLAIM_WINDOW_SECS,
+            ErrorCode::RevenueEpochClosed
+        );
+        let leaf = hashv(&[holder.as_ref(), &balance.to_le_bytes()]).to_bytes();
+        require!(
+            verify_merkle_proof(&proof, epoch.holders_root, leaf),
+            ErrorCode::InvalidMerkleProof
+        );
+
+        let payout = validation::output(
+            ((epoch.amount as u128)
+                .checked_mul(balance as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / epoch.total_balance as u128) as u64,
+        )?;
+        epoch.claimed = epoch
+            .claimed
+            .checked_add(payout)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(epoch.claimed <= epoch.amount, ErrorCode::InvalidMerkleProof);
+
+        let stream = &mut ctx.accounts.revenue_stream;
+        stream.total_claimed = stream
+            .total_claimed
+            .checked_add(payout)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if stream.revenue_mint == Pubkey::default() {
+            **stream.to_account_info().try_borrow_mut_lamports()? -= payout;
+            **ctx
+                .accounts
+                .holder
+                .to_account_info()
+                .try_borrow_mut_lamports()? += payout;
+        } else {
+            let (Some(vault), Some(to)) = (
+                &ctx.accounts.revenue_vault,
+                &ctx.accounts.holder_token_account,
+            ) else {
+                return err!(ErrorCode::InvalidRevenueAccounts);
+            };
+            require_keys_eq!(
+                to.mint,
+                stream.revenue_mint,
+                ErrorCode::InvalidRevenueAccounts
+            );
+            let seeds = &[b"revenue_stream", stream.campaign.as_ref(), &[stream.bump]];
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: vault.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: stream.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                payout,
+            )?;
+        }
+
+        let claim = &mut ctx.accounts.revenue_claim;
+        claim.epoch = epoch.key();
+        claim.holder = holder;
+        claim.amount = payout;
+        claim.bump = ctx.bumps.revenue_claim;
+
+        emit_logged!(RevenueClaimedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: stream.campaign,
+            epoch: epoch.index,
+            holder,
+            amount: payout,
+        });
+
+        Ok(())
+    }
+
+    // Move part of a graduated raise into a salary stream that accrues to the
+    // creator by the second over `duration_secs`. Funding it is a withdrawal,
+    // with the same limits, approvals and receipt as withdraw_funds. Streams
+    // are numbered per campaign, so a new one can follow a finished one.
+    pub fn open_salary_stream(
+        ctx: Context<OpenSalaryStream>,
+        amount: u64,
+        duration_secs: i64,
+    ) -> Result<()> {
+        require!(duration_secs > 0, ErrorCode::InvalidSalaryStream);
+        let accounts = ctx.accounts;
+        let campaign = &mut accounts.campaign;
+
+        campaign.authorize(accounts.creator.key(), ROLE_WITHDRAW)?;
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        let funded = release_withdrawal(
+            campaign,
+            &accounts.global_config,
+            accounts.denylist_entry.as_ref(),
+            accounts.withdrawal_approval.as_ref(),
+            &accounts.salary_stream.to_account_info(),
+            amount,
+        )?;
+        record_withdrawal(
+            campaign,
+            &mut accounts.receipt,
+            ctx.bumps.receipt,
+            amount,
+            WithdrawalPurpose::Salary,
+            [0; 32],
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let stream = &mut accounts.salary_stream;
+        stream.campaign = campaign.key();
+        stream.index = campaign.salary_stream_count;
+        stream.recipient = campaign.creator;
+        stream.total = funded;
+        stream.claimed = 0;
+        stream.start_time = now;
+        stream.duration_secs = duration_secs;
+        stream.paused_at = 0;
+        stream.paused_secs = 0;
+        stream.bump = ctx.bumps.salary_stream;
+        campaign.salary_stream_count = campaign
+            .salary_stream_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit_logged!(SalaryStreamOpenedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: stream.campaign,
+            index: stream.index,
+            recipient: stream.recipient,
+            total: funded,
+            start_time: now,
+            duration_secs,
+        });
+
+        assert_invariants(&accounts.campaign, None)?;
+
+        Ok(())
+    }
+
+    pub fn claim_salary(ctx: Context<ClaimSalary>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let stream = &mut ctx.accounts.salary_stream;
+        let amount = stream.accrued(now)? - stream.claimed;
+        validation::nonzero(amount)?;
+
+        stream.claimed += amount;
+        **stream.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx
+            .accounts
+            .recipient
+            .to_account_info()
+            .try_borrow_mut_lamports()? += amount;
+
+        emit_logged!(SalaryClaimedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: stream.campaign,
+            index: stream.index,
+            recipient: stream.recipient,
+            amount,
+            claimed: stream.claimed,
+            total: stream.total,
+        });
+
+        Ok(())
+    }
+
+    // Token holder governance can stop the stream accruing, e.g. while the
+    // team is behind on its milestones; paused time is added to the schedule
+    pub fn set_salary_stream_paused(
+        ctx: Context<SetSalaryStreamPaused>,
+        paused: bool,
+    ) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        require!(
+            campaign.governance != Pubkey::default()
+                && ctx.accounts.governance.key() == campaign.governance,
+            ErrorCode::NotCampaignGovernance
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let stream = &mut ctx.accounts.salary_stream;
+        if paused && stream.paused_at == 0 {
+            stream.paused_at = now;
+        } else if !paused && stream.paused_at != 0 {
+            stream.paused_secs = stream
+                .paused_secs
+                .checked_add(now - stream.paused_at)
+                .ok_or(ErrorCode::MathOverflow)?;
+            stream.paused_at = 0;
+        }
+
+        emit_logged!(SalaryStreamPausedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            index: stream.index,
+            paused,
+            accrued: stream.accrued(now)?,
+        });
+
+        Ok(())
+    }
+
+    // A donor's receipt is a one-off, fixed-supply NFT minted to their wallet;
+    // it can be claimed any time after their first donation
+    pub fn mint_donation_receipt(ctx: Context<MintDonationReceipt>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        require!(campaign.donation_mode, ErrorCode::NotDonationCampaign);
+        let donated = ctx.accounts.contribution_record.sol_contributed;
+        require!(donated > 0, ErrorCode::ZeroAmount);
+
+        let seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let token_program = ctx.accounts.token_program.to_account_info();
+        token::mint_to(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                token::MintTo {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    to: ctx.accounts.donor_receipt_account.to_account_info(),
+                    authority: campaign.to_account_info(),
+                },
+                signer,
+            ),
+            1,
+        )?;
+        token::set_authority(
+            CpiContext::new_with_signer(
+                token_program,
+                token::SetAuthority {
+                    current_authority: campaign.to_account_info(),
+                    account_or_mint: ctx.accounts.receipt_mint.to_account_info(),
+                },
+                signer,
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+
+        emit_logged!(DonationReceiptMintedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            donor: ctx.accounts.donor.key(),
+            receipt_mint: ctx.accounts.receipt_mint.key(),
+            donated,
+        });
+
+        Ok(())
+    }
+
+    // Refund a failed campaign's backers in bulk. Remaining accounts come in
+    // triples of contribution record, its contributor's wallet and their backer
+    // profile address; each record is refunded in full and closed, with its
+    // rent going back to the contributor less the cranker's tip. Only ticket
+    // and donation campaigns qualify: their backers hold no curve tokens the
+    // refund would have to burn, while token holders refund themselves.
+    pub fn crank_refund_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CrankRefundBatch<'info>>,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let triples = ctx.remaining_accounts;
+        require!(
+            !triples.is_empty()
+                && triples.len().is_multiple_of(3)
+                && triples.len() / 3 <= MAX_REFUND_BATCH,
+            ErrorCode::InvalidRefundBatch
+        );
+        require!(
+            accounts.campaign.ticket_mint != Pubkey::default() || accounts.campaign.donation_mode,
+            ErrorCode::RefundBatchUnsupported
+        );
+        open_refunds(&mut accounts.campaign)?;
+        let campaign_key = accounts.campaign.key();
+        let cranker = accounts.cranker.to_account_info();
+
+        let mut total_payout: u64 = 0;
+        let mut total_tips: u64 = 0;
+        for triple in triples.chunks(3) {
+            let [record_info, contributor_info, profile_info] = triple else {
+                return err!(ErrorCode::InvalidRefundBatch);
+            };
+            // A record passed twice is already closed the second time round
+            let mut record = Account::<ContributionRecord>::try_from(record_info)?;
+            require_keys_eq!(record.campaign, campaign_key, ErrorCode::InvalidRefundBatch);
+            require_keys_eq!(
+                contributor_info.key(),
+                record.contributor,
+                ErrorCode::InvalidRefundBatch
+            );
+            let (profile_key, _) = Pubkey::find_program_address(
+                &[b"backer_profile", contributor_info.key.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(
+                profile_info.key(),
+                profile_key,
+                ErrorCode::InvalidRefundBatch
+            );
+
+            let refund = record.sol_contributed;
+            let payout = if refund > 0 {
+                pay_refund(
+                    &mut accounts.campaign,
+                    &mut record,
+                    contributor_info,
+                    refund,
+                    0,
+                )?
+            } else {
+                0
+            };
+            update_backer_profile(profile_info, |profile| {
+                profile.record_exit(refund, payout, true)
+            })?;
+
+            let rent = record_info.lamports();
+            let tip = calculate_bps_share(rent, REFUND_CRANK_TIP_BPS)?;
+            **record_info.try_borrow_mut_lamports()? = 0;
+            **contributor_info.try_borrow_mut_lamports()? += rent - tip;
+            **cranker.try_borrow_mut_lamports()? += tip;
+            record_info.assign(&System::id());
+            record_info.resize(0)?;
+
+            total_payout = total_payout
+                .checked_add(payout)
+                .ok_or(ErrorCode::MathOverflow)?;
+            total_tips += tip;
+            emit_logged!(RefundEvent {
+                schema_version: EVENT_SCHEMA_VERSION,
+                campaign: campaign_key,
+                contributor: contributor_info.key(),
+                burned: 0,
+                sol_amount: payout,
+            });
+        }
+
+        emit_logged!(RefundBatchCrankedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign_key,
+            cranker: cranker.key(),
+            records: (triples.len() / 3) as u32,
+            total_refunded: total_payout,
+            total_tips,
+        });
+
+        assert_invariants(&accounts.campaign, None)?;
+
+        Ok(())
+    }
+
+    // Link a verified handle to the creator's profile. A .sol domain is proven
+    // by passing its name registry account, which must be owned by the creator;
+    // a DID by an Ed25519 instruction in which the platform's identity verifier
+    // signs the creator, the DID, a nonce and an expiry. Linking again replaces
+    // the old handle. Domains can change hands afterwards, which
+    // refresh_identity catches.
+    pub fn link_identity(
+        ctx: Context<LinkIdentity>,
+        kind: IdentityKind,
+        handle: String,
+        nonce: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        require!(
+            !handle.is_empty() && handle.len() <= MAX_IDENTITY_HANDLE_LEN,
+            ErrorCode::InvalidIdentityHandle
+        );
+        let creator = ctx.accounts.creator.key();
+        match kind {
+            IdentityKind::SnsDomain => {
+                let name_account = ctx
+                    .accounts
+                    .name_account
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidSnsDomain)?;
+                require_keys_eq!(
+                    name_account.key(),
+                    sns_domain_address(&handle)?,
+                    ErrorCode::InvalidSnsDomain
+                );
+                require!(
+                    sns_domain_owned_by(name_account, &creator)?,
+                    ErrorCode::InvalidSnsDomain
+                );
+            }
+            IdentityKind::Did => {
+                require!(is_did(&handle), ErrorCode::InvalidIdentityHandle);
+                let verifier = ctx.accounts.global_config.identity_verifier;
+                require!(
+                    verifier != Pubkey::default(),
+                    ErrorCode::IdentityVerifierNotSet
+                );
+                require!(
+                    Clock::get()?.unix_timestamp <= expiry,
+                    ErrorCode::IdentityAttestationExpired
+                );
+                let instructions = ctx
+                    .accounts
+                    .instructions
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidSignature)?;
+                let statement = IdentityStatement {
+                    creator,
+                    did: handle.clone(),
+                    nonce,
+                    expiry,
+                };
+                let mut message = IDENTITY_ATTESTATION_DOMAIN.to_vec();
+                statement.serialize(&mut message)?;
+                verify_ed25519_signature(instructions, &verifier, &message)?;
+                // Nonces only move forward, so an attestation links once
+                let profile = &mut ctx.accounts.creator_profile;
+                require!(nonce > profile.identity_nonce, ErrorCode::NonceAlreadyUsed);
+                profile.identity_nonce = nonce;
+            }
+            IdentityKind::None => return err!(ErrorCode::InvalidIdentityHandle),
+        }
+
+        let profile = &mut ctx.accounts.creator_profile;
+        profile.identity_kind = kind;
+        profile.identity_handle = handle;
+        profile.identity_linked_at = Clock::get()?.unix_timestamp;
+
+        emit_logged!(IdentityLinkedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            creator,
+            identity_kind: kind,
+            handle: profile.identity_handle.clone(),
+        });
+
+        Ok(())
+    }
+
+    // Mint from the creator allocation or liquidity reserve set aside at
+    // initialization. Both open at graduation; the creator's share also waits
+    // out the team lockup.
+    pub fn mint_reserved_allocation(
+        ctx: Context<MintReservedAllocation>,
+        bucket: SupplyBucket,
+        amount: u64,
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.authorize(ctx.accounts.authority.key(), ROLE_WITHDRAW)?;
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        validation::nonzero(amount)?;
+        match bucket {
+            SupplyBucket::Creator => require!(
+                Clock::get()?.unix_timestamp
+                    >= campaign
+                        .graduated_at
+                        .saturating_add(campaign.team_lockup_secs),
+                ErrorCode::TeamSellEmbargo
+            ),
+            SupplyBucket::Liquidity => {}
+            SupplyBucket::Curve | SupplyBucket::Airdrop => {
+                return err!(ErrorCode::InvalidSupplyAllocation)
+            }
+        }
+        campaign.draw_supply(bucket, amount)?;
+
+        let seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: campaign.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        emit_logged!(ReservedAllocationMintedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            bucket,
+            destination: ctx.accounts.destination.key(),
+            amount,
+            remaining: campaign.unminted(bucket),
+        });
+
+        assert_invariants(
+            &ctx.accounts.campaign,
+            Some(ctx.accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    // Open the campaign's oracle-layout price account. Anyone can pay for it;
+    // from then on every trade has to pass it, which keeps it current.
+    pub fn initialize_price_feed(ctx: Context<InitializePriceFeed>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.price_feed = ctx.accounts.price_feed.key();
+        let clock = Clock::get()?;
+        let price = i64::try_from(calculate_token_price(
+            &campaign.curve,
+            campaign.raised_amount,
+        )?)
+        .map_err(|_| ErrorCode::MathOverflow)?;
+
+        let feed = &mut ctx.accounts.price_feed;
+        feed.write_authority = campaign.key();
+        feed.verification_level = PRICE_FEED_VERIFICATION_FULL;
+        feed.feed_id = campaign.key().to_bytes();
+        feed.price = price;
+        feed.conf = 0;
+        feed.exponent = PRICE_FEED_EXPONENT;
+        feed.publish_time = clock.unix_timestamp;
+        feed.prev_publish_time = clock.unix_timestamp;
+        feed.ema_price = price;
+        feed.ema_conf = 0;
+        feed.posted_slot = clock.slot;
+        feed.campaign = campaign.key();
+        feed.bump = ctx.bumps.price_feed;
+
+        emit_logged!(PriceFeedInitializedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            price_feed: feed.key(),
+            price: feed.price,
+            exponent: feed.exponent,
+        });
+
+        Ok(())
+    }
+
+    // Deposit adapters are the interface for programs that route other people's
+    // money into campaigns (yield vaults, savings apps). A program registers by
+    // signing with its DEPOSIT_ADAPTER_SIGNER_SEED PDA, which only it can do,
+    // and can route deposits once the admin has approved it.
+    pub fn register_deposit_adapter(
+        ctx: Context<RegisterDepositAdapter>,
+        name: String,
+    ) -> Result<()> {
+        require!(
+            !name.is_empty() && name.len() <= MAX_ADAPTER_NAME_LEN,
+            ErrorCode::InvalidAdapterName
+        );
+
+        let adapter = &mut ctx.accounts.adapter;
+        adapter.program = ctx.accounts.adapter_program.key();
+        adapter.signer = ctx.accounts.adapter_signer.key();
+        adapter.name = name;
+        adapter.active = false;
+        adapter.total_routed = 0;
+        adapter.deposit_count = 0;
+        adapter.registered_at = Clock::get()?.unix_timestamp;
+        adapter.bump = ctx.bumps.adapter;
+
+        emit_logged!(DepositAdapterRegisteredEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            adapter: adapter.key(),
+            program: adapter.program,
+            name: adapter.name.clone(),
+        });
+
+        Ok(())
+    }
+
+    // Approves a registered adapter, or switches off one that misbehaves
+    pub fn set_deposit_adapter_active(
+        ctx: Context<SetDepositAdapterActive>,
+        active: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_config.admin == ctx.accounts.admin.key(),
+            ErrorCode::NotAdmin
+        );
+        let adapter = &mut ctx.accounts.adapter;
+        adapter.active = active;
+
+        emit_logged!(DepositAdapterUpdatedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            adapter: adapter.key(),
+            active,
+        });
+
+        Ok(())
+    }
+
+    // Called by an adapter over CPI: it signs with its adapter PDA, `funder`
+    // pays, and the position belongs to `beneficiary`, the adapter's user.
+    // `reference` is the adapter's own identifier for where the money came from.
+    pub fn deposit_via_adapter(
+        ctx: Context<DepositViaAdapter>,
+        amount: u64,
+        reference: [u8; 32],
+    ) -> Result<()> {
+        let beneficiary_key = ctx.accounts.beneficiary.key();
+        let accounts = ctx.accounts;
+        require!(accounts.adapter.active, ErrorCode::DepositAdapterInactive);
+        check_region_access(
+            &accounts.campaign,
+            accounts.region_attestation.as_ref(),
+            beneficiary_key,
+        )?;
+        // Whatever the hard cap leaves no room for stays with the funder
+        let amount = refund_excess(
+            &accounts.campaign,
+            None,
+            &accounts.funder.to_account_info(),
+            amount,
+        )?;
+        require!(amount > 0, ErrorCode::HardCapExceeded);
+
+        let outcome = settle_contribution(
+            ContributionPayment {
+                campaign: &mut accounts.campaign,
+                global_config: &accounts.global_config,
+                payer: accounts.funder.to_account_info(),
+                treasury: accounts.treasury.to_account_info(),
+                insurance_pool: accounts.insurance_pool.as_mut(),
+                frontend: None,
+                frontend_payout: None,
+                matchable: true,
+                match_commitment: accounts.match_commitment.as_deref_mut(),
+                token_mint: accounts.token_mint.to_account_info(),
+                recipient_token_account: accounts.beneficiary_token_account.to_account_info(),
+                contribution_record: &mut accounts.contribution_record,
+                record_bump: ctx.bumps.contribution_record,
+                price_history: &mut *accounts.price_history.load_mut()?,
+                price_feed: accounts.price_feed.as_deref_mut(),
+                ticket: accounts
+                    .ticket_mint
+                    .as_ref()
+                    .zip(accounts.beneficiary_ticket_account.as_ref())
+                    .map(|(mint, account)| (mint.to_account_info(), account.to_account_info())),
+                token_program: accounts.token_program.to_account_info(),
+                tier_bonus_bps: None,
+                denylist_entry: accounts.denylist_entry.as_ref(),
+                governance: Some(GovernanceMint {
+                    governance_mint: accounts.governance_mint.as_ref(),
+                    governance_authority: accounts.governance_authority.as_ref(),
+                    holder_account: accounts.beneficiary_governance_account.as_ref(),
+                    token_2022_program: accounts.token_2022_program.as_ref(),
+                    holder: accounts.beneficiary.to_account_info(),
+                    payer: accounts.funder.to_account_info(),
+                    associated_token_program: accounts.associated_token_program.to_account_info(),
+                    system_program: accounts.system_program.to_account_info(),
+                }),
+                leaderboard: accounts.leaderboard.as_ref(),
+                backer_profile: Some(&accounts.backer_profile),
+                system_program: accounts.system_program.to_account_info(),
+                reference: None,
+            },
+            beneficiary_key,
+            amount,
+        )?;
+        let net_amount = outcome.sol_amount;
+
+        // Each adapter and reference keeps its own tally of what it routed here
+        let adapter_key = accounts.adapter.key();
+        let source = &mut accounts.adapter_source;
+        if source.adapter == Pubkey::default() {
+            source.campaign = accounts.campaign.key();
+            source.beneficiary = beneficiary_key;
+            source.adapter = adapter_key;
+            source.reference = reference;
+            source.bump = ctx.bumps.adapter_source;
+        }
+        source.routed = source
+            .routed
+            .checked_add(net_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let adapter = &mut accounts.adapter;
+        adapter.total_routed = adapter
+            .total_routed
+            .checked_add(net_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        adapter.deposit_count = adapter.deposit_count.saturating_add(1);
+
+        emit_logged!(AdapterDepositEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: accounts.campaign.key(),
+            adapter: adapter_key,
+            beneficiary: beneficiary_key,
+            sol_amount: net_amount,
+            reference,
+        });
+
+        assert_invariants(
+            &accounts.campaign,
+            Some(accounts.token_mint.to_account_info()),
+        )?;
+
+        Ok(())
+    }
+
+    // Write the campaign's permanent record once it has an outcome, so its
+    // provenance survives the campaign and history accounts being closed. The
+    // archive has no instruction that writes it again.
+    pub fn archive_campaign(ctx: Context<ArchiveCampaign>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let clock = Clock::get()?;
+        let outcome = if campaign.has_failed(clock.unix_timestamp) {
+            CampaignStatus::Failed
+        } else {
+            require!(
+                campaign.status == CampaignStatus::Graduated,
+                ErrorCode::CampaignStillActive
+            );
+            CampaignStatus::Graduated
+        };
+
+        let archive = &mut ctx.accounts.archive;
+        archive.campaign = campaign.key();
+        archive.creator = campaign.creator;
+        archive.token_mint = campaign.token_mint;
+        archive.name_hash = campaign.name_hash;
+        archive.outcome = outcome;
+        archive.target_amount = campaign.target_amount;
+        archive.raised_amount = campaign.raised_amount;
+        archive.contributor_count = campaign.contributor_count;
+        archive.contribution_count = campaign.contribution_count;
+        archive.total_supply = campaign.total_supply;
+        archive.created_at = campaign.created_at;
+        archive.end_time = campaign.end_time;
+        archive.graduated_at = campaign.graduated_at;
+        archive.state_hash = hash(&campaign.try_to_vec()?).to_bytes();
+        archive.history_hash = hash(
+            &ctx.accounts
+                .price_history
+                .to_account_info()
+                .try_borrow_data()?,
+        )
+        .to_bytes();
+        archive.archived_at = clock.unix_timestamp;
+        archive.archived_slot = clock.slot;
+        archive.bump = ctx.bumps.archive;
+
+        emit_logged!(CampaignArchivedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: archive.campaign,
+            archive: archive.key(),
+            outcome,
+            raised_amount: archive.raised_amount,
+            contributor_count: archive.contributor_count,
+            state_hash: archive.state_hash,
+            history_hash: archive.history_hash,
+        });
+
+        Ok(())
+    }
+
+    // Tiers give lists of wallets (guild members, OG holders) early access,
+    // each with its own window, per-wallet cap and token bonus. They're fixed
+    // once written and must be set up before the first contribution; plain
+    // contributions open when the last tier's window closes.
+    pub fn configure_allowlist_tier(
+        ctx: Context<ConfigureAllowlistTier>,
+        index: u8,
+        merkle_root: [u8; 32],
+        starts_at: i64,
+        ends_at: i64,
+        per_wallet_cap: u64,
+        bonus_bps: u16,
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        require!(
+            campaign.creator == ctx.accounts.creator.key(),
+            ErrorCode::NotCampaignCreator
+        );
+        require!(
+            campaign.contribution_count == 0,
+            ErrorCode::CampaignAlreadyStarted
+        );
+        require!(
+            index < MAX_ALLOWLIST_TIERS
+                && merkle_root != [0; 32]
+                && starts_at < ends_at
+                && ends_at <= campaign.end_time
+                && bonus_bps <= MAX_TIER_BONUS_BPS,
+            ErrorCode::InvalidAllowlistTier
+        );
+        // Queued deposits convert ahead of everyone, which would let any wallet skip the list
+        require!(!campaign.pre_deposits, ErrorCode::PreDepositsUnsupported);
+        campaign.allowlist_ends_at = campaign.allowlist_ends_at.max(ends_at);
+
+        let tier = &mut ctx.accounts.tier;
+        tier.campaign = campaign.key();
+        tier.index = index;
+        tier.merkle_root = merkle_root;
+        tier.starts_at = starts_at;
+        tier.ends_at = ends_at;
+        tier.per_wallet_cap = per_wallet_cap;
+        tier.bonus_bps = bonus_bps;
+        tier.total_contributed = 0;
+        tier.contribution_count = 0;
+        tier.bump = ctx.bumps.tier;
+
+        emit_logged!(AllowlistTierConfiguredEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            tier: index,
+            merkle_root,
+            starts_at,
+            ends_at,
+            per_wallet_cap,
+            bonus_bps,
+        });
+
+        Ok(())
+    }
+
+    // Mint an allowlisted backer's tier bonus once the campaign has graduated
+    // and the curve no longer buys tokens back
+    pub fn claim_tier_bonus(ctx: Context<ClaimTierBonus>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        let record = &mut ctx.accounts.contribution_record;
+
+        require!(
+            campaign.status == CampaignStatus::Graduated,
+            ErrorCode::CampaignNotGraduated
+        );
+        let amount = record.pending_bonus;
+        require!(amount > 0, ErrorCode::NoTierBonusPending);
+        record.pending_bonus = 0;
+        campaign.curve_undelivered = campaign.curve_undelivered.saturating_sub(amount);
+
+        if campaign.freeze_during_raise {
+            set_token_account_frozen(
+                campaign,
+                ctx.accounts.token_mint.to_account_info(),
+                ctx.accounts.contributor_token_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                false,
+            )?;
+        }
+
+        let seeds = &[
+            b"campaign",
+            campaign.creator.as_ref(),
+            campaign.name_hash.as_ref(),
+            &[campaign.bump],
+        ];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.contributor_token_account.to_account_info(),
+                    authority: campaign.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        emit_logged!(TierBonusClaimedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            contributor: record.contributor,
+            amount,
+        });
+
+        assert_invariants(campaign, Some(ctx.accounts.token_mint.to_account_info()))?;
+
+        Ok(())
+    }
+
+    // Unlink a .sol domain its creator no longer owns. Anyone can call it with
+    // the domain's name registry account once the domain is transferred or
+    // expires, so campaigns stop showing a handle that has moved on.
+    pub fn refresh_identity(ctx: Context<RefreshIdentity>) -> Result<()> {
+        let profile = &mut ctx.accounts.creator_profile;
+        require!(
+            profile.identity_kind == IdentityKind::SnsDomain,
+            ErrorCode::InvalidIdentityHandle
+        );
+        let name_account = &ctx.accounts.name_account;
+        require_keys_eq!(
+            name_account.key(),
+            sns_domain_address(&profile.identity_handle)?,
+            ErrorCode::InvalidSnsDomain
+        );
+        require!(
+            !sns_domain_owned_by(name_account, &profile.creator)?,
+            ErrorCode::SnsDomainStillOwned
+        );
+
+        let handle = std::mem::take(&mut profile.identity_handle);
+        profile.identity_kind = IdentityKind::None;
+        profile.identity_linked_at = 0;
+
+        emit_logged!(IdentityUnlinkedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            creator: profile.creator,
+            handle,
+        });
+
+        Ok(())
+    }
+}
+
+// Accounts every curve purchase touches, whichever instruction funds it
+struct CurvePurchase<'a, 'info> {
+    campaign: &'a mut Account<'info, Campaign>,
+    token_mint: AccountInfo<'info>,
+    recipient_token_account: AccountInfo<'info>,
+    // None for campaigns that keep records in a compressed tree
+    contribution_record: Option<&'a mut Account<'info, ContributionRecord>>,
+    record_bump: u8,
+    price_history: &'a mut PriceHistory,
+    price_feed: Option<&'a mut Account<'info, PriceFeed>>,
+    // Ticket mint and the beneficiary's ticket account, for uniform-price raises
+    ticket: Option<(AccountInfo<'info>, AccountInfo<'info>)>,
+    token_program: AccountInfo<'info>,
+    // Platform fee already charged on this purchase, credited towards loyalty rebates
+    fee_paid: u64,
+    // Converting a pre-launch deposit, which goes ahead of everyone else
+    from_queue: bool,
+    // Bought through an allowlist tier, with that tier's bonus. Everything else
+    // waits for the allowlist phase to end.
+    tier_bonus_bps: Option<u16>,
+    // Part of the amount paid by a sponsor's match. It buys tokens for the
+    // beneficiary but is the sponsor's to get back, so it stays out of their basis.
+    sponsored: u64,
+    // Screening of the beneficiary against the platform denylist
+    global_config: &'a GlobalConfig,
+    denylist_entry: Option<&'a UncheckedAccount<'info>>,
+}
+
+// Validate a purchase, mint curve tokens (or claim tickets) to the beneficiary
+// and update the campaign and contribution record. Callers move the SOL into
+// the campaign. Returns the curve tokens minted, which is zero for ticket mode
+// and donations.
+fn buy_from_curve(purchase: CurvePurchase, beneficiary: Pubkey, amount: u64) -> Result<u64> {
+    check_denylist(purchase.global_config, purchase.denylist_entry)?;
+    let campaign = purchase.campaign;
+
+    campaign.ensure_active()?;
+    // Every way onto the curve takes the same minimum, measured before fees.
+    // Queued deposits met it when they were escrowed.
+    if purchase.from_queue {
+        validation::nonzero(amount)?;
+    } else {
+        validation::contribution(
+            amount
+                .checked_add(purchase.fee_paid)
+                .ok_or(ErrorCode::MathOverflow)?,
+        )?;
+    }
+    let now = Clock::get()?.unix_timestamp;
+    require!(now < campaign.end_time, ErrorCode::DeadlinePassed);
+    require!(now >= campaign.start_time, ErrorCode::CampaignNotStarted);
+    require!(
+        !campaign.contributions_paused,
+        ErrorCode::ContributionsPaused
+    );
+    // Pre-launch deposits get the opening prices, so the curve stays closed until they're in
+    require!(
+        purchase.from_queue || campaign.pre_deposit_head == campaign.pre_deposit_tail,
+        ErrorCode::PreDepositsPending
+    );
+    require!(
+        purchase.tier_bonus_bps.is_some() || now >= campaign.allowlist_ends_at,
+        ErrorCode::AllowlistPhaseActive
+    );
+    require!(
+        purchase.contribution_record.is_some() || campaign.record_tree != Pubkey::default(),
+        ErrorCode::CompressionNotEnabled
+    );
+
+    // The campaign's buy fee stays in the vault; only the rest goes through the curve
+    let curve_fee = retain_curve_fee(campaign, beneficiary, amount, true)?;
+    let amount = validation::output(amount - curve_fee)?;
+    validation::within_cap(campaign.raised_amount, amount, campaign.hard_cap)?;
+    let basis = amount.saturating_sub(sponsor_basis(campaign, purchase.sponsored)?);
+
+    // Whales have to split orders that would move the price too far at once
+    if campaign.max_price_impact_bps > 0 {
+        let price_before = calculate_token_price(&campaign.curve, campaign.raised_amount)?;
+        let price_after = calculate_token_price(
+            &campaign.curve,
+            campaign
+                .raised_amount
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathOverflow)?,
+        )?;
+        let impact_bps =
+            (price_after - price_before) as u128 * BPS_DENOMINATOR as u128 / price_before as u128;
+        require!(
+            impact_bps <= campaign.max_price_impact_bps as u128,
+            ErrorCode::PriceImpactExceeded
+        );
+    }
+
+    // Drawn from the curve bucket up front, before the signer seeds borrow the
+    // campaign. A tier bonus is reserved alongside but not minted yet.
+    let (curve_tokens, tier_bonus) =
+        if campaign.ticket_mint == Pubkey::default() && !campaign.donation_mode {
+            // Calculate tokens to mint based on bonding curve
+            let tokens = if purchase.from_queue {
+                campaign.queued_tokens(amount)?
+            } else {
+                calculate_tokens_from_sol(&campaign.curve, amount, campaign.raised_amount)?
+            };
+            let tokens = validation::output(tokens)?;
+            let bonus = calculate_bps_share(tokens, purchase.tier_bonus_bps.unwrap_or(0))?;
+            campaign.draw_supply(
+                SupplyBucket::Curve,
+                tokens.checked_add(bonus).ok_or(ErrorCode::MathOverflow)?,
+            )?;
+            (tokens, bonus)
+        } else {
+            (0, 0)
+        };
+
+    let seeds = &[
+        b"campaign",
+        campaign.creator.as_ref(),
+        campaign.name_hash.as_ref(),
+        &[campaign.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let tokens_to_mint = if campaign.ticket_mint != Pubkey::default() {
+        // Uniform-price raises issue one ticket per lamport and settle tokens at close
+        let (ticket_mint, ticket_account) =
+            purchase.ticket.ok_or(ErrorCode::MissingTicketAccounts)?;
+        require_keys_eq!(
+            ticket_mint.key(),
+            campaign.ticket_mint,
+            ErrorCode::MissingTicketAccounts
+        );
+
+        let cpi_accounts = token::MintTo {
+            mint: ticket_mint,
+            to: ticket_account,
+            authority: campaign.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(purchase.token_program, cpi_accounts, signer);
+        token::mint_to(cpi_ctx, amount)?;
+
+        0
+    } else if campaign.donation_mode {
+        0
+    } else {
+        let tokens_to_mint = curve_tokens;
+
+        // Frozen balances have to be thawed before they can be topped up
+        if campaign.freeze_during_raise {
+            set_token_account_frozen(
+                campaign,
+                purchase.token_mint.clone(),
+                purchase.recipient_token_account.clone(),
+                purchase.token_program.clone(),
+                false,
+            )?;
+        }
+
+        let cpi_accounts = token::MintTo {
+            mint: purchase.token_mint.clone(),
+            to: purchase.recipient_token_account.clone(),
+            authority: campaign.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(purchase.token_program.clone(), cpi_accounts, signer);
+        token::mint_to(cpi_ctx, tokens_to_mint)?;
+
+        if campaign.freeze_during_raise {
+            set_token_account_frozen(
+                campaign,
+                purchase.token_mint,
+                purchase.recipient_token_account,
+                purchase.token_program,
+                true,
+            )?;
+        }
+
+        tokens_to_mint
+    };
+
+    // Update campaign raised amount
+    campaign.raised_amount = campaign
+        .raised_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    campaign.credit_balances(amount)?;
+
+    // Keepers watch for this to trigger graduation once the threshold is crossed
+    if !campaign.ready_to_graduate && campaign.raised_amount >= campaign.graduation_threshold {
+        campaign.ready_to_graduate = true;
+        emit_logged!(GraduationReadyEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            raised_amount: campaign.raised_amount,
+            threshold: campaign.graduation_threshold,
+        });
+    }
+
+    // Progress towards stretch goals counts what the creator already withdrew
+    let gross_raised = campaign
+        .raised_amount
+        .saturating_add(campaign.withdrawn_amount);
+    while campaign.stretch_goals_reached < campaign.stretch_goal_count
+        && gross_raised >= campaign.stretch_goals[campaign.stretch_goals_reached as usize]
+    {
+        let index = campaign.stretch_goals_reached;
+        campaign.stretch_goals_reached += 1;
+        emit_logged!(StretchGoalReachedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            index,
+            goal: campaign.stretch_goals[index as usize],
+            raised_amount: gross_raised,
+        });
+    }
+
+    // Lock the guaranteed share of this contribution for floor redemptions
+    let reserve_share = calculate_bps_share(amount, campaign.floor_reserve_bps)?;
+    campaign.floor_reserve = campaign
+        .floor_reserve
+        .checked_add(reserve_share)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // Price once and reuse it for the stats, the receipt and the history sample
+    let price = calculate_token_price(&campaign.curve, campaign.raised_amount)?;
+
+    // Track what this contributor has put in and received. Compressed
+    // campaigns append a receipt leaf instead, and can't tell repeat backers
+    // apart, so they leave contributor_count and the top backers alone.
+    if let Some(record) = purchase.contribution_record {
+        if record.contributor == Pubkey::default() {
+            record.campaign = campaign.key();
+            record.contributor = beneficiary;
+            record.bump = purchase.record_bump;
+            record.holding_since = now;
+            campaign.contributor_count = campaign.contributor_count.saturating_add(1);
+        }
+        record.last_buy_at = hold_start(record, now, tokens_to_mint);
+        record.fees_paid = record
+            .fees_paid
+            .checked_add(purchase.fee_paid)
+            .ok_or(ErrorCode::MathOverflow)?;
+        record.sol_contributed = record
+            .sol_contributed
+            .checked_add(basis)
+            .ok_or(ErrorCode::MathOverflow)?;
+        record.tokens_received = record
+            .tokens_received
+            .checked_add(tokens_to_mint)
+            .ok_or(ErrorCode::MathOverflow)?;
+        record.pending_bonus = record
+            .pending_bonus
+            .checked_add(tier_bonus)
+            .ok_or(ErrorCode::MathOverflow)?;
+        campaign.curve_undelivered = campaign
+            .curve_undelivered
+            .checked_add(tier_bonus)
+            .ok_or(ErrorCode::MathOverflow)?;
+        record.refresh_entry_price()?;
+        count_milestone_backer(campaign, record);
+        rank_entry(
+            &mut purchase.price_history.top_backers,
+            beneficiary,
+            record.sol_contributed,
+        );
+
+        // Each receipt hash chains onto the backer's previous one, so the latest
+        // value on the record commits to their whole contribution statement
+        let receipt_hash = hashv(&[
+            &record.receipt_hash,
+            campaign.key().as_ref(),
+            beneficiary.as_ref(),
+            &amount.to_le_bytes(),
+            &tokens_to_mint.to_le_bytes(),
+            &price.to_le_bytes(),
+            &now.to_le_bytes(),
+            &campaign.contribution_count.to_le_bytes(),
+        ])
+        .to_bytes();
+        record.receipt_hash = receipt_hash;
+        record.receipt_count += 1;
+        emit_logged!(ContributionReceiptEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            contributor: beneficiary,
+            receipt_index: record.receipt_count - 1,
+            sequence: campaign.contribution_count,
+            sol_amount: amount,
+            token_amount: tokens_to_mint,
+            price,
+            timestamp: now,
+            receipt_hash,
+        });
+    }
+
+    campaign.contribution_count = campaign.contribution_count.saturating_add(1);
+    campaign.peak_price = campaign.peak_price.max(price);
+
+    // Queued deposits were committed before the curve opened, so they always convert
+    if !purchase.from_queue {
+        check_circuit_breaker(campaign, now, price)?;
+    }
+    let price_cumulative = campaign.accumulate_price(now, price)?;
+    purchase
+        .price_history
+        .record(now, campaign.raised_amount, price, price_cumulative);
+    publish_price_feed(campaign, purchase.price_feed, price)?;
+
+    Ok(tokens_to_mint)
+}
+
+// Once a campaign has opened a feed, a trade that leaves it out would let it go stale
+fn publish_price_feed(
+    campaign: &Campaign,
+    feed: Option<&mut Account<PriceFeed>>,
+    price: u64,
+) -> Result<()> {
+    match feed {
+        Some(feed) => {
+            let clock = Clock::get()?;
+            feed.publish(clock.unix_timestamp, clock.slot, price)
+        }
+        None => {
+            require!(
+                campaign.price_feed == Pubkey::default(),
+                ErrorCode::PriceFeedRequired
+            );
+            Ok(())
+        }
+    }
+}
+
+// Optional governance accounts plus whoever receives and pays for them
+struct GovernanceMint<'a, 'info> {
+    governance_mint: Option<&'a UncheckedAccount<'info>>,
+    governance_authority: Option<&'a UncheckedAccount<'info>>,
+    holder_account: Option<&'a UncheckedAccount<'info>>,
+    token_2022_program: Option<&'a Program<'info, Token2022>>,
+    holder: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    associated_token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+}
+
+fn mint_governance_tokens(
+    campaign: &Account<Campaign>,
+    accounts: GovernanceMint,
+    amount: u64,
+) -> Result<()> {
+    let (
+        Some(governance_mint),
+        Some(governance_authority),
+        Some(governance_account),
+        Some(token_2022_program),
+    ) = (
+        accounts.governance_mint,
+        accounts.governance_authority,
+        accounts.holder_account,
+        accounts.token_2022_program,
+    )
+    else {
+        return err!(ErrorCode::MissingGovernanceAccounts);
+    };
+
+    let expected = associated_token::get_associated_token_address_with_program_id(
+        &accounts.holder.key(),
+        &governance_mint.key(),
+        &token_2022_program.key(),
+    );
+    require_keys_eq!(
+        governance_account.key(),
+        expected,
+        ErrorCode::MissingGovernanceAccounts
+    );
+
+    associated_token::create_idempotent(CpiContext::new(
+        accounts.associated_token_program,
+        associated_token::Create {
+            payer: accounts.payer,
+            associated_token: governance_account.to_account_info(),
+            authority: accounts.holder,
+            mint: governance_mint.to_account_info(),
+            system_program: accounts.system_program,
+            token_program: token_2022_program.to_account_info(),
+        },
+    ))?;
+
+    let campaign_key = campaign.key();
+    let seeds = &[
+        b"governance_authority",
+        campaign_key.as_ref(),
+        &[campaign.governance_authority_bump],
+    ];
+    let signer = &[&seeds[..]];
+    let locked = campaign.governance_lockup_secs > 0;
+
+    // Locked balances sit frozen, so thaw before topping up
+    let frozen = {
+        let data = governance_account.try_borrow_data()?;
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?
+            .base
+            .state
+            == AccountState::Frozen
+    };
+    if frozen {
+        token_2022::thaw_account(CpiContext::new_with_signer(
+            token_2022_program.to_account_info(),
+            token_2022::ThawAccount {
+                account: governance_account.to_account_info(),
+                mint: governance_mint.to_account_info(),
+                authority: governance_authority.to_account_info(),
+            },
+            signer,
+        ))?;
+    }
+
+    token_2022::mint_to(
+        CpiContext::new_with_signer(
+            token_2022_program.to_account_info(),
+            token_2022::MintTo {
+                mint: governance_mint.to_account_info(),
+                to: governance_account.to_account_info(),
+                authority: governance_authority.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+
+    if locked {
+        token_2022::freeze_account(CpiContext::new_with_signer(
+            token_2022_program.to_account_info(),
+            token_2022::FreezeAccount {
+                account: governance_account.to_account_info(),
+                mint: governance_mint.to_account_info(),
+                authority: governance_authority.to_account_info(),
+            },
+            signer,
+        ))?;
+    }
+
+    Ok(())
+}
+
+struct GovernanceBurn<'a, 'info> {
+    governance_mint: Option<&'a UncheckedAccount<'info>>,
+    governance_authority: Option<&'a UncheckedAccount<'info>>,
+    holder_account: Option<&'a UncheckedAccount<'info>>,
+    token_2022_program: Option<&'a Program<'info, Token2022>>,
+    holder: AccountInfo<'info>,
+}
+
+// Curve tokens going back to the campaign take their governance tokens with
+// them, so voting weight can't be bought again with the same SOL
+fn burn_governance_tokens(
+    campaign: &Account<Campaign>,
+    accounts: GovernanceBurn,
+    amount: u64,
+) -> Result<()> {
+    let (
+        Some(governance_mint),
+        Some(governance_authority),
+        Some(governance_account),
+        Some(token_2022_program),
+    ) = (
+        accounts.governance_mint,
+        accounts.governance_authority,
+        accounts.holder_account,
+        accounts.token_2022_program,
+    )
+    else {
+        return err!(ErrorCode::MissingGovernanceAccounts);
+    };
+
+    let expected = associated_token::get_associated_token_address_with_program_id(
+        &accounts.holder.key(),
+        &governance_mint.key(),
+        &token_2022_program.key(),
+    );
+    require_keys_eq!(
+        governance_account.key(),
+        expected,
+        ErrorCode::MissingGovernanceAccounts
+    );
+
+    let (frozen, balance) = {
+        let data = governance_account.try_borrow_data()?;
+        let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?.base;
+        (account.state == AccountState::Frozen, account.amount)
+    };
+    // Tokens that arrived by transfer came without any, and can't be sold for them
+    require!(balance >= amount, ErrorCode::GovernanceTokensShort);
+
+    let campaign_key = campaign.key();
+    let seeds = &[
+        b"governance_authority",
+        campaign_key.as_ref(),
+        &[campaign.governance_authority_bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    // Locked balances sit frozen, so thaw for the burn and lock the rest again
+    if frozen {
+        token_2022::thaw_account(CpiContext::new_with_signer(
+            token_2022_program.to_account_info(),
+            token_2022::ThawAccount {
+                account: governance_account.to_account_info(),
+                mint: governance_mint.to_account_info(),
+                authority: governance_authority.to_account_info(),
+            },
+            signer,
+        ))?;
+    }
+
+    token_2022::burn(
+        CpiContext::new(
+            token_2022_program.to_account_info(),
+            token_2022::Burn {
+                mint: governance_mint.to_account_info(),
+                from: governance_account.to_account_info(),
+                authority: accounts.holder,
+            },
+        ),
+        amount,
+    )?;
+
+    if frozen {
+        token_2022::freeze_account(CpiContext::new_with_signer(
+            token_2022_program.to_account_info(),
+            token_2022::FreezeAccount {
+                account: governance_account.to_account_info(),
+                mint: governance_mint.to_account_info(),
+                authority: governance_authority.to_account_info(),
+            },
+            signer,
+        ))?;
+    }
+
+    Ok(())
+}
+
+// The curve's rate in bps of its base tokens per SOL: the early bonus tapers
+// off in step with the raise instead of dropping away at a threshold
+fn curve_multiplier_bps(curve: &CurveConfig, current_raised: u64) -> u128 {
+    let bonus_bps = if current_raised < curve.bonus_until {
+        curve.bonus_bps as u128 * (curve.bonus_until - current_raised) as u128
+            / curve.bonus_until as u128
+    } else {
+        0
+    };
+    BPS_DENOMINATOR as u128 + bonus_bps
+}
+
+// Base units minted per lamport, Q64.64: the curve's whole tokens per SOL
+// scaled to the mint's decimals, with the bonus applied
+fn curve_rate_q64(curve: &CurveConfig, current_raised: u64) -> Result<u128> {
+    let numerator = (curve.tokens_per_sol as u128)
+        .checked_mul(TOKEN_PRECISION * curve_multiplier_bps(curve, current_raised))
+        .ok_or(ErrorCode::MathOverflow)?;
+    q64::ratio(
+        numerator,
+        LAMPORTS_PER_SOL as u128 * BPS_DENOMINATOR as u128,
+    )
+}
+
+fn calculate_tokens_from_sol(
+    curve: &CurveConfig,
+    sol_amount: u64,
+    current_raised: u64,
+) -> Result<u64> {
+    q64::mul_floor(curve_rate_q64(curve, current_raised)?, sol_amount)
+}
+
+// Inverse of calculate_tokens_from_sol at the rate currently offered by the curve
+fn calculate_sol_from_tokens(
+    curve: &CurveConfig,
+    token_amount: u64,
+    current_raised: u64,
+) -> Result<u64> {
+    q64::div_floor(token_amount, curve_rate_q64(curve, current_raised)?)
+}
+
+// Check that the instruction before this one is an Ed25519 program
+// verification of `message` signed by `signer`
+fn verify_ed25519_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, ErrorCode::InvalidSignature);
+    let ix = load_instruction_at_checked((current - 1) as usize, instructions)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        ErrorCode::InvalidSignature
+    );
+
+    // Header is the signature count and padding, followed by one set of u16 offsets
+    let data = &ix.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        ErrorCode::InvalidSignature
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let signature_ix = read_u16(4);
+    let pubkey_offset = read_u16(6);
+    let pubkey_ix = read_u16(8);
+    let message_offset = read_u16(10);
+    let message_size = read_u16(12);
+    let message_ix = read_u16(14);
+
+    // Everything must be read from the Ed25519 instruction itself
+    let this_ix = u16::MAX as usize;
+    require!(
+        signature_ix == this_ix && pubkey_ix == this_ix && message_ix == this_ix,
+        ErrorCode::InvalidSignature
+    );
+
+    let pubkey = data
+        .get(pubkey_offset..pubkey_offset + 32)
+        .ok_or(ErrorCode::InvalidSignature)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidSignature)?;
+    require!(pubkey == signer.as_ref(), ErrorCode::InvalidSignature);
+    require!(signed_message == message, ErrorCode::InvalidSignature);
+
+    Ok(())
+}
+
+// Portion of an amount expressed in basis points
+fn calculate_bps_share(amount: u64, bps: u16) -> Result<u64> {
+    let share = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(share).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+// Price per whole token as Q64.64 lamports, the inverse of curve_rate_q64:
+// what the curve charges for TOKEN_PRECISION base units at this point in the
+// raise, rising as the bonus tapers off
+fn calculate_token_price_q64(curve: &CurveConfig, raised_amount: u64) -> Result<u128> {
+    let denominator = (curve.tokens_per_sol as u128)
+        .checked_mul(curve_multiplier_bps(curve, raised_amount))
+        .ok_or(ErrorCode::MathOverflow)?;
+    q64::ratio(
+        LAMPORTS_PER_SOL as u128 * BPS_DENOMINATOR as u128,
+        denominator,
+    )
+}
+
+// Calculate current token price in lamports
+fn calculate_token_price(curve: &CurveConfig, raised_amount: u64) -> Result<u64> {
+    u64::try_from(calculate_token_price_q64(curve, raised_amount)? >> 64)
+        .map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+// Post-graduation price in lamports per whole token: the primary's closing
+// price, rising by secondary_slope_bps of it for every SOL bought since. The
+// slope is at least 100%, so each SOL at least doubles it where the primary
+// stays flat once its bonus has run out.
+fn calculate_secondary_price(campaign: &Campaign, secondary_raised: u64) -> Result<u64> {
+    let scale = LAMPORTS_PER_SOL as u128 * BPS_DENOMINATOR as u128;
+    let price = (secondary_raised as u128 * campaign.secondary_slope_bps as u128 + scale)
+        .checked_mul(campaign.secondary_base_price as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / scale;
+    u64::try_from(price).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+// SOL returned for selling `token_amount` into the secondary curve, priced
+// after the sale like buys are. Solves sol = tokens * price(raised - sol),
+// with the tokens valued at the closing price first (rounded down).
+fn calculate_secondary_sale(campaign: &Campaign, token_amount: u64) -> Result<u64> {
+    let scale = LAMPORTS_PER_SOL as u128 * BPS_DENOMINATOR as u128;
+    let slope = campaign.secondary_slope_bps as u128;
+    let base_value = token_amount as u128 * campaign.secondary_base_price as u128 / TOKEN_PRECISION;
+    let numerator = (slope * campaign.secondary_raised as u128 + scale)
+        .checked_mul(base_value)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let denominator = base_value
+        .checked_mul(slope)
+        .and_then(|increase| increase.checked_add(scale))
+        .ok_or(ErrorCode::MathOverflow)?;
+    u64::try_from(numerator / denominator).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+// Accounts every curve sale touches
+struct CurveSale<'a, 'info> {
+    campaign: &'a mut Account<'info, Campaign>,
+    token_mint: AccountInfo<'info>,
+    seller_token_account: AccountInfo<'info>,
+    seller: AccountInfo<'info>,
+    contribution_record: &'a mut Account<'info, ContributionRecord>,
+    price_history: &'a mut PriceHistory,
+    price_feed: Option<&'a mut Account<'info, PriceFeed>>,
+    token_program: AccountInfo<'info>,
+}
+
+// Burn the seller's tokens against the curve and update campaign state.
+// Returns the SOL owed to the seller; callers move the lamports.
+fn sell_to_curve(sale: CurveSale, token_amount: u64) -> Result<u64> {
+    let campaign = sale.campaign;
+    let seller_key = sale.seller.key();
+
+    campaign.ensure_active()?;
+    require!(
+        campaign.ticket_mint == Pubkey::default(),
+        ErrorCode::TicketModeActive
+    );
+    validation::nonzero(token_amount)?;
+    let now = Clock::get()?.unix_timestamp;
+    require!(!campaign.donation_mode, ErrorCode::DonationModeUnsupported);
+    require!(!campaign.has_failed(now), ErrorCode::CampaignFailed);
+    campaign.check_team_embargo(seller_key, now)?;
+    // Keeps backers from flipping straight out of the early bonus. Only tokens
+    // bought on this wallet's own record are covered by its clock, so moving
+    // fresh tokens to an older wallet doesn't get them out early either.
+    if campaign.min_hold_secs > 0 {
+        require!(
+            now >= sale
+                .contribution_record
+                .last_buy_at
+                .saturating_add(campaign.min_hold_secs)
+                && token_amount <= sale.contribution_record.tokens_received,
+            ErrorCode::HoldingPeriodNotMet
+        );
+    }
+
+    let sol_amount = validation::output(calculate_sol_from_tokens(
+        &campaign.curve,
+        token_amount,
+        campaign.raised_amount,
+    )?)?;
+    require!(
+        sol_amount
+            <= campaign
+                .raised_amount
+                .saturating_sub(campaign.floor_reserve)
+                .saturating_sub(campaign.lent_amount),
+        ErrorCode::InsufficientCurveLiquidity
+    );
+
+    if campaign.freeze_during_raise {
+        set_token_account_frozen(
+            campaign,
+            sale.token_mint.clone(),
+            sale.seller_token_account.clone(),
+            sale.token_program.clone(),
+            false,
+        )?;
+    }
+
+    let cpi_accounts = token::Burn {
+        mint: sale.token_mint.clone(),
+        from: sale.seller_token_account.clone(),
+        authority: sale.seller,
+    };
+    token::burn(
+        CpiContext::new(sale.token_program.clone(), cpi_accounts),
+        token_amount,
+    )?;
+
+    if campaign.freeze_during_raise {
+        set_token_account_frozen(
+            campaign,
+            sale.token_mint,
+            sale.seller_token_account,
+            sale.token_program,
+            true,
+        )?;
+    }
+
+    campaign.release_supply(SupplyBucket::Curve, token_amount);
+    campaign.raised_amount -= sol_amount;
+    campaign.debit_balances(sol_amount);
+    let curve_fee = retain_curve_fee(campaign, seller_key, sol_amount, false)?;
+
+    // Release the reserve share that was locked when this SOL came in
+    let reserve_share = calculate_bps_share(sol_amount, campaign.floor_reserve_bps)?;
+    campaign.floor_reserve = campaign.floor_reserve.saturating_sub(reserve_share);
+
+    // Sold tokens no longer count towards the seller's voting weight, and
+    // their cost basis leaves what a refund would pay back
+    let record = sale.contribution_record;
+    let sold = token_amount.min(record.tokens_received);
+    if sold > 0 {
+        let basis =
+            (record.sol_contributed as u128) * (sold as u128) / (record.tokens_received as u128);
+        record.sol_contributed -= basis as u64;
+    }
+    record.tokens_received -= sold;
+    record.refresh_entry_price()?;
+    count_milestone_backer(campaign, record);
+    // Selling restarts the loyalty clock
+    record.holding_since = now;
+    rank_entry(
+        &mut sale.price_history.top_backers,
+        record.contributor,
+        record.sol_contributed,
+    );
+    if !record.has_sold {
+        record.has_sold = true;
+        campaign.unique_sellers = campaign.unique_sellers.saturating_add(1);
+    }
+
+    let price = calculate_token_price(&campaign.curve, campaign.raised_amount)?;
+    // Sells are never held back, so backers can always get out; they still
+    // feed the average the breaker compares buys against
+    let price_cumulative = campaign.accumulate_price(now, price)?;
+    sale.price_history
+        .record(now, campaign.raised_amount, price, price_cumulative);
+    publish_price_feed(campaign, sale.price_feed, price)?;
+
+    Ok(sol_amount - curve_fee)
+}
+
+// Compare a buy's price with the time-weighted average since the breaker
+// window opened, and reject it if it moved too far. Rejecting reverts the
+// whole transaction, so nothing is halted: smaller buys can still go
+// through, and the window rolls over after circuit_breaker_slots.
+// Call before accumulate_price so the average only covers earlier trades.
+fn check_circuit_breaker(campaign: &mut Account<Campaign>, now: i64, price: u64) -> Result<()> {
+    if campaign.circuit_breaker_bps == 0 {
+        return Ok(());
+    }
+    let slot = Clock::get()?.slot;
+    if campaign.breaker_window_at == 0
+        || slot.saturating_sub(campaign.breaker_window_slot) > campaign.circuit_breaker_slots
+    {
+        campaign.breaker_window_slot = slot;
+        campaign.breaker_window_at = now;
+        campaign.breaker_window_cumulative = campaign.cumulative_price_at(now)?;
+        campaign.breaker_window_price = campaign.last_trade_price;
+    }
+
+    let reference = if now > campaign.breaker_window_at {
+        let elapsed = (now - campaign.breaker_window_at) as u128;
+        u64::try_from(
+            (campaign.cumulative_price_at(now)? - campaign.breaker_window_cumulative) / elapsed,
+        )
+        .map_err(|_| ErrorCode::MathOverflow)?
+    } else {
+        campaign.breaker_window_price
+    };
+    if reference == 0 {
+        return Ok(());
+    }
+    let move_bps = (price.abs_diff(reference) as u128 * BPS_DENOMINATOR as u128 / reference as u128)
+        .min(u64::MAX as u128) as u64;
+    require!(
+        move_bps <= campaign.circuit_breaker_bps as u64,
+        ErrorCode::CircuitBreakerTripped
+    );
+    Ok(())
+}
+
+// Keep the campaign's curve fee on a trade in the vault for contributors
+fn retain_curve_fee(
+    campaign: &mut Account<Campaign>,
+    trader: Pubkey,
+    amount: u64,
+    is_buy: bool,
+) -> Result<u64> {
+    let fee_bps = if is_buy {
+        campaign.buy_fee_bps
+    } else {
+        campaign.sell_fee_bps
+    };
+    let fee = calculate_bps_share(amount, fee_bps)?;
+    if fee > 0 {
+        campaign.refund_buffer = campaign
+            .refund_buffer
+            .checked_add(fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+        emit_logged!(CurveFeeEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            trader,
+            is_buy,
+            amount: fee,
+            retained_total: campaign.refund_buffer,
+        });
+    }
+    Ok(fee)
+}
+
+// Where a contribution's platform fee is charged from and paid to
+struct PlatformFee<'a, 'info> {
+    config: &'a GlobalConfig,
+    // Its cumulative gross raise picks the fee tier
+    campaign: &'a mut Account<'info, Campaign>,
+    payer: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+    frontend: Option<&'a Account<'info, FrontendConfig>>,
+    frontend_payout: Option<AccountInfo<'info>>,
+    // Required once the insurance pool takes a share of the fee
+    insurance_pool: Option<&'a mut Account<'info, InsurancePool>>,
+    system_program: AccountInfo<'info>,
+}
+
+struct FeeSplit {
+    total: u64,
+    frontend: u64,
+}
+
+// Charge the platform fee on `amount`, routing the frontend's share to its payout address
+fn collect_platform_fee(fee: PlatformFee, amount: u64) -> Result<FeeSplit> {
+    let total = fee
+        .config
+        .tiered_fee(fee.campaign.fee_basis_raised, amount)?;
+    fee.campaign.fee_basis_raised = fee
+        .campaign
+        .fee_basis_raised
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let mut frontend_cut = 0;
+    if let Some(frontend) = fee.frontend {
+        let payout = fee
+            .frontend_payout
+            .ok_or(ErrorCode::InvalidFrontendPayout)?;
+        require_keys_eq!(
+            payout.key(),
+            frontend.payout,
+            ErrorCode::InvalidFrontendPayout
+        );
+
+        frontend_cut = calculate_bps_share(total, fee.config.frontend_fee_share_bps)?;
+        if frontend_cut > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    fee.system_program.clone(),
+                    anchor_lang::system_program::Transfer {
+                        from: fee.payer.clone(),
+                        to: payout,
+                    },
+                ),
+                frontend_cut,
+            )?;
+        }
+    }
+
+    let mut treasury_cut = total - frontend_cut;
+    let insurance_cut = calculate_bps_share(treasury_cut, fee.config.insurance_fee_share_bps)?;
+    if insurance_cut > 0 {
+        let pool = fee.insurance_pool.ok_or(ErrorCode::InsurancePoolRequired)?;
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                fee.system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: fee.payer.clone(),
+                    to: pool.to_account_info(),
+                },
+            ),
+            insurance_cut,
+        )?;
+        pool.accrued = pool
+            .accrued
+            .checked_add(insurance_cut)
+            .ok_or(ErrorCode::MathOverflow)?;
+        treasury_cut -= insurance_cut;
+    }
+    if treasury_cut > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                fee.system_program,
+                anchor_lang::system_program::Transfer {
+                    from: fee.payer,
+                    to: fee.treasury,
+                },
+            ),
+            treasury_cut,
+        )?;
+    }
+
+    Ok(FeeSplit {
+        total,
+        frontend: frontend_cut,
+    })
+}
+
+fn emit_frontend_fee(
+    campaign: &Account<Campaign>,
+    frontend: Option<&Account<FrontendConfig>>,
+    amount: u64,
+) {
+    if let Some(frontend) = frontend {
+        if amount > 0 {
+            emit_logged!(FrontendFeeEvent {
+                schema_version: EVENT_SCHEMA_VERSION,
+                campaign: campaign.key(),
+                frontend: frontend.key(),
+                payout: frontend.payout,
+                amount,
+            });
+        }
+    }
+}
+
+// Move a voter's tokens into the vote's escrow, thawing their account around
+// the transfer while the raise keeps holder accounts frozen
+fn escrow_vote_tokens<'info>(
+    campaign: &Account<'info, Campaign>,
+    mint: AccountInfo<'info>,
+    voter_account: AccountInfo<'info>,
+    escrow: AccountInfo<'info>,
+    voter: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let refreeze = campaign.freeze_during_raise && campaign.status == CampaignStatus::Active;
+    if refreeze {
+        set_token_account_frozen(
+            campaign,
+            mint.clone(),
+            voter_account.clone(),
+            token_program.clone(),
+            false,
+        )?;
+    }
+    token::transfer(
+        CpiContext::new(
+            token_program.clone(),
+            token::Transfer {
+                from: voter_account.clone(),
+                to: escrow,
+                authority: voter,
+            },
+        ),
+        amount,
+    )?;
+    if refreeze {
+        set_token_account_frozen(campaign, mint, voter_account, token_program, true)?;
+    }
+    Ok(())
+}
+
+// Hand escrowed vote tokens back and close the escrow to the voter
+#[allow(clippy::too_many_arguments)]
+fn release_vote_tokens<'info>(
+    campaign: &Account<'info, Campaign>,
+    mint: AccountInfo<'info>,
+    escrow: &Account<'info, TokenAccount>,
+    voter_account: AccountInfo<'info>,
+    vote: AccountInfo<'info>,
+    voter: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let amount = escrow.amount;
+    let refreeze = campaign.freeze_during_raise && campaign.status == CampaignStatus::Active;
+    if refreeze {
+        set_token_account_frozen(
+            campaign,
+            mint.clone(),
+            voter_account.clone(),
+            token_program.clone(),
+            false,
+        )?;
+    }
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            token::Transfer {
+                from: escrow.to_account_info(),
+                to: voter_account.clone(),
+                authority: vote.clone(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+    if refreeze {
+        set_token_account_frozen(campaign, mint, voter_account, token_program.clone(), true)?;
+    }
+    token::close_account(CpiContext::new_with_signer(
+        token_program,
+        token::CloseAccount {
+            account: escrow.to_account_info(),
+            destination: voter.clone(),
+            authority: vote,
+        },
+        signer,
+    ))?;
+
+    emit_logged!(VoteEscrowReleasedEvent {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign: campaign.key(),
+        voter: voter.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+// A realm account of SPL Governance: its type byte, followed by at least the community mint
+fn is_realm(realm: &AccountInfo) -> Result<bool> {
+    let data = realm.try_borrow_data()?;
+    Ok(data.len() >= 33 && REALM_ACCOUNT_TYPES.contains(&data[0]))
+}
+
+// Freeze or thaw a holder's campaign token account, signing as the campaign.
+// Does nothing when the account is already in the requested state.
+fn set_token_account_frozen<'info>(
+    campaign: &Account<'info, Campaign>,
+    mint: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    frozen: bool,
+) -> Result<()> {
+    let is_frozen = {
+        let data = account.try_borrow_data()?;
+        spl_token::state::Account::unpack(&data)?.is_frozen()
+    };
+    if is_frozen == frozen {
+        return Ok(());
+    }
+
+    let seeds = &[
+        b"campaign",
+        campaign.creator.as_ref(),
+        campaign.name_hash.as_ref(),
+        &[campaign.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    if frozen {
+        token::freeze_account(CpiContext::new_with_signer(
+            token_program,
+            token::FreezeAccount {
+                account,
+                mint,
+                authority: campaign.to_account_info(),
+            },
+            signer,
+        ))
+    } else {
+        token::thaw_account(CpiContext::new_with_signer(
+            token_program,
+            token::ThawAccount {
+                account,
+                mint,
+                authority: campaign.to_account_info(),
+            },
+            signer,
+        ))
+    }
+}
+
+// Walk a merkle proof using sorted pairs so the tree needs no position bits
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == root
+}
+
+// Root of a tree that verify_merkle_proof accepts: sorted pairs, with an odd
+// node out carried up unchanged
+fn merkle_root(mut level: Vec<[u8; 32]>) -> [u8; 32] {
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] if a <= b => hashv(&[a, b]).to_bytes(),
+                [a, b] => hashv(&[b, a]).to_bytes(),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    level.first().copied().unwrap_or_default()
+}
+
+// Move the sponsor's share of `amount` from the match escrow into the campaign
+fn draw_match(
+    campaign: &Account<Campaign>,
+    commitment: Option<&mut Account<MatchCommitment>>,
+    amount: u64,
+) -> Result<u64> {
+    if campaign.match_commitment == Pubkey::default() {
+        return Ok(0);
+    }
+    let commitment = commitment.ok_or(ErrorCode::MissingMatchCommitment)?;
+    require_keys_eq!(
+        commitment.key(),
+        campaign.match_commitment,
+        ErrorCode::MissingMatchCommitment
+    );
+    if Clock::get()?.unix_timestamp >= commitment.expiry {
+        return Ok(0);
+    }
+
+    let matched =
+        calculate_bps_share(amount, commitment.ratio_bps)?.min(commitment.cap - commitment.matched);
+    if matched == 0 {
+        return Ok(0);
+    }
+
+    **commitment.to_account_info().try_borrow_mut_lamports()? -= matched;
+    **campaign.to_account_info().try_borrow_mut_lamports()? += matched;
+    commitment.matched += matched;
+    commitment.basis = commitment
+        .basis
+        .checked_add(sponsor_basis(campaign, matched)?)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit_logged!(MatchDrawnEvent {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign: campaign.key(),
+        sponsor: commitment.sponsor,
+        amount: matched,
+        remaining: commitment.cap - commitment.matched,
+    });
+
+    Ok(matched)
+}
+
+// What a sponsor's match adds to raised_amount once the curve keeps its buy fee
+fn sponsor_basis(campaign: &Campaign, matched: u64) -> Result<u64> {
+    Ok(matched - calculate_bps_share(matched, campaign.buy_fee_bps)?)
+}
+
+// Contribution carried by a verified Wormhole message
+struct WormholeContribution {
+    emitter_chain: u16,
+    sequence: u64,
+    campaign: Pubkey,
+    beneficiary: Pubkey,
+}
+
+// N bytes at `at`, or an error when the message is too short to hold them
+fn vaa_bytes<const N: usize>(data: &[u8], at: usize) -> Result<[u8; N]> {
+    at.checked_add(N)
+        .and_then(|end| data.get(at..end))
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(ErrorCode::InvalidWormholeMessage.into())
+}
+
+// Read a PostedVAA the core bridge has already verified guardian signatures for.
+// Only the core bridge can create accounts it owns, so ownership plus the tag
+// is enough to trust the contents. The message must be a token bridge transfer
+// with payload to the config, sent by the configured EVM contract, whose own
+// payload is campaign (32) and beneficiary (32). The amount isn't read here:
+// only what the redemption actually delivers is credited.
+fn parse_posted_vaa(
+    posted_vaa: &AccountInfo,
+    config: &Account<WormholeConfig>,
+) -> Result<WormholeContribution> {
+    require_keys_eq!(
+        *posted_vaa.owner,
+        config.core_bridge,
+        ErrorCode::InvalidWormholeMessage
+    );
+
+    let data = posted_vaa.try_borrow_data()?;
+    require!(
+        data.get(..3) == Some(&POSTED_VAA_MAGIC[..]),
+        ErrorCode::InvalidWormholeMessage
+    );
+
+    // Fixed header: version, consistency, vaa_time, signature set, submission_time, nonce
+    let sequence = u64::from_le_bytes(vaa_bytes(&data, 49)?);
+    let emitter_chain = u16::from_le_bytes(vaa_bytes(&data, 57)?);
+    let payload_len = u32::from_le_bytes(vaa_bytes(&data, 91)?) as usize;
+    let payload = data
+        .get(95..)
+        .and_then(|rest| rest.get(..payload_len))
+        .ok_or(ErrorCode::InvalidWormholeMessage)?;
+    require!(
+        payload.len() == TRANSFER_WITH_PAYLOAD_HEADER_LEN + 64
+            && payload[0] == TRANSFER_WITH_PAYLOAD_ID,
+        ErrorCode::InvalidWormholeMessage
+    );
+
+    // Transfer fields: amount (32), token address (32) and chain (2), then the
+    // recipient and its chain, then the sending contract
+    let to: [u8; 32] = vaa_bytes(payload, 67)?;
+    let to_chain = u16::from_be_bytes(vaa_bytes(payload, 99)?);
+    let from: [u8; 32] = vaa_bytes(payload, 101)?;
+    require!(
+        emitter_chain == config.emitter_chain
+            && from == config.emitter_address
+            && to_chain == SOLANA_WORMHOLE_CHAIN_ID
+            && to == config.key().to_bytes(),
+        ErrorCode::InvalidWormholeMessage
+    );
+
+    Ok(WormholeContribution {
+        emitter_chain,
+        sequence,
+        campaign: Pubkey::new_from_array(vaa_bytes(payload, TRANSFER_WITH_PAYLOAD_HEADER_LEN)?),
+        beneficiary: Pubkey::new_from_array(vaa_bytes(
+            payload,
+            TRANSFER_WITH_PAYLOAD_HEADER_LEN + 32,
+        )?),
+    })
+}
+
+// Complete the VAA's wrapped transfer on the token bridge, minting it into
+// custody. The config is the transfer's redeemer and signs for it.
+fn redeem_wrapped_transfer(accounts: &ContributeFromWormhole) -> Result<()> {
+    let ix = Instruction {
+        program_id: accounts.token_bridge_program.key(),
+        accounts: vec![
+            AccountMeta::new(accounts.relayer.key(), true),
+            AccountMeta::new_readonly(accounts.token_bridge_config.key(), false),
+            AccountMeta::new_readonly(accounts.posted_vaa.key(), false),
+            AccountMeta::new(accounts.token_bridge_claim.key(), false),
+            AccountMeta::new_readonly(accounts.token_bridge_endpoint.key(), false),
+            AccountMeta::new(accounts.custody.key(), false),
+            AccountMeta::new_readonly(accounts.wormhole_config.key(), true),
+            AccountMeta::new(accounts.relayer_usdc_account.key(), false),
+            AccountMeta::new(accounts.usdc_mint.key(), false),
+            AccountMeta::new_readonly(accounts.wrapped_meta.key(), false),
+            AccountMeta::new_readonly(accounts.token_bridge_mint_authority.key(), false),
+            AccountMeta::new_readonly(accounts.rent.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new_readonly(accounts.core_bridge_program.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+        ],
+        data: vec![COMPLETE_WRAPPED_WITH_PAYLOAD_IX],
+    };
+    let cpi_accounts = [
+        accounts.relayer.to_account_info(),
+        accounts.token_bridge_config.to_account_info(),
+        accounts.posted_vaa.to_account_info(),
+        accounts.token_bridge_claim.to_account_info(),
+        accounts.token_bridge_endpoint.to_account_info(),
+        accounts.custody.to_account_info(),
+        accounts.wormhole_config.to_account_info(),
+        accounts.relayer_usdc_account.to_account_info(),
+        accounts.usdc_mint.to_account_info(),
+        accounts.wrapped_meta.to_account_info(),
+        accounts.token_bridge_mint_authority.to_account_info(),
+        accounts.rent.to_account_info(),
+        accounts.system_program.to_account_info(),
+        accounts.core_bridge_program.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.token_bridge_program.to_account_info(),
+    ];
+    let config_seeds = &[
+        b"wormhole_config".as_ref(),
+        &[accounts.wormhole_config.bump],
+    ];
+    invoke_signed(&ix, &cpi_accounts, &[&config_seeds[..]])?;
+    Ok(())
+}
+
+// Check a creator withdrawal against the campaign's obligations and move the
+// lamports, less any exit fee; returns what reached the destination
+fn release_withdrawal(
+    campaign: &mut Account<Campaign>,
+    config: &GlobalConfig,
+    // The withdrawer's, screened like every buyer's
+    denylist_entry: Option<&UncheckedAccount>,
+    approval: Option<&Account<WithdrawalApproval>>,
+    destination: &AccountInfo,
+    amount: u64,
+) -> Result<u64> {
+    check_denylist(config, denylist_entry)?;
+    // Funds of a failed campaign belong to its contributors
+    require!(
+        !campaign.has_failed(Clock::get()?.unix_timestamp),
+        ErrorCode::CampaignFailed
+    );
+    require!(!campaign.paused, ErrorCode::CampaignPaused);
+    require!(
+        amount <= campaign.raised_amount,
+        ErrorCode::WithdrawalExceedsRaised
+    );
+    require!(
+        amount
+            <= campaign
+                .raised_amount
+                .saturating_sub(campaign.floor_reserve),
+        ErrorCode::ReserveLocked
+    );
+    // The curve reserve stays behind to honour sells
+    require!(
+        amount <= campaign.withdrawable_balance,
+        ErrorCode::CurveReserveLocked
+    );
+    draw_drip_allowance(campaign, amount)?;
+    // and so does whatever queued exits are still owed
+    require!(
+        amount
+            <= campaign
+                .withdrawable_balance
+                .saturating_sub(campaign.pending_exit_sol),
+        ErrorCode::ExitQueueNotEmpty
+    );
+
+    require!(
+        campaign.withdrawn_amount.saturating_add(amount) <= campaign.withdrawal_cap(),
+        ErrorCode::StretchGoalLocked
+    );
+    // Once unapproved withdrawals would pass the threshold, every top backer
+    // snapshotted for this withdrawal has to sign off; an approved one starts
+    // the running total over
+    if campaign.needs_withdrawal_approval(amount) {
+        let approval = approval.ok_or(ErrorCode::WithdrawalNotApproved)?;
+        require!(
+            approval.amount == amount && approval.is_complete(),
+            ErrorCode::WithdrawalNotApproved
+        );
+        campaign.unapproved_withdrawn = 0;
+    } else if campaign.withdrawal_approval_threshold > 0 {
+        campaign.unapproved_withdrawn = campaign
+            .unapproved_withdrawn
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    let campaign_lamports = campaign.to_account_info().lamports();
+    require!(
+        amount <= campaign_lamports,
+        ErrorCode::InsufficientCampaignBalance
+    );
+
+    // The exit fee stays in the campaign account for stakers, or for whoever
+    // stakes next while nobody is
+    let exit_fee = (amount as u128 * campaign.exit_fee_bps as u128 / 10_000) as u64;
+    let released = amount - exit_fee;
+
+    **campaign.to_account_info().try_borrow_mut_lamports()? -= released;
+    **destination.try_borrow_mut_lamports()? += released;
+
+    campaign.raised_amount -= amount;
+    campaign.withdrawable_balance -= amount;
+    campaign.withdrawn_amount = campaign
+        .withdrawn_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    if exit_fee > 0 {
+        campaign.accrue_exit_fee(exit_fee)?;
+        emit_logged!(ExitFeeCollectedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            amount: exit_fee,
+            total_staked: campaign.total_staked,
+        });
+    }
+
+    Ok(released)
+}
+
+// Accounts that settle a contribution once its own path has checked and funded
+// it, whichever instruction it came in through
+struct ContributionPayment<'a, 'info> {
+    campaign: &'a mut Account<'info, Campaign>,
+    global_config: &'a GlobalConfig,
+    // Pays the platform fee and the contribution itself
+    payer: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+    insurance_pool: Option<&'a mut Account<'info, InsurancePool>>,
+    frontend: Option<&'a Account<'info, FrontendConfig>>,
+    frontend_payout: Option<AccountInfo<'info>>,
+    // Whether a sponsor's match is drawn for this purchase, and the commitment
+    // that's required to do so while one is registered
+    matchable: bool,
+    match_commitment: Option<&'a mut Account<'info, MatchCommitment>>,
+    token_mint: AccountInfo<'info>,
+    recipient_token_account: AccountInfo<'info>,
+    contribution_record: &'a mut Account<'info, ContributionRecord>,
+    record_bump: u8,
+    price_history: &'a mut PriceHistory,
+    price_feed: Option<&'a mut Account<'info, PriceFeed>>,
+    ticket: Option<(AccountInfo<'info>, AccountInfo<'info>)>,
+    token_program: AccountInfo<'info>,
+    tier_bonus_bps: Option<u16>,
+    denylist_entry: Option<&'a UncheckedAccount<'info>>,
+    // None for purchases that carry no governance weight
+    governance: Option<GovernanceMint<'a, 'info>>,
+    leaderboard: Option<&'a AccountLoader<'info, Leaderboard>>,
+    // None for purchases that don't count towards the beneficiary's backing
+    backer_profile: Option<&'a UncheckedAccount<'info>>,
+    system_program: AccountInfo<'info>,
+    reference: Option<Pubkey>,
+}
+
+// Charge the fee, draw any sponsor match, buy from the curve for `beneficiary`,
+// move the SOL into the campaign and do the bookkeeping every contribution
+// shares. `amount` is what's left after the hard cap, before the fee.
+fn settle_contribution(
+    payment: ContributionPayment,
+    beneficiary: Pubkey,
+    amount: u64,
+) -> Result<ContributionOutcome> {
+    let first_contribution = payment.contribution_record.contributor == Pubkey::default();
+    let opens_position = payment.contribution_record.sol_contributed == 0;
+
+    // Platform fee comes out of the contribution before it reaches the curve
+    let fee = collect_platform_fee(
+        PlatformFee {
+            config: payment.global_config,
+            campaign: &mut *payment.campaign,
+            payer: payment.payer.clone(),
+            treasury: payment.treasury,
+            insurance_pool: payment.insurance_pool,
+            frontend: payment.frontend,
+            frontend_payout: payment.frontend_payout,
+            system_program: payment.system_program.clone(),
+        },
+        amount,
+    )?;
+    let net_amount = amount - fee.total;
+
+    // A sponsor's match rides along with the contribution it's drawn for
+    let matched = if payment.matchable {
+        draw_match(payment.campaign, payment.match_commitment, net_amount)?
+    } else {
+        0
+    };
+
+    let tokens_to_mint = buy_from_curve(
+        CurvePurchase {
+            campaign: &mut *payment.campaign,
+            token_mint: payment.token_mint,
+            recipient_token_account: payment.recipient_token_account,
+            contribution_record: Some(payment.contribution_record),
+            record_bump: payment.record_bump,
+            price_history: payment.price_history,
+            price_feed: payment.price_feed,
+            ticket: payment.ticket,
+            token_program: payment.token_program,
+            fee_paid: fee.total,
+            from_queue: false,
+            tier_bonus_bps: payment.tier_bonus_bps,
+            sponsored: matched,
+            global_config: payment.global_config,
+            denylist_entry: payment.denylist_entry,
+        },
+        beneficiary,
+        net_amount + matched,
+    )?;
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            payment.system_program,
+            anchor_lang::system_program::Transfer {
+                from: payment.payer,
+                to: payment.campaign.to_account_info(),
+            },
+        ),
+        net_amount,
+    )?;
+
+    // Governance tokens are minted 1:1 alongside the curve tokens
+    if let Some(governance) = payment.governance {
+        if payment.campaign.governance_mint != Pubkey::default() {
+            mint_governance_tokens(payment.campaign, governance, tokens_to_mint)?;
+        }
+    }
+
+    if let Some(leaderboard) = payment.leaderboard {
+        leaderboard.load_mut()?.record(
+            payment.campaign.key(),
+            payment.campaign.raised_amount,
+            payment.campaign.contributor_count,
+        );
+    }
+    if let Some(backer_profile) = payment.backer_profile {
+        update_backer_profile(backer_profile, |profile| {
+            profile.record_contribution(first_contribution, opens_position, amount)
+        })?;
+    }
+
+    emit_logged!(ContributionEvent {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign: payment.campaign.key(),
+        contributor: beneficiary,
+        sol_amount: net_amount,
+        token_amount: tokens_to_mint,
+        new_total: payment.campaign.raised_amount,
+        fee_amount: fee.total,
+        reference: payment.reference,
+    });
+    emit_frontend_fee(payment.campaign, payment.frontend, fee.frontend);
+
+    Ok(ContributionOutcome {
+        sol_amount: net_amount,
+        fee_amount: fee.total,
+        matched_amount: matched,
+        token_amount: tokens_to_mint,
+        new_total: payment.campaign.raised_amount,
+        new_price: calculate_token_price(&payment.campaign.curve, payment.campaign.raised_amount)?,
+    })
+}
+
+// Shared by contribute and its dry run, which throws the state changes away
+fn process_contribution<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Contribute<'info>>,
+    amount: u64,
+    client_nonce: Option<u64>,
+    reference: Option<Pubkey>,
+    tier_bonus_bps: Option<u16>,
+    split_bps: &[u16],
+) -> Result<ContributionOutcome> {
+    let contributor_key = ctx.accounts.contributor.key();
+    let accounts = ctx.accounts;
+
+    // Retries of a timed-out transaction reuse their nonce and are rejected
+    if let Some(nonce) = client_nonce {
+        require!(
+            nonce > accounts.contribution_record.client_nonce,
+            ErrorCode::NonceAlreadyUsed
+        );
+        accounts.contribution_record.client_nonce = nonce;
+    }
+
+    check_region_access(
+        &accounts.campaign,
+        accounts.region_attestation.as_ref(),
+        contributor_key,
+    )?;
+
+    // Nothing has moved yet, so whatever doesn't fit under the hard cap stays in the wallet
+    let amount = refund_excess(
+        &accounts.campaign,
+        None,
+        &accounts.contributor.to_account_info(),
+        amount,
+    )?;
+    require!(amount > 0, ErrorCode::HardCapExceeded);
+    let basis_before = accounts.contribution_record.sol_contributed;
+
+    let outcome = settle_contribution(
+        ContributionPayment {
+            campaign: &mut accounts.campaign,
+            global_config: &accounts.global_config,
+            payer: accounts.contributor.to_account_info(),
+            treasury: accounts.treasury.to_account_info(),
+            insurance_pool: accounts.insurance_pool.as_mut(),
+            frontend: accounts.frontend.as_deref(),
+            frontend_payout: accounts
+                .frontend_payout
+                .as_ref()
+                .map(|payout| payout.to_account_info()),
+            matchable: true,
+            match_commitment: accounts.match_commitment.as_deref_mut(),
+            token_mint: accounts.token_mint.to_account_info(),
+            recipient_token_account: accounts.contributor_token_account.to_account_info(),
+            contribution_record: &mut accounts.contribution_record,
+            record_bump: ctx.bumps.contribution_record,
+            price_history: &mut *accounts.price_history.load_mut()?,
+            price_feed: accounts.price_feed.as_deref_mut(),
+            ticket: accounts
+                .ticket_mint
+                .as_ref()
+                .zip(accounts.contributor_ticket_account.as_ref())
+                .map(|(mint, account)| (mint.to_account_info(), account.to_account_info())),
+            token_program: accounts.token_program.to_account_info(),
+            tier_bonus_bps,
+            denylist_entry: accounts.denylist_entry.as_ref(),
+            governance: Some(GovernanceMint {
+                governance_mint: accounts.governance_mint.as_ref(),
+                governance_authority: accounts.governance_authority.as_ref(),
+                holder_account: accounts.contributor_governance_account.as_ref(),
+                token_2022_program: accounts.token_2022_program.as_ref(),
+                holder: accounts.contributor.to_account_info(),
+                payer: accounts.contributor.to_account_info(),
+                associated_token_program: accounts.associated_token_program.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+            }),
+            leaderboard: accounts.leaderboard.as_ref(),
+            backer_profile: Some(&accounts.backer_profile),
+            system_program: accounts.system_program.to_account_info(),
+            reference,
+        },
+        contributor_key,
+        amount,
+    )?;
+
+    if !split_bps.is_empty() {
+        let basis = accounts.contribution_record.sol_contributed - basis_before;
+        split_contribution(
+            accounts,
+            ctx.remaining_accounts,
+            split_bps,
+            outcome.token_amount,
+            basis,
+        )?;
+    }
+
+    assert_invariants(
+        &accounts.campaign,
+        Some(accounts.token_mint.to_account_info()),
+    )?;
+
+    Ok(outcome)
+}
+
+// Hands each beneficiary its share of a contribution's tokens together with the
+// matching slice of cost basis, so the share is a position of its own rather
+// than tokens the beneficiary can neither refund nor sell through the curve
+fn split_contribution<'info>(
+    accounts: &mut Contribute<'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    split_bps: &[u16],
+    tokens: u64,
+    basis: u64,
+) -> Result<()> {
+    let campaign_key = accounts.campaign.key();
+    let contributor_key = accounts.contributor.key();
+    let now = Clock::get()?.unix_timestamp;
+    let mut beneficiaries = Vec::with_capacity(split_bps.len());
+    let mut amounts = Vec::with_capacity(split_bps.len());
+    let mut basis_amounts = Vec::with_capacity(split_bps.len());
+
+    for (pair, bps) in remaining_accounts.chunks_exact(2).zip(split_bps) {
+        let (token_account_info, record_info) = (&pair[0], &pair[1]);
+        let token_account = Account::<TokenAccount>::try_from(token_account_info)?;
+        let beneficiary = token_account.owner;
+        require_keys_eq!(
+            token_account_info.key(),
+            associated_token::get_associated_token_address(
+                &beneficiary,
+                &accounts.campaign.token_mint
+            ),
+            ErrorCode::InvalidContributionSplit
+        );
+        // Each beneficiary gets one record, and never the contributor's own
+        require!(
+            beneficiary != contributor_key && !beneficiaries.contains(&beneficiary),
+            ErrorCode::InvalidContributionSplit
+        );
+
+        let (record_key, record_bump) = Pubkey::find_program_address(
+            &[b"contribution", campaign_key.as_ref(), beneficiary.as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(
+            record_info.key(),
+            record_key,
+            ErrorCode::InvalidContributionSplit
+        );
+        let mut record = if record_info.data_is_empty() {
+            let space = 8 + ContributionRecord::INIT_SPACE;
+            anchor_lang::system_program::create_account(
+                CpiContext::new_with_signer(
+                    accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::CreateAccount {
+                        from: accounts.contributor.to_account_info(),
+                        to: record_info.clone(),
+                    },
+                    &[&[
+                        b"contribution",
+                        campaign_key.as_ref(),
+                        beneficiary.as_ref(),
+                        &[record_bump],
+                    ]],
+                ),
+                Rent::get()?.minimum_balance(space),
+                space as u64,
+                &crate::ID,
+            )?;
+            Box::new(Account::<ContributionRecord>::try_from_unchecked(
+                record_info,
+            )?)
+        } else {
+            Box::new(Account::<ContributionRecord>::try_from(record_info)?)
+        };
+
+        let share = calculate_bps_share(tokens, *bps)?;
+        let share_basis = calculate_bps_share(basis, *bps)?;
+        if share > 0 {
+            token::transfer(
+                CpiContext::new(
+                    accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: accounts.contributor_token_account.to_account_info(),
+                        to: token_account_info.clone(),
+                        authority: accounts.contributor.to_account_info(),
+                    },
+                ),
+                share,
+            )?;
+        }
+
+        if record.contributor == Pubkey::default() {
+            record.campaign = campaign_key;
+            record.contributor = beneficiary;
+            record.bump = record_bump;
+        }
+        if record.sol_contributed == 0 {
+            record.holding_since = now;
+        }
+        record.last_buy_at = hold_start(&record, now, share);
+        record.tokens_received = record
+            .tokens_received
+            .checked_add(share)
+            .ok_or(ErrorCode::MathOverflow)?;
+        record.sol_contributed = record
+            .sol_contributed
+            .checked_add(share_basis)
+            .ok_or(ErrorCode::MathOverflow)?;
+        record.refresh_entry_price()?;
+        count_milestone_backer(&mut accounts.campaign, &mut record);
+        record.exit(&crate::ID)?;
+
+        let source = &mut accounts.contribution_record;
+        source.tokens_received = source
+            .tokens_received
+            .checked_sub(share)
+            .ok_or(ErrorCode::MathOverflow)?;
+        source.sol_contributed = source
+            .sol_contributed
+            .checked_sub(share_basis)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        beneficiaries.push(beneficiary);
+        amounts.push(share);
+        basis_amounts.push(share_basis);
+    }
+    accounts.contribution_record.refresh_entry_price()?;
+    count_milestone_backer(&mut accounts.campaign, &mut accounts.contribution_record);
+
+    emit_logged!(ContributionSplitEvent {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign: campaign_key,
+        contributor: contributor_key,
+        token_amount: tokens,
+        beneficiaries,
+        split_bps: split_bps.to_vec(),
+        amounts,
+        basis_amounts,
+    });
+
+    Ok(())
+}
+
+// When a position's minimum hold runs from: the token-weighted average of its
+// last start and now, so topping up restarts the clock only in proportion and a
+// dust buy into someone else's position (an adapter deposit, a split) can't
+// lock them in
+fn hold_start(record: &ContributionRecord, now: i64, tokens: u64) -> i64 {
+    let held = record.tokens_received as i128;
+    let total = held + tokens as i128;
+    if held == 0 || total == 0 {
+        return now;
+    }
+    ((record.last_buy_at as i128 * held + now as i128 * tokens as i128) / total) as i64
+}
+
+// Campaigns that restrict regions only accept holders attested to an allowed one
+fn check_region_access(
+    campaign: &Campaign,
+    attestation: Option<&Account<RegionAttestation>>,
+    holder: Pubkey,
+) -> Result<()> {
+    if campaign.restricted_regions_bitmap == 0 {
+        return Ok(());
+    }
+    let attestation = attestation.ok_or(ErrorCode::RegionAttestationRequired)?;
+    require_keys_eq!(
+        attestation.holder,
+        holder,
+        ErrorCode::RegionAttestationRequired
+    );
+    require!(
+        campaign.restricted_regions_bitmap & (1u64 << attestation.region) == 0,
+        ErrorCode::RegionRestricted
+    );
+    Ok(())
+}
+
+// Accounts of the admin's lending market that every deposit and withdrawal touches
+struct LendingCpi<'info> {
+    program: AccountInfo<'info>,
+    reserve: AccountInfo<'info>,
+    liquidity_supply: AccountInfo<'info>,
+    position: AccountInfo<'info>,
+    yield_vault: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+}
+
+// Call the lending market's deposit or withdraw instruction for `amount` of
+// wrapped SOL, with the campaign signing as the owner of its yield vault and
+// position. Both are built here so the campaign's signature only ever moves
+// funds between its own vault and its own position.
+fn invoke_lending<'info>(
+    campaign: &Account<'info, Campaign>,
+    lending: LendingCpi<'info>,
+    discriminator: [u8; 8],
+    amount: u64,
+) -> Result<()> {
+    let mut data = discriminator.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    let ix = Instruction {
+        program_id: lending.program.key(),
+        accounts: vec![
+            AccountMeta::new(lending.reserve.key(), false),
+            AccountMeta::new(lending.liquidity_supply.key(), false),
+            AccountMeta::new(lending.position.key(), false),
+            AccountMeta::new(lending.yield_vault.key(), false),
+            AccountMeta::new_readonly(campaign.key(), true),
+            AccountMeta::new_readonly(lending.token_program.key(), false),
+        ],
+        data,
+    };
+    let cpi_accounts = [
+        lending.reserve,
+        lending.liquidity_supply,
+        lending.position,
+        lending.yield_vault,
+        campaign.to_account_info(),
+        lending.token_program,
+        lending.program,
+    ];
+    let seeds = &[
+        b"campaign",
+        campaign.creator.as_ref(),
+        campaign.name_hash.as_ref(),
+        &[campaign.bump],
+    ];
+    invoke_signed(&ix, &cpi_accounts, &[&seeds[..]])?;
+    Ok(())
+}
+
+// Receipt leaf for a compressed contribution; the index keeps repeat
+// contributions of the same size distinct
+fn compressed_record_leaf(
+    campaign: Pubkey,
+    contributor: Pubkey,
+    sol_amount: u64,
+    token_amount: u64,
+    leaf_index: u64,
+) -> [u8; 32] {
+    hashv(&[
+        campaign.as_ref(),
+        contributor.as_ref(),
+        &sol_amount.to_le_bytes(),
+        &token_amount.to_le_bytes(),
+        &leaf_index.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+// Call an spl-account-compression instruction on the campaign's tree, signing
+// as the tree authority. `args` is the Borsh encoding of its arguments.
+fn invoke_compression<'info>(
+    campaign: &Account<'info, Campaign>,
+    compression_program: &AccountInfo<'info>,
+    merkle_tree: &AccountInfo<'info>,
+    noop_program: &AccountInfo<'info>,
+    name: &str,
+    args: &[u8],
+    proof: &[AccountInfo<'info>],
+) -> Result<()> {
+    let mut data = hash(format!("global:{name}").as_bytes()).to_bytes()[..8].to_vec();
+    data.extend_from_slice(args);
+
+    let mut metas = vec![
+        AccountMeta::new(merkle_tree.key(), false),
+        AccountMeta::new_readonly(campaign.key(), true),
+        AccountMeta::new_readonly(noop_program.key(), false),
+    ];
+    metas.extend(
+        proof
+            .iter()
+            .map(|node| AccountMeta::new_readonly(node.key(), false)),
+    );
+    let ix = Instruction {
+        program_id: compression_program.key(),
+        accounts: metas,
+        data,
+    };
+
+    let mut cpi_accounts = vec![
+        merkle_tree.clone(),
+        campaign.to_account_info(),
+        noop_program.clone(),
+    ];
+    cpi_accounts.extend(proof.iter().cloned());
+    cpi_accounts.push(compression_program.clone());
+    let seeds = &[
+        b"campaign",
+        campaign.creator.as_ref(),
+        campaign.name_hash.as_ref(),
+        &[campaign.bump],
+    ];
+    invoke_signed(&ix, &cpi_accounts, &[&seeds[..]])?;
+    Ok(())
+}
+
+// Append the next receipt in the campaign's withdrawal sequence
+fn record_withdrawal(
+    campaign: &mut Account<Campaign>,
+    receipt: &mut Account<WithdrawalReceipt>,
+    bump: u8,
+    amount: u64,
+    purpose: WithdrawalPurpose,
+    memo_hash: [u8; 32],
+) -> Result<()> {
+    receipt.campaign = campaign.key();
+    receipt.index = campaign.withdrawal_count;
+    receipt.amount = amount;
+    receipt.purpose = purpose;
+    receipt.memo_hash = memo_hash;
+    receipt.timestamp = Clock::get()?.unix_timestamp;
+    receipt.bump = bump;
+    campaign.withdrawal_count += 1;
+
+    emit_logged!(WithdrawalReceiptEvent {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign: receipt.campaign,
+        index: receipt.index,
+        amount,
+        purpose,
+        memo_hash,
+    });
+
+    Ok(())
+}
+
+// Run at the end of every instruction that moves campaign funds or supply.
+// The cheap checks always run; builds with the strict-invariants feature (as
+// the test suite uses) also reconcile the vault and the mint.
+#[cfg_attr(not(feature = "strict-invariants"), allow(unused_variables))]
+fn assert_invariants(campaign: &Account<Campaign>, token_mint: Option<AccountInfo>) -> Result<()> {
+    let info = campaign.to_account_info();
+    let rent_reserve = Rent::get()?.minimum_balance(info.data_len());
+    require!(
+        info.lamports() >= rent_reserve,
+        ErrorCode::RentReserveViolated
+    );
+    require!(
+        campaign.floor_reserve <= campaign.raised_amount,
+        ErrorCode::InvariantViolated
+    );
+
+    #[cfg(feature = "strict-invariants")]
+    {
+        // Everything raised and not withdrawn must still be in the vault, apart from what's lent out
+        let owed = campaign
+            .raised_amount
+            .saturating_sub(campaign.lent_amount)
+            .checked_add(campaign.refund_buffer)
+            .and_then(|owed| owed.checked_add(campaign.secondary_raised))
+            .and_then(|owed| owed.checked_add(campaign.exit_rewards_pool))
+            .and_then(|owed| owed.checked_add(rent_reserve))
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(info.lamports() >= owed, ErrorCode::InvariantViolated);
+        require!(
+            campaign
+                .reserve_balance
+                .saturating_add(campaign.withdrawable_balance)
+                <= campaign.raised_amount,
+            ErrorCode::InvariantViolated
+        );
+        if let Some(mint) = token_mint {
+            let supply = spl_token::state::Mint::unpack(&mint.try_borrow_data()?)?.supply;
+            require!(
+                supply <= campaign.total_supply,
+                ErrorCode::InvariantViolated
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// Name and discriminator of every event the program can emit
+fn event_schema() -> Vec<EventSchemaEntry> {
+    macro_rules! entries {
+        ($($event:ident),* $(,)?) => {
+            vec![$(EventSchemaEntry {
+                name: stringify!($event).to_string(),
+                discriminator: $event::DISCRIMINATOR.to_vec(),
+            }),*]
+        };
+    }
+    entries!(
+        AdapterDepositEvent,
+        AddressAllowedEvent,
+        AddressDeniedEvent,
+        AdminTransferredToGovernanceEvent,
+        AirdropClaimedEvent,
+        AirdropConfiguredEvent,
+        AllowlistContributionEvent,
+        AllowlistTierConfiguredEvent,
+        AttestationPostedEvent,
+        BackerProfileInitializedEvent,
+        BadgeClaimedEvent,
+        BasketContributionEvent,
+        CampaignArchivedEvent,
+        CampaignAuthorityTransferredEvent,
+        CampaignCreatedEvent,
+        CampaignLaunchedEvent,
+        CampaignMetadataUpdatedEvent,
+        CampaignPausedEvent,
+        CampaignReviewedEvent,
+        CampaignSettledEvent,
+        CampaignSnapshotEvent,
+        CampaignUpdatePostedEvent,
+        ClosingAuctionOpenedEvent,
+        ClosingAuctionSettledEvent,
+        ClosingBidPlacedEvent,
+        ClosingBidReclaimedEvent,
+        ClosingBidRevealedEvent,
+        CoCreatorUpdatedEvent,
+        CompressedContributionEvent,
+        CompressedRecordClaimedEvent,
+        CompressedRecordsEnabledEvent,
+        ContentKeyRegisteredEvent,
+        ContentKeyRevokedEvent,
+        ContentThresholdSetEvent,
+        ContributionEvent,
+        ContributionReceiptEvent,
+        ContributionSplitEvent,
+        ContributionsPausedEvent,
+        ContributorMilestoneLostEvent,
+        ContributorMilestoneReachedEvent,
+        CurveDiscrepancyEvent,
+        CurveFeeEvent,
+        DeploymentVerifiedEvent,
+        DepositAdapterRegisteredEvent,
+        DepositAdapterUpdatedEvent,
+        DistributionFinalizedEvent,
+        DonationReceiptMintedEvent,
+        EventSchemaEvent,
+        ExcessRefundedEvent,
+        ExchangeClaimEvent,
+        ExitFeeCollectedEvent,
+        ExitProcessedEvent,
+        ExitRequestedEvent,
+        ExtensionFinalizedEvent,
+        ExtensionProposedEvent,
+        ExtensionVoteEvent,
+        FaqEntryUpdatedEvent,
+        FloorRedemptionEvent,
+        FraudRulingEvent,
+        FrontendFeeEvent,
+        FrontendPayoutUpdatedEvent,
+        FrontendRegisteredEvent,
+        GatedContentPostedEvent,
+        GlobalConfigUpdatedEvent,
+        GovernanceTokensUnlockedEvent,
+        GraduationEvent,
+        GraduationReadyEvent,
+        IdentityLinkedEvent,
+        IdentityUnlinkedEvent,
+        InsuranceClaimEvent,
+        LaunchSlotReleasedEvent,
+        LaunchSlotReservedEvent,
+        LeaderboardInitializedEvent,
+        LendingDepositEvent,
+        LendingRecallEvent,
+        LiquidityLockedEvent,
+        LiquidityUnlockVoteEvent,
+        LiquidityUnlockedEvent,
+        LoyaltyRebateEvent,
+        MatchClosedEvent,
+        MatchDrawnEvent,
+        MatchRefundedEvent,
+        MatchRegisteredEvent,
+        NameBlockedEvent,
+        ParentCampaignResolvedEvent,
+        PreDepositCancelledEvent,
+        PreDepositEvent,
+        PreDepositProcessedEvent,
+        PriceCrankFundedEvent,
+        PriceFeedInitializedEvent,
+        PriceHistoryMigratedEvent,
+        RebatePoolConfiguredEvent,
+        RefundBatchCrankedEvent,
+        RefundEvent,
+        RegionAttestedEvent,
+        RelayEscrowWithdrawnEvent,
+        RelayedContributionEvent,
+        ReservedAllocationMintedEvent,
+        RevenueClaimedEvent,
+        RevenueDepositedEvent,
+        RevenueEpochClosedEvent,
+        RevenueSnapshotEvent,
+        RevenueStreamRegisteredEvent,
+        SalaryClaimedEvent,
+        SalaryStreamOpenedEvent,
+        SalaryStreamPausedEvent,
+        SecondaryTradeEvent,
+        SellEvent,
+        StablecoinWithdrawalEvent,
+        StakeEvent,
+        StakingRewardsClaimedEvent,
+        StaleCampaignExpiredEvent,
+        StretchGoalReachedEvent,
+        SymbolReleasedEvent,
+        ThawEvent,
+        TicketRedeemedEvent,
+        TierBonusClaimedEvent,
+        TokenPriceEvent,
+        TokensInvalidatedEvent,
+        TreasuryBuyEvent,
+        TwapEvent,
+        UnstakeEvent,
+        VestedTreasuryReleasedEvent,
+        VoteEscrowReleasedEvent,
+        WithdrawalApprovedEvent,
+        WithdrawalEvent,
+        WithdrawalProposalCancelledEvent,
+        WithdrawalProposedEvent,
+        WithdrawalReceiptEvent,
+        WormholeContributionEvent,
+    )
+}
+
+// Trim a purchase to what fits under the campaign's hard cap. An escrow that
+// already holds the funds pays the excess back to the recipient; a wallet
+// simply never sends it. Returns the amount left to fill.
+fn refund_excess(
+    campaign: &Account<Campaign>,
+    escrow: Option<&AccountInfo>,
+    recipient: &AccountInfo,
+    amount: u64,
+) -> Result<u64> {
+    let fill = campaign.cap_room(amount);
+    let excess = amount - fill;
+    if excess > 0 {
+        if let Some(escrow) = escrow {
+            **escrow.try_borrow_mut_lamports()? -= excess;
+            **recipient.try_borrow_mut_lamports()? += excess;
+        }
+        emit_logged!(ExcessRefundedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            recipient: recipient.key(),
+            amount: excess,
+        });
+    }
+    Ok(fill)
+}
+
+// Accounts a refund burns against and pays out to
+struct RefundBurn<'a, 'info> {
+    campaign: &'a mut Account<'info, Campaign>,
+    record: &'a mut Account<'info, ContributionRecord>,
+    mint: AccountInfo<'info>,
+    contributor_token_account: AccountInfo<'info>,
+    contributor: AccountInfo<'info>,
+    // Where the payout lands; redirects take it from there into the next campaign
+    destination: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+}
+
+// Burn a failed campaign's units at cost and move the payout to the destination.
+// Returns the cost basis returned and the payout including the buffer bonus.
+fn burn_for_refund(burn: RefundBurn, amount: u64) -> Result<(u64, u64)> {
+    let campaign = burn.campaign;
+    let record = burn.record;
+
+    open_refunds(campaign)?;
+
+    // Only units minted to this contributor by the campaign are refundable,
+    // so tokens bought elsewhere after the failure can't be redeemed at cost
+    let ticket_mode = campaign.ticket_mint != Pubkey::default();
+    // Donors hold nothing to burn and are refunded by the lamport
+    let donation = campaign.donation_mode;
+    let (expected_mint, entitled) = if ticket_mode {
+        (campaign.ticket_mint, record.sol_contributed)
+    } else if donation {
+        (campaign.token_mint, record.sol_contributed)
+    } else {
+        (campaign.token_mint, record.tokens_received)
+    };
+    require_keys_eq!(burn.mint.key(), expected_mint, ErrorCode::InvalidTokenMint);
+    require!(entitled > 0, ErrorCode::NothingToRefund);
+    validation::nonzero(amount)?;
+    if campaign.partial_refunds {
+        validation::within(amount, 1, entitled, ErrorCode::RefundAmountMismatch)?;
+    } else {
+        require!(amount == entitled, ErrorCode::RefundAmountMismatch);
+    }
+
+    // A partial refund small enough to round to nothing would burn the units for free
+    let refund = validation::output(
+        u64::try_from(
+            (record.sol_contributed as u128)
+                .checked_mul(amount as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / entitled as u128,
+        )
+        .map_err(|_| ErrorCode::MathOverflow)?,
+    )?;
+
+    let refreeze = (campaign.freeze_during_raise || campaign.tokens_invalidated) && !ticket_mode;
+    if refreeze {
+        set_token_account_frozen(
+            campaign,
+            burn.mint.clone(),
+            burn.contributor_token_account.clone(),
+            burn.token_program.clone(),
+            false,
+        )?;
+    }
+
+    if !donation {
+        let cpi_accounts = token::Burn {
+            mint: burn.mint.clone(),
+            from: burn.contributor_token_account.clone(),
+            authority: burn.contributor.clone(),
+        };
+        let cpi_ctx = CpiContext::new(burn.token_program.clone(), cpi_accounts);
+        token::burn(cpi_ctx, amount)?;
+    }
+
+    if refreeze {
+        set_token_account_frozen(
+            campaign,
+            burn.mint.clone(),
+            burn.contributor_token_account.clone(),
+            burn.token_program.clone(),
+            true,
+        )?;
+    }
+
+    let units = if ticket_mode || donation { 0 } else { amount };
+    campaign.release_supply(SupplyBucket::Curve, units);
+    let payout = pay_refund(campaign, record, &burn.destination, refund, units)?;
+
+    Ok((refund, payout))
+}
+
+// Moves a record in or out of the campaign's milestone backers after its
+// basis changed, so a backer who sells or is refunded below the minimum stops
+// counting and the milestones they carried are lost again
+fn count_milestone_backer(campaign: &mut Account<Campaign>, record: &mut ContributionRecord) {
+    let qualifies = campaign.qualifies_for_milestones(record.sol_contributed);
+    if qualifies != record.counts_toward_milestones {
+        record.counts_toward_milestones = qualifies;
+        adjust_milestone_backers(campaign, qualifies);
+    }
+}
+
+fn adjust_milestone_backers(campaign: &mut Account<Campaign>, joined: bool) {
+    campaign.milestone_backers = if joined {
+        campaign.milestone_backers.saturating_add(1)
+    } else {
+        campaign.milestone_backers.saturating_sub(1)
+    };
+    let milestones =
+        &campaign.contributor_milestones[..campaign.contributor_milestone_count as usize];
+    let reached = milestones
+        .iter()
+        .take_while(|m| campaign.milestone_backers >= **m)
+        .count() as u8;
+    while campaign.contributor_milestones_reached < reached {
+        let index = campaign.contributor_milestones_reached;
+        campaign.contributor_milestones_reached += 1;
+        emit_logged!(ContributorMilestoneReachedEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            index,
+            milestone_backers: campaign.milestone_backers,
+        });
+    }
+    while campaign.contributor_milestones_reached > reached {
+        campaign.contributor_milestones_reached -= 1;
+        emit_logged!(ContributorMilestoneLostEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            index: campaign.contributor_milestones_reached,
+            milestone_backers: campaign.milestone_backers,
+        });
+    }
+}
+
+// Every refund path waits for the campaign to fail and settles its status
+fn open_refunds(campaign: &mut Account<Campaign>) -> Result<()> {
+    require!(
+        campaign.has_failed(Clock::get()?.unix_timestamp),
+        ErrorCode::CampaignNotFailed
+    );
+    require!(!campaign.paused, ErrorCode::CampaignPaused);
+    campaign.transition(CampaignStatus::Failed)?;
+    require!(campaign.lent_amount == 0, ErrorCode::FundsStillLent);
+    Ok(())
+}
+
+// Return `refund` of a record's cost basis plus its share of the refund buffer,
+// taking `units` curve tokens off the record. Returns the payout.
+fn pay_refund(
+    campaign: &mut Account<Campaign>,
+    record: &mut ContributionRecord,
+    destination: &AccountInfo,
+    refund: u64,
+    units: u64,
+) -> Result<u64> {
+    let bonus = refund_bonus(campaign, refund)?;
+    let payout = refund.checked_add(bonus).ok_or(ErrorCode::MathOverflow)?;
+
+    let campaign_info = campaign.to_account_info();
+    let available = campaign_info
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(campaign_info.data_len()));
+    require!(payout <= available, ErrorCode::InsufficientCampaignBalance);
+
+    **campaign.to_account_info().try_borrow_mut_lamports()? -= payout;
+    **destination.try_borrow_mut_lamports()? += payout;
+    campaign.refund_buffer -= bonus;
+
+    record.sol_contributed -= refund;
+    record.tokens_received -= units;
+    record.refresh_entry_price()?;
+    count_milestone_backer(campaign, record);
+    campaign.raised_amount = campaign.raised_amount.saturating_sub(refund);
+    campaign.debit_balances(refund);
+    let reserve_share = calculate_bps_share(refund, campaign.floor_reserve_bps)?;
+    campaign.floor_reserve = campaign.floor_reserve.saturating_sub(reserve_share);
+
+    Ok(payout)
+}
+
+// The drip cap limits what leaves the campaign over any drip epoch to a share
+// of the raise, so even a stolen creator key can only drain it slowly. The
+// window rolls in eighths of the epoch rather than resetting, so withdrawals
+// can't be bunched either side of a boundary. The share is of the raise as it
+// stood before the window's withdrawals took it down.
+fn draw_drip_allowance(campaign: &mut Campaign, amount: u64) -> Result<()> {
+    if campaign.drip_cap_bps == 0 {
+        return Ok(());
+    }
+    let bucket_secs = campaign.drip_epoch_secs / DRIP_BUCKETS as i64;
+    let bucket = Clock::get()?.unix_timestamp / bucket_secs;
+    // Empty the buckets that have rolled out of the window since the last draw
+    let lapsed = bucket
+        .saturating_sub(campaign.drip_bucket_at)
+        .min(DRIP_BUCKETS as i64);
+    for step in 1..=lapsed {
+        campaign.drip_buckets[(campaign.drip_bucket_at + step) as usize % DRIP_BUCKETS] = 0;
+    }
+    campaign.drip_bucket_at = bucket;
+
+    let in_window = campaign
+        .drip_buckets
+        .iter()
+        .try_fold(0u64, |sum, drawn| sum.checked_add(*drawn));
+    let in_window = in_window.ok_or(ErrorCode::MathOverflow)?;
+    let base = campaign
+        .raised_amount
+        .checked_add(in_window)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let withdrawn = in_window
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        withdrawn <= calculate_bps_share(base, campaign.drip_cap_bps)?,
+        ErrorCode::DripCapExceeded
+    );
+    campaign.drip_buckets[bucket as usize % DRIP_BUCKETS] += amount;
+    Ok(())
+}
+
+// SOL set aside for contributors is shared out pro rata
+fn refund_bonus(campaign: &Campaign, refund: u64) -> Result<u64> {
+    Ok(u64::try_from(
+        (campaign.refund_buffer as u128)
+            .checked_mul(refund as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / campaign.raised_amount.max(1) as u128,
+    )
+    .map_err(|_| ErrorCode::MathOverflow)?
+    .min(campaign.refund_buffer))
+}
+
+// Screening is proven by passing the wallet's denylist entry address: the
+// seeds constraint pins it to the wallet, and it only holds data when listed
+fn check_denylist(config: &GlobalConfig, entry: Option<&UncheckedAccount>) -> Result<()> {
+    if !config.denylist_enabled {
+        return Ok(());
+    }
+    let entry = entry.ok_or(ErrorCode::DenylistEntryRequired)?;
+    require!(entry.data_is_empty(), ErrorCode::AddressDenied);
+    Ok(())
+}
+
+// A campaign closed before it ever opened hands back what others escrowed
+// against it: the sponsor match, and queued deposits passed as deposit and
+// depositor pairs, every one of which has to be there.
+fn return_escrows<'info>(
+    campaign: &mut Account<'info, Campaign>,
+    match_commitment: Option<&Account<'info, MatchCommitment>>,
+    sponsor: Option<&UncheckedAccount<'info>>,
+    deposits: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    if campaign.match_commitment != Pubkey::default() {
+        let (Some(commitment), Some(sponsor)) = (match_commitment, sponsor) else {
+            return err!(ErrorCode::MissingMatchCommitment);
+        };
+        require_keys_eq!(
+            commitment.key(),
+            campaign.match_commitment,
+            ErrorCode::MissingMatchCommitment
+        );
+        require_keys_eq!(sponsor.key(), commitment.sponsor, ErrorCode::Unauthorized);
+        commitment.close(sponsor.to_account_info())?;
+        campaign.match_commitment = Pubkey::default();
+    }
+
+    require!(
+        deposits.len().is_multiple_of(2),
+        ErrorCode::InvalidPreDepositRefund
+    );
+    for pair in deposits.chunks(2) {
+        let [deposit_info, depositor_info] = pair else {
+            return err!(ErrorCode::InvalidPreDepositRefund);
+        };
+        let mut deposit = Account::<QueuedDeposit>::try_from(deposit_info)?;
+        require_keys_eq!(
+            deposit.campaign,
+            campaign.key(),
+            ErrorCode::InvalidPreDepositRefund
+        );
+        require_keys_eq!(
+            depositor_info.key(),
+            deposit.depositor,
+            ErrorCode::InvalidPreDepositRefund
+        );
+
+        let amount = deposit.amount;
+        let returned = amount + deposit.rent_allowance;
+        deposit.amount = 0;
+        deposit.rent_allowance = 0;
+        **deposit_info.try_borrow_mut_lamports()? -= returned;
+        **depositor_info.try_borrow_mut_lamports()? += returned;
+        deposit.exit(&crate::ID)?;
+        campaign.pre_deposit_total -= amount;
+
+        emit_logged!(PreDepositCancelledEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign: campaign.key(),
+            depositor: deposit.depositor,
+            index: deposit.index,
+            amount,
+        });
+    }
+    require!(
+        campaign.pre_deposit_total == 0,
+        ErrorCode::InvalidPreDepositRefund
+    );
+    Ok(())
+}
+
+// Moves SOL out of a signer's relay escrow. It must either be emptied or keep
+// its rent-exempt minimum, since the runtime rejects anything in between.
+fn spend_relay_escrow<'info>(
+    escrow: &SystemAccount<'info>,
+    contributor: Pubkey,
+    bump: u8,
+    to: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    let remaining = escrow
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ErrorCode::RelayEscrowInsufficient)?;
+    require!(
+        remaining == 0 || remaining >= Rent::get()?.minimum_balance(0),
+        ErrorCode::RelayEscrowInsufficient
+    );
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: escrow.to_account_info(),
+                to: to.clone(),
+            },
+            &[&[b"relay_escrow", contributor.as_ref(), &[bump]]],
+        ),
+        amount,
+    )
+}
+
+// Global config changes all log the admin and which setting they touched;
+// the new values are on the config account
+fn emit_config_updated(admin: Pubkey, setting: &str) {
+    emit_logged!(GlobalConfigUpdatedEvent {
+        schema_version: EVENT_SCHEMA_VERSION,
+        admin,
+        setting: setting.to_string(),
+    });
+}
+
+// Name registry account of a .sol domain under the root TLD
+fn sns_domain_address(handle: &str) -> Result<Pubkey> {
+    let name = handle
+        .strip_suffix(".sol")
+        .ok_or(ErrorCode::InvalidIdentityHandle)?;
+    let hashed_name = hashv(&[NAME_SERVICE_HASH_PREFIX.as_bytes(), name.as_bytes()]);
+    let (address, _) = Pubkey::find_program_address(
+        &[
+            hashed_name.as_ref(),
+            Pubkey::default().as_ref(),
+            SOL_TLD_AUTHORITY.as_ref(),
+        ],
+        &NAME_SERVICE_PROGRAM_ID,
+    );
+    Ok(address)
+}
+
+// Whether a name registry account currently names `owner` as the domain's
+// owner. A closed or reassigned account reads as not owned.
+fn sns_domain_owned_by(name_account: &AccountInfo, owner: &Pubkey) -> Result<bool> {
+    if *name_account.owner != NAME_SERVICE_PROGRAM_ID {
+        return Ok(false);
+    }
+    // Registry header: parent name, then the domain's owner
+    let data = name_account.try_borrow_data()?;
+    Ok(data.len() >= 64 && data[32..64] == owner.to_bytes())
+}
+
+// did:<method>:<method-specific id>, with a lowercase alphanumeric method
+fn is_did(handle: &str) -> bool {
+    let Some(rest) = handle.strip_prefix("did:") else {
+        return false;
+    };
+    match rest.split_once(':') {
+        Some((method, id)) => {
+            !method.is_empty()
+                && method
+                    .bytes()
+                    .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit())
+                && !id.is_empty()
+        }
+        None => false,
+    }
+}
+
+// Backer profiles are opt-in, but every path that moves a wallet's positions
+// takes the profile's address, so once one exists it can't be left out of an
+// update. Wallets without one simply have nothing to update. Compressed
+// contributions have no record to count until they're claimed onto one.
+fn update_backer_profile(
+    info: &AccountInfo,
+    update: impl FnOnce(&mut BackerProfile) -> Result<()>,
+) -> Result<()> {
+    if info.owner != &crate::ID {
+        return Ok(());
+    }
+    let mut data = info.try_borrow_mut_data()?;
+    let mut profile = BackerProfile::try_deserialize(&mut &data[..])?;
+    update(&mut profile)?;
+    profile.try_serialize(&mut &mut data[..])
+}
+
+#[derive(Accounts)]
+#[instruction(name: String, description: String, target_amount: u64, token_symbol: String, token_name: String)]
+pub struct InitializeCampaign<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Campaign::INIT_SPACE,
+        seeds = [b"campaign", creator.key().as_ref(), hash(name.as_bytes()).as_ref()],
+        bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = 9,
+        mint::authority = campaign,
+        mint::freeze_authority = campaign,
+    )]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + std::mem::size_of::<PriceHistory>(),
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    // Only required for uniform-price raises
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"ticket_mint", campaign.key().as_ref()],
+        bump,
+        mint::decimals = 9,
+        mint::authority = campaign,
+    )]
+    pub ticket_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    // Only required when the campaign issues a governance token
+    /// CHECK: created and initialized as a Token-2022 mint in the handler
+    #[account(
+        mut,
+        seeds = [b"governance_mint", campaign.key().as_ref()],
+        bump
+    )]
+    pub governance_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: PDA used only as the governance mint and freeze authority
+    #[account(seeds = [b"governance_authority", campaign.key().as_ref()], bump)]
+    pub governance_authority: Option<UncheckedAccount<'info>>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    // Required while the platform enforces unique symbols; creation fails if
+    // another active campaign already holds the symbol
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + SymbolIndex::INIT_SPACE,
+        seeds = [b"symbol", naming::symbol_seed(&token_symbol).as_ref()],
+        bump
+    )]
+    pub symbol_index: Option<Account<'info, SymbolIndex>>,
+
+    /// CHECK: blocklist entry for the symbol's skeleton, which must be empty; required while the name blocklist is on
+    #[account(seeds = [b"blocked_name", naming::skeleton_hash(&token_symbol).as_ref()], bump)]
+    pub symbol_block_entry: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: the same for the token name
+    #[account(seeds = [b"blocked_name", naming::skeleton_hash(&token_name).as_ref()], bump)]
+    pub name_block_entry: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_profile: Box<Account<'info, CreatorProfile>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct Contribute<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        associated_token::mint = token_mint,
+        associated_token::authority = contributor
+    )]
+    pub contributor_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = 8 + ContributionRecord::INIT_SPACE,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", campaign.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    // Only required when the campaign issues a governance token
+    /// CHECK: must be the campaign's governance mint
+    #[account(mut, address = campaign.governance_mint @ ErrorCode::InvalidTokenMint)]
+    pub governance_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: PDA used only as the governance mint and freeze authority
+    #[account(
+        seeds = [b"governance_authority", campaign.key().as_ref()],
+        bump = campaign.governance_authority_bump
+    )]
+    pub governance_authority: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: contributor's governance ATA, verified and created in the handler
+    #[account(mut)]
+    pub contributor_governance_account: Option<UncheckedAccount<'info>>,
+
+    // Only required for uniform-price raises
+    #[account(mut, address = campaign.ticket_mint @ ErrorCode::InvalidTokenMint)]
+    pub ticket_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(mut, token::mint = ticket_mint, token::authority = contributor)]
+    pub contributor_ticket_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // Required once the insurance pool takes a share of platform fees
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Option<Account<'info, InsurancePool>>,
+
+    // Optional integrator that routed this contribution
+    pub frontend: Option<Box<Account<'info, FrontendConfig>>>,
+
+    #[account(mut)]
+    pub frontend_payout: Option<SystemAccount<'info>>,
+
+    // Required while a sponsor's match is registered on the campaign
+    #[account(
+        mut,
+        seeds = [b"match", campaign.key().as_ref()],
+        bump = match_commitment.bump
+    )]
+    pub match_commitment: Option<Box<Account<'info, MatchCommitment>>>,
+
+    // Optional; refreshed with this campaign's totals when passed in
+    #[account(mut, seeds = [b"leaderboard"], bump = leaderboard.load()?.bump)]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    /// CHECK: the contributor's backer profile, updated when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", contributor.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    /// CHECK: the contributor's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", contributor.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    // Required when the campaign restricts contributor regions
+    #[account(seeds = [b"region", contributor.key().as_ref()], bump = region_attestation.bump)]
+    pub region_attestation: Option<Account<'info, RegionAttestation>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct ContributeWithSignature<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: authenticated by the Ed25519 signature over the intent
+    pub contributor: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        associated_token::mint = token_mint,
+        associated_token::authority = contributor
+    )]
+    pub contributor_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = 8 + ContributionRecord::INIT_SPACE,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", campaign.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    // The signer's SOL for relayed contributions
+    #[account(mut, seeds = [b"relay_escrow", contributor.key().as_ref()], bump)]
+    pub relay_escrow: SystemAccount<'info>,
+
+    /// CHECK: the contributor's backer profile, updated when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", contributor.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    /// CHECK: must be the campaign's governance mint
+    #[account(mut, address = campaign.governance_mint @ ErrorCode::InvalidTokenMint)]
+    pub governance_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: PDA used only as the governance mint and freeze authority
+    #[account(
+        seeds = [b"governance_authority", campaign.key().as_ref()],
+        bump = campaign.governance_authority_bump
+    )]
+    pub governance_authority: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: contributor's governance ATA, verified and created in the handler
+    #[account(mut)]
+    pub contributor_governance_account: Option<UncheckedAccount<'info>>,
+
+    // Only required for uniform-price raises
+    #[account(mut, address = campaign.ticket_mint @ ErrorCode::InvalidTokenMint)]
+    pub ticket_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(mut, token::mint = ticket_mint, token::authority = contributor)]
+    pub contributor_ticket_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the contributor's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", contributor.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // Required once the insurance pool takes a share of platform fees
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Option<Account<'info, InsurancePool>>,
+
+    // Optional integrator that routed this contribution
+    pub frontend: Option<Box<Account<'info, FrontendConfig>>>,
+
+    #[account(mut)]
+    pub frontend_payout: Option<SystemAccount<'info>>,
+
+    // Required while a sponsor's match is registered on the campaign
+    #[account(
+        mut,
+        seeds = [b"match", campaign.key().as_ref()],
+        bump = match_commitment.bump
+    )]
+    pub match_commitment: Option<Box<Account<'info, MatchCommitment>>>,
+
+    // Optional; refreshed with this campaign's totals when passed in
+    #[account(mut, seeds = [b"leaderboard"], bump = leaderboard.load()?.bump)]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    /// CHECK: instructions sysvar, read to find the Ed25519 verification
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    // Required when the campaign restricts contributor regions
+    #[account(seeds = [b"region", contributor.key().as_ref()], bump = region_attestation.bump)]
+    pub region_attestation: Option<Account<'info, RegionAttestation>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawRelayEscrow<'info> {
+    #[account(mut, seeds = [b"relay_escrow", contributor.key().as_ref()], bump)]
+    pub relay_escrow: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFunds<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + WithdrawalReceipt::INIT_SPACE,
+        seeds = [b"withdrawal", campaign.key().as_ref(), &campaign.withdrawal_count.to_le_bytes()],
+        bump
+    )]
+    pub receipt: Account<'info, WithdrawalReceipt>,
+
+    // Required for withdrawals above the campaign's approval threshold
+    #[account(
+        seeds = [b"withdrawal_approval", campaign.key().as_ref(), &campaign.withdrawal_count.to_le_bytes()],
+        bump = withdrawal_approval.bump
+    )]
+    pub withdrawal_approval: Option<Account<'info, WithdrawalApproval>>,
+
+    // The creator, or a co-creator holding the withdraw role
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    // Funds go to the campaign creator whoever signs, so the withdraw role
+    // doesn't let a co-creator pay themselves
+    #[account(mut, address = campaign.creator @ ErrorCode::InvalidWithdrawalDestination)]
+    pub destination: SystemAccount<'info>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the withdrawer's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", creator.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetTokenPrice<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeExtension<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    // One proposal per campaign, so the deadline can only be extended once
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + ExtensionProposal::INIT_SPACE,
+        seeds = [b"extension", campaign.key().as_ref()],
+        bump
+    )]
+    pub extension_proposal: Account<'info, ExtensionProposal>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VoteExtension<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"extension", campaign.key().as_ref()],
+        bump = extension_proposal.bump
+    )]
+    pub extension_proposal: Account<'info, ExtensionProposal>,
+
+    #[account(
+        seeds = [b"contribution", campaign.key().as_ref(), voter.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + ExtensionVote::INIT_SPACE,
+        seeds = [b"extension_vote", extension_proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub extension_vote: Account<'info, ExtensionVote>,
+
+    #[account(address = campaign.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(mut, token::mint = token_mint, token::authority = voter)]
+    pub voter_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = voter,
+        seeds = [b"vote_escrow", extension_vote.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = extension_vote
+    )]
+    pub vote_escrow: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeExtension<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"extension", campaign.key().as_ref()],
+        bump = extension_proposal.bump
+    )]
+    pub extension_proposal: Account<'info, ExtensionProposal>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimExtensionVote<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        seeds = [b"extension", campaign.key().as_ref()],
+        bump = extension_proposal.bump
+    )]
+    pub extension_proposal: Account<'info, ExtensionProposal>,
+
+    #[account(
+        seeds = [b"extension_vote", extension_proposal.key().as_ref(), voter.key().as_ref()],
+        bump = extension_vote.bump
+    )]
+    pub extension_vote: Account<'info, ExtensionVote>,
+
+    #[account(address = campaign.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(mut, seeds = [b"vote_escrow", extension_vote.key().as_ref()], bump)]
+    pub vote_escrow: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = token_mint, token::authority = voter)]
+    pub voter_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GraduateCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    // Only required for uniform-price raises
+    #[account(address = campaign.ticket_mint @ ErrorCode::InvalidTokenMint)]
+    pub ticket_mint: Option<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"creator_profile", campaign.creator.as_ref()],
+        bump = creator_profile.bump
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    pub creator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemAtFloor<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = holder
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockGovernanceTokens<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    /// CHECK: must be the campaign's governance mint
+    #[account(address = campaign.governance_mint @ ErrorCode::InvalidTokenMint)]
+    pub governance_mint: UncheckedAccount<'info>,
+
+    /// CHECK: PDA used only as the governance mint and freeze authority
+    #[account(
+        seeds = [b"governance_authority", campaign.key().as_ref()],
+        bump = campaign.governance_authority_bump
+    )]
+    pub governance_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Token-2022 validates the account belongs to the governance mint
+    #[account(mut, owner = token_2022_program.key())]
+    pub holder_governance_account: UncheckedAccount<'info>,
+
+    pub token_2022_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct SellTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), seller.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", campaign.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: the seller's backer profile, updated when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", seller.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the seller's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", seller.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    // Only required when the campaign issues a governance token
+    /// CHECK: must be the campaign's governance mint
+    #[account(mut, address = campaign.governance_mint @ ErrorCode::InvalidTokenMint)]
+    pub governance_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: PDA used only as the governance mint and freeze authority
+    #[account(
+        seeds = [b"governance_authority", campaign.key().as_ref()],
+        bump = campaign.governance_authority_bump
+    )]
+    pub governance_authority: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: seller's governance ATA, verified in the handler
+    #[account(mut)]
+    pub seller_governance_account: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGlobalConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + GlobalConfig::INIT_SPACE,
+        seeds = [b"global_config"],
+        bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    // Only the program's upgrade authority may claim the admin role
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::LaunchFund>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::NotUpgradeAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGlobalConfig<'info> {
+    #[account(mut, seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(domain_hash: [u8; 32])]
+pub struct RegisterFrontend<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FrontendConfig::INIT_SPACE,
+        seeds = [b"frontend", domain_hash.as_ref()],
+        bump
+    )]
+    pub frontend: Account<'info, FrontendConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFrontend<'info> {
+    #[account(mut, seeds = [b"frontend", frontend.domain_hash.as_ref()], bump = frontend.bump)]
+    pub frontend: Account<'info, FrontendConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ThawHolder<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(mut, token::mint = token_mint)]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ThawAll<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemTicket<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.ticket_mint @ ErrorCode::InvalidTokenMint)]
+    pub ticket_mint: Account<'info, Mint>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = ticket_mint,
+        token::authority = holder
+    )]
+    pub holder_ticket_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        associated_token::mint = token_mint,
+        associated_token::authority = holder
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    // The campaign token, or the ticket mint for uniform-price raises
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = contributor
+    )]
+    pub contributor_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    /// CHECK: the contributor's backer profile, updated when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", contributor.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    // Only required when the campaign issues a governance token
+    /// CHECK: must be the campaign's governance mint
+    #[account(mut, address = campaign.governance_mint @ ErrorCode::InvalidTokenMint)]
+    pub governance_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: PDA used only as the governance mint and freeze authority
+    #[account(
+        seeds = [b"governance_authority", campaign.key().as_ref()],
+        bump = campaign.governance_authority_bump
+    )]
+    pub governance_authority: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: contributor's governance ATA, verified in the handler
+    #[account(mut)]
+    pub contributor_governance_account: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureAirdrop<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Airdrop::INIT_SPACE,
+        seeds = [b"airdrop", campaign.key().as_ref()],
+        bump
+    )]
+    pub airdrop: Account<'info, Airdrop>,
+
+    // Mint whose holders were snapshotted to build the merkle tree
+    pub source_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop", campaign.key().as_ref()],
+        bump = airdrop.bump
+    )]
+    pub airdrop: Account<'info, Airdrop>,
+
+    // Existence of the receipt is what stops a second claim
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + AirdropClaim::INIT_SPACE,
+        seeds = [b"airdrop_claim", airdrop.key().as_ref(), claimant.key().as_ref()],
+        bump
+    )]
+    pub claim_receipt: Account<'info, AirdropClaim>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        associated_token::mint = token_mint,
+        associated_token::authority = claimant
+    )]
+    pub claimant_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct LockLiquidity<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + LiquidityLock::INIT_SPACE,
+        seeds = [b"liquidity_lock", campaign.key().as_ref()],
+        bump
+    )]
+    pub liquidity_lock: Account<'info, LiquidityLock>,
+
+    // Must be the configured AMM's LP, so the lock can't be satisfied with a
+    // worthless mint of the creator's own
+    #[account(
+        constraint = global_config.lp_mint_authority != Pubkey::default()
+            && lp_mint.mint_authority == COption::Some(global_config.lp_mint_authority)
+            && lp_mint.key() != campaign.token_mint
+            @ ErrorCode::InvalidLpMint
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"lp_vault", campaign.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = liquidity_lock
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = lp_mint, token::authority = creator)]
+    pub creator_lp_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct VoteLiquidityUnlock<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"liquidity_lock", campaign.key().as_ref()],
+        bump = liquidity_lock.bump
+    )]
+    pub liquidity_lock: Account<'info, LiquidityLock>,
+
+    #[account(
+        seeds = [b"contribution", campaign.key().as_ref(), voter.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + LiquidityUnlockVote::INIT_SPACE,
+        seeds = [b"liquidity_vote", liquidity_lock.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub unlock_vote: Account<'info, LiquidityUnlockVote>,
+
+    #[account(address = campaign.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(mut, token::mint = token_mint, token::authority = voter)]
+    pub voter_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = voter,
+        seeds = [b"vote_escrow", unlock_vote.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = unlock_vote
+    )]
+    pub vote_escrow: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimUnlockVote<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        seeds = [b"liquidity_lock", campaign.key().as_ref()],
+        bump = liquidity_lock.bump
+    )]
+    pub liquidity_lock: Account<'info, LiquidityLock>,
+
+    #[account(
+        seeds = [b"liquidity_vote", liquidity_lock.key().as_ref(), voter.key().as_ref()],
+        bump = unlock_vote.bump
+    )]
+    pub unlock_vote: Account<'info, LiquidityUnlockVote>,
+
+    #[account(address = campaign.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(mut, seeds = [b"vote_escrow", unlock_vote.key().as_ref()], bump)]
+    pub vote_escrow: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = token_mint, token::authority = voter)]
+    pub voter_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockLiquidity<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"liquidity_lock", campaign.key().as_ref()],
+        bump = liquidity_lock.bump
+    )]
+    pub liquidity_lock: Account<'info, LiquidityLock>,
+
+    #[account(mut, address = liquidity_lock.lp_vault)]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = liquidity_lock.lp_mint, token::authority = creator)]
+    pub creator_lp_account: Account<'info, TokenAccount>,
+
+    pub creator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterMatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = sponsor,
+        space = 8 + MatchCommitment::INIT_SPACE,
+        seeds = [b"match", campaign.key().as_ref()],
+        bump
+    )]
+    pub match_commitment: Account<'info, MatchCommitment>,
+
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    // The campaign has a single match slot, so the creator accepts who fills it
+    #[account(address = campaign.creator @ ErrorCode::NotCampaignCreator)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"match", campaign.key().as_ref()],
+        bump = match_commitment.bump,
+        has_one = sponsor @ ErrorCode::Unauthorized
+    )]
+    pub match_commitment: Account<'info, MatchCommitment>,
+
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundMatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    // Closed to the sponsor along with the refund, escrow remainder included
+    #[account(
+        mut,
+        close = sponsor,
+        seeds = [b"match", campaign.key().as_ref()],
+        bump = match_commitment.bump,
+        has_one = sponsor @ ErrorCode::Unauthorized
+    )]
+    pub match_commitment: Account<'info, MatchCommitment>,
+
+    /// CHECK: the sponsor on the commitment; anyone can crank their refund
+    #[account(mut)]
+    pub sponsor: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLeaderboard<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<Leaderboard>(),
+        seeds = [b"leaderboard"],
+        bump
+    )]
+    pub leaderboard: AccountLoader<'info, Leaderboard>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseSymbol<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    // Indexes created while enforcement was off used to be left without their
+    // campaign or bump, so those release with the symbol's campaign too
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"symbol", naming::stored_symbol_seed(&campaign.token_symbol).as_ref()],
+        bump,
+        constraint = symbol_index.campaign == campaign.key()
+            || symbol_index.campaign == Pubkey::default() @ ErrorCode::SymbolIndexMismatch
+    )]
+    pub symbol_index: Account<'info, SymbolIndex>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: receives the index rent and must be the campaign creator
+    #[account(mut, address = campaign.creator)]
+    pub creator: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TreasuryBuy<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = token_mint,
+        associated_token::authority = creator
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+
+    // Treasury buys are kept apart from the creator's own contribution record,
+    // so they never count towards the creator's votes, refunds or sells
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + ContributionRecord::INIT_SPACE,
+        seeds = [b"treasury_record", campaign.key().as_ref()],
+        bump
+    )]
+    pub treasury_record: Account<'info, ContributionRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", campaign.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    // Only required for uniform-price raises
+    #[account(mut, address = campaign.ticket_mint @ ErrorCode::InvalidTokenMint)]
+    pub ticket_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(mut, token::mint = ticket_mint, token::authority = creator)]
+    pub creator_ticket_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the creator's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", creator.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // Required once the insurance pool takes a share of platform fees
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Option<Account<'info, InsurancePool>>,
+
+    // Optional; refreshed with this campaign's totals when passed in
+    #[account(mut, seeds = [b"leaderboard"], bump = leaderboard.load()?.bump)]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeWormholeConfig<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + WormholeConfig::INIT_SPACE,
+        seeds = [b"wormhole_config"],
+        bump
+    )]
+    pub wormhole_config: Account<'info, WormholeConfig>,
+
+    // Wrapped USDC as minted by the token bridge
+    pub usdc_mint: Account<'info, Mint>,
+
+    // Where redeemed transfers land until the relayer is repaid
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"wormhole_custody"],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = wormhole_config
+    )]
+    pub custody: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ContributeFromWormhole<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: must match the beneficiary named in the VAA payload
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        associated_token::mint = token_mint,
+        associated_token::authority = beneficiary
+    )]
+    pub beneficiary_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = 8 + ContributionRecord::INIT_SPACE,
+        seeds = [b"contribution", campaign.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub contribution_record: Box<Account<'info, ContributionRecord>>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", campaign.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    #[account(seeds = [b"wormhole_config"], bump = wormhole_config.bump)]
+    pub wormhole_config: Box<Account<'info, WormholeConfig>>,
+
+    /// CHECK: parsed and verified against the core bridge in the handler
+    pub posted_vaa: UncheckedAccount<'info>,
+
+    // Replay guard: one credit per posted VAA
+    #[account(
+        init,
+        payer = relayer,
+        space = 8 + WormholeReceived::INIT_SPACE,
+        seeds = [b"wormhole_received", posted_vaa.key().as_ref()],
+        bump
+    )]
+    pub received: Box<Account<'info, WormholeReceived>>,
+
+    #[account(mut, address = wormhole_config.usdc_mint @ ErrorCode::InvalidTokenMint)]
+    pub usdc_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, address = wormhole_config.custody)]
+    pub custody: Box<Account<'info, TokenAccount>>,
+
+    // Repaid the redeemed USDC, and receives any relayer fee the sender attached
+    #[account(mut, token::mint = usdc_mint)]
+    pub relayer_usdc_account: Box<Account<'info, TokenAccount>>,
+
+    // Required when the hard cap only takes part of the transfer; gets the rest
+    #[account(mut, token::mint = usdc_mint, token::authority = beneficiary)]
+    pub beneficiary_usdc_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: the token bridge configured by the admin
+    #[account(executable, address = wormhole_config.token_bridge)]
+    pub token_bridge_program: UncheckedAccount<'info>,
+
+    /// CHECK: the core bridge the VAA was posted to
+    #[account(executable, address = wormhole_config.core_bridge)]
+    pub core_bridge_program: UncheckedAccount<'info>,
+
+    /// CHECK: token bridge config, checked by the token bridge
+    pub token_bridge_config: UncheckedAccount<'info>,
+
+    /// CHECK: the VAA's claim record, created by the token bridge on redemption
+    #[account(mut)]
+    pub token_bridge_claim: UncheckedAccount<'info>,
+
+    /// CHECK: registered emitter of the source chain, checked by the token bridge
+    pub token_bridge_endpoint: UncheckedAccount<'info>,
+
+    /// CHECK: wrapped asset metadata of the USDC mint, checked by the token bridge
+    pub wrapped_meta: UncheckedAccount<'info>,
+
+    /// CHECK: the token bridge's mint authority, checked by the token bridge
+    pub token_bridge_mint_authority: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: the beneficiary's backer profile, updated when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", beneficiary.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    /// CHECK: must be the campaign's governance mint
+    #[account(mut, address = campaign.governance_mint @ ErrorCode::InvalidTokenMint)]
+    pub governance_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: PDA used only as the governance mint and freeze authority
+    #[account(
+        seeds = [b"governance_authority", campaign.key().as_ref()],
+        bump = campaign.governance_authority_bump
+    )]
+    pub governance_authority: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: beneficiary's governance ATA, verified and created in the handler
+    #[account(mut)]
+    pub beneficiary_governance_account: Option<UncheckedAccount<'info>>,
+
+    // Only required for uniform-price raises
+    #[account(mut, address = campaign.ticket_mint @ ErrorCode::InvalidTokenMint)]
+    pub ticket_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(mut, token::mint = ticket_mint, token::authority = beneficiary)]
+    pub beneficiary_ticket_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the beneficiary's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", beneficiary.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // Required once the insurance pool takes a share of platform fees
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Option<Account<'info, InsurancePool>>,
+
+    // Required while a sponsor's match is registered on the campaign
+    #[account(
+        mut,
+        seeds = [b"match", campaign.key().as_ref()],
+        bump = match_commitment.bump
+    )]
+    pub match_commitment: Option<Box<Account<'info, MatchCommitment>>>,
+
+    // Optional; refreshed with this campaign's totals when passed in
+    #[account(mut, seeds = [b"leaderboard"], bump = leaderboard.load()?.bump)]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    // Required when the campaign restricts contributor regions
+    #[account(seeds = [b"region", beneficiary.key().as_ref()], bump = region_attestation.bump)]
+    pub region_attestation: Option<Account<'info, RegionAttestation>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureRebatePool<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + RebatePool::INIT_SPACE,
+        seeds = [b"rebate_pool", campaign.key().as_ref()],
+        bump
+    )]
+    pub rebate_pool: Account<'info, RebatePool>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"rebate_vault", campaign.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = rebate_pool
+    )]
+    pub rebate_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = token_mint, token::authority = creator)]
+    pub creator_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimLoyaltyRebate<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"rebate_pool", campaign.key().as_ref()],
+        bump = rebate_pool.bump
+    )]
+    pub rebate_pool: Account<'info, RebatePool>,
+
+    #[account(mut, address = rebate_pool.vault)]
+    pub rebate_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), holder.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(mut, token::mint = token_mint, token::authority = holder)]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    pub holder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GetCampaignSummary<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawAsStablecoin<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the withdrawer's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", creator.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        token::mint = spl_token::native_mint::ID,
+        token::authority = creator
+    )]
+    pub creator_wsol_account: Box<Account<'info, TokenAccount>>,
+
+    // The campaign creator's, whoever signs
+    #[account(
+        mut,
+        token::mint = global_config.stable_mint,
+        token::authority = campaign.creator
+    )]
+    pub creator_stable_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: the AMM or aggregator configured by the admin
+    #[account(executable, address = global_config.swap_program)]
+    pub swap_program: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + WithdrawalReceipt::INIT_SPACE,
+        seeds = [b"withdrawal", campaign.key().as_ref(), &campaign.withdrawal_count.to_le_bytes()],
+        bump
+    )]
+    pub receipt: Box<Account<'info, WithdrawalReceipt>>,
+
+    // Required for withdrawals above the campaign's approval threshold
+    #[account(
+        seeds = [b"withdrawal_approval", campaign.key().as_ref(), &campaign.withdrawal_count.to_le_bytes()],
+        bump = withdrawal_approval.bump
+    )]
+    pub withdrawal_approval: Option<Box<Account<'info, WithdrawalApproval>>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SnapshotCampaign<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+}
+
+#[derive(Accounts)]
+pub struct ContributeAsProgram<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    // Usually a PDA signed for by the calling program; it only has to authorize
+    pub contributor: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        associated_token::mint = token_mint,
+        associated_token::authority = contributor
+    )]
+    pub contributor_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + ContributionRecord::INIT_SPACE,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution_record: Box<Account<'info, ContributionRecord>>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", campaign.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    /// CHECK: the contributor's backer profile, updated when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", contributor.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// CHECK: must be the campaign's governance mint
+    #[account(mut, address = campaign.governance_mint @ ErrorCode::InvalidTokenMint)]
+    pub governance_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: PDA used only as the governance mint and freeze authority
+    #[account(
+        seeds = [b"governance_authority", campaign.key().as_ref()],
+        bump = campaign.governance_authority_bump
+    )]
+    pub governance_authority: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: contributor's governance ATA, verified and created in the handler
+    #[account(mut)]
+    pub contributor_governance_account: Option<UncheckedAccount<'info>>,
+
+    // Only required for uniform-price raises
+    #[account(mut, address = campaign.ticket_mint @ ErrorCode::InvalidTokenMint)]
+    pub ticket_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(mut, token::mint = ticket_mint, token::authority = contributor)]
+    pub contributor_ticket_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the contributor's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", contributor.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // Required once the insurance pool takes a share of platform fees
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Option<Account<'info, InsurancePool>>,
+
+    // Required while a sponsor's match is registered on the campaign
+    #[account(
+        mut,
+        seeds = [b"match", campaign.key().as_ref()],
+        bump = match_commitment.bump
+    )]
+    pub match_commitment: Option<Box<Account<'info, MatchCommitment>>>,
+
+    // Optional; refreshed with this campaign's totals when passed in
+    #[account(mut, seeds = [b"leaderboard"], bump = leaderboard.load()?.bump)]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    // Required when the campaign restricts contributor regions
+    #[account(seeds = [b"region", contributor.key().as_ref()], bump = region_attestation.bump)]
+    pub region_attestation: Option<Account<'info, RegionAttestation>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+#[instruction(kind: BadgeKind)]
+pub struct ClaimBadge<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        seeds = [b"contribution", campaign.key().as_ref(), holder.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(seeds = [b"price_history", campaign.key().as_ref()], bump = price_history.load()?.bump)]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    /// CHECK: Token-2022 badge mint, created in the handler on first claim
+    #[account(
+        mut,
+        seeds = [b"badge_mint", campaign.key().as_ref(), &[kind as u8]],
+        bump
+    )]
+    pub badge_mint: UncheckedAccount<'info>,
+
+    /// CHECK: holder's badge ATA, verified and created in the handler
+    #[account(mut)]
+    pub holder_badge_account: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = holder,
+        space = 8 + BadgeClaim::INIT_SPACE,
+        seeds = [b"badge", badge_mint.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub badge_claim: Account<'info, BadgeClaim>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct AttestRegion<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = attestor,
+        space = 8 + RegionAttestation::INIT_SPACE,
+        seeds = [b"region", holder.key().as_ref()],
+        bump
+    )]
+    pub attestation: Account<'info, RegionAttestation>,
+
+    /// CHECK: only used as the attestation's subject
+    pub holder: UncheckedAccount<'info>,
+
+    #[account(mut, address = global_config.region_attestor @ ErrorCode::Unauthorized)]
+    pub attestor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositIdleSol<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    // Wrapped SOL staging account the lending program deposits from and withdraws into
+    #[account(
+        init_if_needed,
+        payer = creator,
+        token::mint = native_mint,
+        token::authority = campaign,
+        seeds = [b"yield_vault", campaign.key().as_ref()],
+        bump
+    )]
+    pub yield_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = spl_token::native_mint::ID)]
+    pub native_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: the lending program configured by the admin
+    #[account(executable, address = global_config.lending.program)]
+    pub lending_program: UncheckedAccount<'info>,
+
+    /// CHECK: the admin's lending reserve
+    #[account(mut, address = global_config.lending.reserve)]
+    pub lending_reserve: UncheckedAccount<'info>,
+
+    /// CHECK: the reserve's liquidity supply
+    #[account(mut, address = global_config.lending.liquidity_supply)]
+    pub lending_liquidity_supply: UncheckedAccount<'info>,
+
+    /// CHECK: the campaign's position in the reserve, derived by the lending program
+    #[account(
+        mut,
+        seeds = [b"position", global_config.lending.reserve.as_ref(), campaign.key().as_ref()],
+        seeds::program = global_config.lending.program,
+        bump
+    )]
+    pub lending_position: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecallLentSol<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"yield_vault", campaign.key().as_ref()],
+        bump
+    )]
+    pub yield_vault: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: the lending program configured by the admin
+    #[account(executable, address = global_config.lending.program)]
+    pub lending_program: UncheckedAccount<'info>,
+
+    /// CHECK: the admin's lending reserve
+    #[account(mut, address = global_config.lending.reserve)]
+    pub lending_reserve: UncheckedAccount<'info>,
+
+    /// CHECK: the reserve's liquidity supply
+    #[account(mut, address = global_config.lending.liquidity_supply)]
+    pub lending_liquidity_supply: UncheckedAccount<'info>,
+
+    /// CHECK: the campaign's position in the reserve, derived by the lending program
+    #[account(
+        mut,
+        seeds = [b"position", global_config.lending.reserve.as_ref(), campaign.key().as_ref()],
+        seeds::program = global_config.lending.program,
+        bump
+    )]
+    pub lending_position: UncheckedAccount<'info>,
+
+    // Receives the vault rent and, unless contributors get it, the yield
+    #[account(mut, address = campaign.creator)]
+    pub creator: SystemAccount<'info>,
+
+    pub caller: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct EnableCompressedRecords<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    /// CHECK: empty tree account allocated by the client, initialized by the compression program
+    #[account(mut, owner = ACCOUNT_COMPRESSION_ID)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: spl-account-compression
+    #[account(address = ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: noop program the compression program logs changes through
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ContributeCompressed<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Box<Account<'info, Campaign>>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        associated_token::mint = token_mint,
+        associated_token::authority = contributor
+    )]
+    pub contributor_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", campaign.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    // Only required for uniform-price raises
+    #[account(mut, address = campaign.ticket_mint @ ErrorCode::InvalidTokenMint)]
+    pub ticket_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(mut, token::mint = ticket_mint, token::authority = contributor)]
+    pub contributor_ticket_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the contributor's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", contributor.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // Required once the insurance pool takes a share of platform fees
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Option<Account<'info, InsurancePool>>,
+
+    // Required while a sponsor's match is registered on the campaign
+    #[account(
+        mut,
+        seeds = [b"match", campaign.key().as_ref()],
+        bump = match_commitment.bump
+    )]
+    pub match_commitment: Option<Box<Account<'info, MatchCommitment>>>,
+
+    // Required when the campaign restricts contributor regions
+    #[account(seeds = [b"region", contributor.key().as_ref()], bump = region_attestation.bump)]
+    pub region_attestation: Option<Account<'info, RegionAttestation>>,
+
+    /// CHECK: the campaign's record tree
+    #[account(mut, address = campaign.record_tree)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: spl-account-compression
+    #[account(address = ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: noop program the compression program logs changes through
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct RefundCompressed<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    // Campaign token mint, or the ticket mint for uniform-price raises
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut, token::mint = mint, token::authority = contributor)]
+    pub contributor_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: the campaign's record tree
+    #[account(mut, address = campaign.record_tree)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: spl-account-compression
+    #[account(address = ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: noop program the compression program logs changes through
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCompressedRecord<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Box<Account<'info, Campaign>>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = 8 + ContributionRecord::INIT_SPACE,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution_record: Box<Account<'info, ContributionRecord>>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    /// CHECK: the contributor's backer profile, updated when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", contributor.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    /// CHECK: the campaign's record tree
+    #[account(mut, address = campaign.record_tree)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: spl-account-compression
+    #[account(address = ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: noop program the compression program logs changes through
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyDeployment<'info> {
+    #[account(mut, seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::LaunchFund>,
+
+    pub program_data: Account<'info, ProgramData>,
+}
+
+// Who can upgrade the program, as of the last verify_deployment
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum UpgradeAuthorityStatus {
+    Unverified,
+    Renounced,
+    ExpectedAuthority,
+    UnexpectedAuthority,
+}
+
+#[derive(Accounts)]
+pub struct TransferAdminToGovernance<'info> {
+    #[account(mut, seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// CHECK: pinned to SPL Governance, so realms and governances can't be forged
+    #[account(address = SPL_GOVERNANCE_ID @ ErrorCode::InvalidGovernanceAccount)]
+    pub governance_program: UncheckedAccount<'info>,
+
+    /// CHECK: realm account, owned by the governance program
+    #[account(owner = governance_program.key() @ ErrorCode::InvalidGovernanceAccount)]
+    pub realm: UncheckedAccount<'info>,
+
+    /// CHECK: governance PDA of the realm, re-derived in the handler
+    #[account(owner = governance_program.key() @ ErrorCode::InvalidGovernanceAccount)]
+    pub governance: UncheckedAccount<'info>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleFailedCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"creator_profile", campaign.creator.as_ref()],
+        bump = creator_profile.bump
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+}
+
+// What the creator says a withdrawal is for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum WithdrawalPurpose {
+    Development,
+    Marketing,
+    Liquidity,
+    Operations,
+    Legal,
+    Other,
+    Salary,
+}
+
+#[derive(Accounts)]
+#[instruction(batch_id: u64)]
+pub struct ClaimForExchange<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.ticket_mint @ ErrorCode::InvalidTokenMint)]
+    pub ticket_mint: Account<'info, Mint>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    // Where users' deposited tickets have been pooled
+    #[account(
+        mut,
+        token::mint = ticket_mint,
+        token::authority = exchange
+    )]
+    pub exchange_ticket_account: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = token_mint)]
+    pub omnibus_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = exchange,
+        space = 8 + ExchangeClaim::INIT_SPACE,
+        seeds = [b"exchange_claim", campaign.key().as_ref(), exchange.key().as_ref(), &batch_id.to_le_bytes()],
+        bump
+    )]
+    pub exchange_claim: Account<'info, ExchangeClaim>,
+
+    #[account(mut)]
+    pub exchange: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct TradeSecondary<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = trader
+    )]
+    pub trader_token_account: Account<'info, TokenAccount>,
+
+    // Receives the secondary curve fee
+    #[account(mut, address = campaign.creator @ ErrorCode::NotCampaignCreator)]
+    pub creator: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", campaign.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetCampaignPaused<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RequestExit<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = holder
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), holder.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(
+        init,
+        payer = holder,
+        space = 8 + ExitRequest::INIT_SPACE,
+        seeds = [b"exit_request", campaign.key().as_ref(), &campaign.exit_queue_tail.to_le_bytes()],
+        bump
+    )]
+    pub exit_request: Account<'info, ExitRequest>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+
+    // Only required when the campaign issues a governance token
+    /// CHECK: must be the campaign's governance mint
+    #[account(mut, address = campaign.governance_mint @ ErrorCode::InvalidTokenMint)]
+    pub governance_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: PDA used only as the governance mint and freeze authority
+    #[account(
+        seeds = [b"governance_authority", campaign.key().as_ref()],
+        bump = campaign.governance_authority_bump
+    )]
+    pub governance_authority: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: holder's governance ATA, verified in the handler
+    #[account(mut)]
+    pub holder_governance_account: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+}
+
+#[derive(Accounts)]
+pub struct ProcessExit<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    // Only the request at the head of the queue can be processed
+    #[account(
+        mut,
+        close = holder,
+        seeds = [b"exit_request", campaign.key().as_ref(), &campaign.exit_queue_head.to_le_bytes()],
+        bump = exit_request.bump,
+        has_one = holder
+    )]
+    pub exit_request: Account<'info, ExitRequest>,
+
+    #[account(mut)]
+    pub holder: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePriceHistory<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    // Loaded by hand, since it still has the old layout until the handler rewrites it
+    /// CHECK: PDA and owner are checked here; the handler checks size and discriminator
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub price_history: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PreDeposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = depositor,
+        space = 8 + QueuedDeposit::INIT_SPACE,
+        seeds = [b"pre_deposit", campaign.key().as_ref(), &campaign.pre_deposit_tail.to_le_bytes()],
+        bump
+    )]
+    pub deposit: Account<'info, QueuedDeposit>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // Required once the insurance pool takes a share of platform fees
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Option<Account<'info, InsurancePool>>,
+
+    // Required when the campaign restricts contributor regions
+    #[account(seeds = [b"region", depositor.key().as_ref()], bump = region_attestation.bump)]
+    pub region_attestation: Option<Account<'info, RegionAttestation>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPreDeposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"pre_deposit", campaign.key().as_ref(), &deposit.index.to_le_bytes()],
+        bump = deposit.bump,
+        has_one = depositor
+    )]
+    pub deposit: Account<'info, QueuedDeposit>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProcessPreDeposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Box<Account<'info, Campaign>>,
+
+    // Only the deposit at the head of the queue can be processed
+    #[account(
+        mut,
+        close = depositor,
+        seeds = [b"pre_deposit", campaign.key().as_ref(), &campaign.pre_deposit_head.to_le_bytes()],
+        bump = deposit.bump,
+        has_one = depositor
+    )]
+    pub deposit: Account<'info, QueuedDeposit>,
+
+    /// CHECK: receives the deposit's tokens and rent; matched against the deposit
+    #[account(mut)]
+    pub depositor: UncheckedAccount<'info>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: the depositor's token account, created by the handler when the deposit converts into tokens
+    #[account(
+        mut,
+        address = associated_token::get_associated_token_address(&depositor.key(), &token_mint.key())
+            @ ErrorCode::InvalidTokenAccount
+    )]
+    pub depositor_token_account: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = 8 + ContributionRecord::INIT_SPACE,
+        seeds = [b"contribution", campaign.key().as_ref(), depositor.key().as_ref()],
+        bump
+    )]
+    pub contribution_record: Box<Account<'info, ContributionRecord>>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", campaign.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    // Only required for uniform-price raises
+    #[account(mut, address = campaign.ticket_mint @ ErrorCode::InvalidTokenMint)]
+    pub ticket_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(mut, token::mint = ticket_mint, token::authority = depositor)]
+    pub depositor_ticket_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the depositor's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", depositor.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+}
+
+// Instructions the creator can delegate to co-creators by role
+#[derive(Accounts)]
+pub struct CampaignAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(question_hash: [u8; 32])]
+pub struct SetFaqEntry<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + FaqEntry::INIT_SPACE,
+        seeds = [b"faq", campaign.key().as_ref(), question_hash.as_ref()],
+        bump
+    )]
+    pub faq_entry: Account<'info, FaqEntry>,
+
+    // The creator, or a co-creator holding the metadata role
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFaqEntry<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"faq", campaign.key().as_ref(), faq_entry.question_hash.as_ref()],
+        bump = faq_entry.bump
+    )]
+    pub faq_entry: Account<'info, FaqEntry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveCampaign<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    // Gets the review bond back
+    #[account(mut, address = campaign.creator @ ErrorCode::NotCampaignCreator)]
+    pub creator: SystemAccount<'info>,
+
+    pub reviewer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RejectCampaign<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    #[account(
+        mut,
+        seeds = [b"creator_profile", campaign.creator.as_ref()],
+        bump = creator_profile.bump
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    // Required when unique symbols are enforced, so the symbol is freed too
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"symbol", naming::stored_symbol_seed(&campaign.token_symbol).as_ref()],
+        bump = symbol_index.bump,
+        has_one = campaign
+    )]
+    pub symbol_index: Option<Account<'info, SymbolIndex>>,
+
+    // Required when a sponsor match is registered, so its escrow goes back
+    #[account(mut, seeds = [b"match", campaign.key().as_ref()], bump = match_commitment.bump)]
+    pub match_commitment: Option<Account<'info, MatchCommitment>>,
+
+    /// CHECK: receives the match escrow; checked against the commitment in the handler
+    #[account(mut)]
+    pub sponsor: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, address = campaign.creator @ ErrorCode::NotCampaignCreator)]
+    pub creator: SystemAccount<'info>,
+
+    pub reviewer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InvalidateTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ContributeBasket<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    /// CHECK: the contributor's backer profile, updated once per leg when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", contributor.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the contributor's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", contributor.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // Required once the insurance pool takes a share of platform fees
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Option<Account<'info, InsurancePool>>,
+
+    // Required when any campaign in the basket restricts contributor regions
+    #[account(seeds = [b"region", contributor.key().as_ref()], bump = region_attestation.bump)]
+    pub region_attestation: Option<Account<'info, RegionAttestation>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeInsurancePool<'info> {
+    #[account(mut, seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + InsurancePool::INIT_SPACE,
+        seeds = [b"insurance_pool"],
+        bump
+    )]
+    pub insurance_pool: Account<'info, InsurancePool>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RuleCampaignFraudulent<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Account<'info, InsurancePool>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FraudRuling::INIT_SPACE,
+        seeds = [b"fraud_ruling", campaign.key().as_ref()],
+        bump
+    )]
+    pub ruling: Account<'info, FraudRuling>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimInsurance<'info> {
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Account<'info, InsurancePool>,
+
+    #[account(
+        mut,
+        seeds = [b"fraud_ruling", ruling.campaign.as_ref()],
+        bump = ruling.bump
+    )]
+    pub ruling: Account<'info, FraudRuling>,
+
+    // Existence of the receipt is what stops a second claim
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + InsuranceClaim::INIT_SPACE,
+        seeds = [b"insurance_claim", ruling.key().as_ref(), claimant.key().as_ref()],
+        bump
+    )]
+    pub claim_receipt: Account<'info, InsuranceClaim>,
+
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireStaleCampaign<'info> {
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    #[account(
+        mut,
+        seeds = [b"creator_profile", campaign.creator.as_ref()],
+        bump = creator_profile.bump
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    // Required when unique symbols are enforced, so the symbol is freed too
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"symbol", naming::stored_symbol_seed(&campaign.token_symbol).as_ref()],
+        bump = symbol_index.bump,
+        has_one = campaign
+    )]
+    pub symbol_index: Option<Account<'info, SymbolIndex>>,
+
+    // Required when a sponsor match is registered, so its escrow goes back
+    #[account(mut, seeds = [b"match", campaign.key().as_ref()], bump = match_commitment.bump)]
+    pub match_commitment: Option<Account<'info, MatchCommitment>>,
+
+    /// CHECK: receives the match escrow; checked against the commitment in the handler
+    #[account(mut)]
+    pub sponsor: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, address = campaign.creator @ ErrorCode::NotCampaignCreator)]
+    pub creator: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferCampaignAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    /// CHECK: pinned to SPL Governance, so realms and governances can't be forged
+    #[account(address = SPL_GOVERNANCE_ID @ ErrorCode::InvalidGovernanceAccount)]
+    pub governance_program: UncheckedAccount<'info>,
+
+    /// CHECK: realm account, owned by the governance program; its community mint is checked in the handler
+    #[account(owner = governance_program.key() @ ErrorCode::InvalidGovernanceAccount)]
+    pub realm: UncheckedAccount<'info>,
+
+    /// CHECK: governance PDA of the realm, re-derived in the handler
+    #[account(owner = governance_program.key() @ ErrorCode::InvalidGovernanceAccount)]
+    pub governance: UncheckedAccount<'info>,
+
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetEventSchema {}
+
+#[derive(Accounts)]
+pub struct AuditCurve<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeDistribution<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    // Where the liquidity share goes, typically the account seeding the AMM pool
+    #[account(mut, token::mint = token_mint)]
+    pub liquidity_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"treasury_vault", campaign.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = campaign
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    // The creator, a co-creator holding the withdraw role, or campaign governance
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseVestedTreasury<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, seeds = [b"treasury_vault", campaign.key().as_ref()], bump)]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = campaign.token_mint)]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeWithdrawal<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(seeds = [b"price_history", campaign.key().as_ref()], bump = price_history.load()?.bump)]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + WithdrawalApproval::INIT_SPACE,
+        seeds = [b"withdrawal_approval", campaign.key().as_ref(), &campaign.withdrawal_count.to_le_bytes()],
+        bump
+    )]
+    pub withdrawal_approval: Account<'info, WithdrawalApproval>,
+
+    // The creator or a co-creator holding the withdraw role
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelWithdrawalProposal<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"withdrawal_approval", campaign.key().as_ref(), &campaign.withdrawal_count.to_le_bytes()],
+        bump = withdrawal_approval.bump
+    )]
+    pub withdrawal_approval: Account<'info, WithdrawalApproval>,
+
+    // The creator or a co-creator holding the withdraw role
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveWithdrawal<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"withdrawal_approval", campaign.key().as_ref(), &campaign.withdrawal_count.to_le_bytes()],
+        bump = withdrawal_approval.bump
+    )]
+    pub withdrawal_approval: Account<'info, WithdrawalApproval>,
+
+    pub approver: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ContributeAndStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    // Campaign-owned vault holding every staked token of this campaign
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        seeds = [b"stake_vault", campaign.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = campaign
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [b"stake", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = 8 + ContributionRecord::INIT_SPACE,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution_record: Box<Account<'info, ContributionRecord>>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", campaign.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the contributor's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", contributor.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // Required once the insurance pool takes a share of platform fees
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Option<Account<'info, InsurancePool>>,
+
+    // Required when the campaign restricts contributor regions
+    #[account(seeds = [b"region", contributor.key().as_ref()], bump = region_attestation.bump)]
+    pub region_attestation: Option<Account<'info, RegionAttestation>>,
+
+    /// CHECK: the contributor's backer profile, updated when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", contributor.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        seeds = [b"stake_vault", campaign.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = campaign
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [b"stake", campaign.key().as_ref(), staker.key().as_ref()],
+        bump
+    )]
+    pub stake_position: Box<Account<'info, StakePosition>>,
+
+    #[account(mut, token::mint = campaign.token_mint, token::authority = staker)]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    /// CHECK: the staker's backer profile, updated when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", staker.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, seeds = [b"stake_vault", campaign.key().as_ref()], bump)]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", campaign.key().as_ref(), staker.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(mut, token::mint = campaign.token_mint, token::authority = staker)]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    pub staker: Signer<'info>,
+
+    /// CHECK: the staker's backer profile, updated when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", staker.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct PostAttestation<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CampaignAttestation::INIT_SPACE,
+        seeds = [b"attestation", campaign.key().as_ref(), &campaign.attestation_nonce.to_le_bytes()],
+        bump
+    )]
+    pub attestation: Account<'info, CampaignAttestation>,
+
+    /// CHECK: instructions sysvar, read to find the Ed25519 verification
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundPriceCrank<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + PriceCrank::INIT_SPACE,
+        seeds = [b"price_crank", campaign.key().as_ref()],
+        bump
+    )]
+    pub price_crank: Account<'info, PriceCrank>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CrankPrice<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"price_crank", campaign.key().as_ref()],
+        bump = price_crank.bump
+    )]
+    pub price_crank: Account<'info, PriceCrank>,
+
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStakingRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", campaign.key().as_ref(), staker.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(mut)]
+    pub staker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLaunchCalendar<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + LaunchCalendar::INIT_SPACE,
+        seeds = [b"launch_calendar"],
+        bump
+    )]
+    pub launch_calendar: Account<'info, LaunchCalendar>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(window_index: u64)]
+pub struct ReserveLaunchSlot<'info> {
+    #[account(seeds = [b"launch_calendar"], bump = launch_calendar.bump)]
+    pub launch_calendar: Account<'info, LaunchCalendar>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + LaunchSlot::INIT_SPACE,
+        seeds = [b"launch_slot".as_ref(), &window_index.to_le_bytes()],
+        bump
+    )]
+    pub launch_slot: Account<'info, LaunchSlot>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LaunchCampaign<'info> {
+    #[account(seeds = [b"launch_calendar"], bump = launch_calendar.bump)]
+    pub launch_calendar: Account<'info, LaunchCalendar>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    pub keeper: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseLaunchSlot<'info> {
+    #[account(
+        mut,
+        seeds = [b"launch_slot".as_ref(), &campaign.launch_window.to_le_bytes()],
+        bump = launch_slot.bump
+    )]
+    pub launch_slot: Account<'info, LaunchSlot>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveParentCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    /// CHECK: the parent campaign, read in the handler; rejected and stale
+    /// campaigns are closed, so it may no longer hold one
+    #[account(address = campaign.parent_campaign @ ErrorCode::InvalidParentCampaign)]
+    pub parent_campaign: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PostGatedContent<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GatedContent::INIT_SPACE,
+        seeds = [b"gated_content", campaign.key().as_ref(), &campaign.gated_content_count.to_le_bytes()],
+        bump
+    )]
+    pub gated_content: Account<'info, GatedContent>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterContentKey<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        seeds = [b"contribution", campaign.key().as_ref(), backer.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(
+        init_if_needed,
+        payer = backer,
+        space = 8 + ContentKeyRegistration::INIT_SPACE,
+        seeds = [b"content_key", campaign.key().as_ref(), backer.key().as_ref()],
+        bump
+    )]
+    pub content_key: Account<'info, ContentKeyRegistration>,
+
+    #[account(mut)]
+    pub backer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeContentKey<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        seeds = [b"contribution", campaign.key().as_ref(), backer.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(
+        mut,
+        close = backer,
+        seeds = [b"content_key", campaign.key().as_ref(), backer.key().as_ref()],
+        bump = content_key.bump,
+        has_one = backer
+    )]
+    pub content_key: Account<'info, ContentKeyRegistration>,
+
+    /// CHECK: the registered backer, who gets the registration's rent back
+    #[account(mut)]
+    pub backer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenClosingAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ClosingAuction::INIT_SPACE,
+        seeds = [b"closing_auction", campaign.key().as_ref()],
+        bump
+    )]
+    pub closing_auction: Account<'info, ClosingAuction>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    pub closing_auction: Account<'info, ClosingAuction>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + ClosingBid::INIT_SPACE,
+        seeds = [b"closing_bid", closing_auction.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub closing_bid: Account<'info, ClosingBid>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealBid<'info> {
+    #[account(mut)]
+    pub closing_auction: Account<'info, ClosingAuction>,
+
+    #[account(
+        mut,
+        seeds = [b"closing_bid", closing_auction.key().as_ref(), bidder.key().as_ref()],
+        bump = closing_bid.bump
+    )]
+    pub closing_bid: Account<'info, ClosingBid>,
+
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Box<Account<'info, Campaign>>,
+
+    #[account(mut, seeds = [b"closing_auction", campaign.key().as_ref()], bump = closing_auction.bump)]
+    pub closing_auction: Account<'info, ClosingAuction>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    // Required when a bid won
+    #[account(
+        mut,
+        seeds = [b"closing_bid", closing_auction.key().as_ref(), closing_auction.best_bidder.as_ref()],
+        bump = winning_bid.bump
+    )]
+    pub winning_bid: Option<Account<'info, ClosingBid>>,
+
+    #[account(mut, token::mint = token_mint)]
+    pub winner_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimBid<'info> {
+    pub closing_auction: Account<'info, ClosingAuction>,
+
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [b"closing_bid", closing_auction.key().as_ref(), bidder.key().as_ref()],
+        bump = closing_bid.bump
+    )]
+    pub closing_bid: Account<'info, ClosingBid>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeBackerProfile<'info> {
+    #[account(
+        init,
+        payer = wallet,
+        space = 8 + BackerProfile::INIT_SPACE,
+        seeds = [b"backer_profile", wallet.key().as_ref()],
+        bump
+    )]
+    pub backer_profile: Account<'info, BackerProfile>,
+
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedirectRefund<'info> {
+    // The failed campaign being refunded
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Box<Account<'info, Campaign>>,
+
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Box<Account<'info, ContributionRecord>>,
+
+    // The campaign token, or the ticket mint for uniform-price raises
+    #[account(mut)]
+    pub mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, token::mint = mint, token::authority = contributor)]
+    pub contributor_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", target_campaign.creator.as_ref(), target_campaign.name_hash.as_ref()],
+        bump = target_campaign.bump
+    )]
+    pub target_campaign: Box<Account<'info, Campaign>>,
+
+    #[account(mut, address = target_campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub target_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        associated_token::mint = target_token_mint,
+        associated_token::authority = contributor
+    )]
+    pub target_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = 8 + ContributionRecord::INIT_SPACE,
+        seeds = [b"contribution", target_campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub target_contribution_record: Box<Account<'info, ContributionRecord>>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", target_campaign.key().as_ref()],
+        bump = target_price_history.load()?.bump
+    )]
+    pub target_price_history: AccountLoader<'info, PriceHistory>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", target_campaign.key().as_ref()], bump = target_price_feed.bump)]
+    pub target_price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    // Required when the target campaign restricts contributor regions
+    #[account(seeds = [b"region", contributor.key().as_ref()], bump = region_attestation.bump)]
+    pub region_attestation: Option<Account<'info, RegionAttestation>>,
+
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the contributor's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", contributor.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // Required once the insurance pool takes a share of platform fees
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Option<Account<'info, InsurancePool>>,
+
+    /// CHECK: the contributor's backer profile, updated when they have initialized one, for both the exit and the new position
+    #[account(mut, seeds = [b"backer_profile", contributor.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct DenyAddress<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + DenylistEntry::INIT_SPACE,
+        seeds = [b"denylist", address.as_ref()],
+        bump
+    )]
+    pub denylist_entry: Account<'info, DenylistEntry>,
+
+    // The admin or the denylist authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(skeleton_hash: [u8; 32])]
+pub struct BlockName<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + BlockedName::INIT_SPACE,
+        seeds = [b"blocked_name", skeleton_hash.as_ref()],
+        bump
+    )]
+    pub blocked_name: Account<'info, BlockedName>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnblockName<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"blocked_name", blocked_name.skeleton_hash.as_ref()],
+        bump = blocked_name.bump
+    )]
+    pub blocked_name: Account<'info, BlockedName>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AllowAddress<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"denylist", denylist_entry.address.as_ref()],
+        bump = denylist_entry.bump
+    )]
+    pub denylist_entry: Account<'info, DenylistEntry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterRevenueStream<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RevenueStream::INIT_SPACE,
+        seeds = [b"revenue_stream", campaign.key().as_ref()],
+        bump
+    )]
+    pub revenue_stream: Account<'info, RevenueStream>,
+
+    // SPL streams only: the mint revenue is paid in and the vault holding it
+    pub revenue_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"revenue_vault", revenue_stream.key().as_ref()],
+        bump,
+        token::mint = revenue_mint,
+        token::authority = revenue_stream,
+    )]
+    pub revenue_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DepositRevenue<'info> {
+    #[account(mut, seeds = [b"revenue_stream", revenue_stream.campaign.as_ref()], bump = revenue_stream.bump)]
+    pub revenue_stream: Account<'info, RevenueStream>,
+
+    #[account(mut)]
+    pub source: Signer<'info>,
+
+    // SPL streams only
+    #[account(mut, token::authority = source)]
+    pub source_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = revenue_stream.vault @ ErrorCode::InvalidRevenueAccounts)]
+    pub revenue_vault: Option<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SnapshotRevenue<'info> {
+    #[account(mut, seeds = [b"revenue_stream", revenue_stream.campaign.as_ref()], bump = revenue_stream.bump)]
+    pub revenue_stream: Account<'info, RevenueStream>,
+
+    #[account(
+        init,
+        payer = oracle,
+        space = 8 + RevenueEpoch::INIT_SPACE,
+        seeds = [b"revenue_epoch", revenue_stream.key().as_ref(), &revenue_stream.epoch_count.to_le_bytes()],
+        bump
+    )]
+    pub revenue_epoch: Account<'info, RevenueEpoch>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VoidRevenueSnapshot<'info> {
+    #[account(mut, seeds = [b"revenue_stream", revenue_stream.campaign.as_ref()], bump = revenue_stream.bump)]
+    pub revenue_stream: Account<'info, RevenueStream>,
+
+    #[account(
+        mut,
+        seeds = [b"revenue_epoch", revenue_stream.key().as_ref(), &revenue_epoch.index.to_le_bytes()],
+        bump = revenue_epoch.bump
+    )]
+    pub revenue_epoch: Account<'info, RevenueEpoch>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SweepRevenueEpoch<'info> {
+    #[account(mut, seeds = [b"revenue_stream", revenue_stream.campaign.as_ref()], bump = revenue_stream.bump)]
+    pub revenue_stream: Account<'info, RevenueStream>,
+
+    #[account(
+        mut,
+        seeds = [b"revenue_epoch", revenue_stream.key().as_ref(), &revenue_epoch.index.to_le_bytes()],
+        bump = revenue_epoch.bump
+    )]
+    pub revenue_epoch: Account<'info, RevenueEpoch>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRevenue<'info> {
+    #[account(mut, seeds = [b"revenue_stream", revenue_stream.campaign.as_ref()], bump = revenue_stream.bump)]
+    pub revenue_stream: Account<'info, RevenueStream>,
+
+    #[account(
+        mut,
+        seeds = [b"revenue_epoch", revenue_stream.key().as_ref(), &revenue_epoch.index.to_le_bytes()],
+        bump = revenue_epoch.bump
+    )]
+    pub revenue_epoch: Account<'info, RevenueEpoch>,
+
+    // Existence of the claim is what stops a second one
+    #[account(
+        init,
+        payer = holder,
+        space = 8 + RevenueClaim::INIT_SPACE,
+        seeds = [b"revenue_claim", revenue_epoch.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub revenue_claim: Account<'info, RevenueClaim>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    // SPL streams only
+    #[account(mut, address = revenue_stream.vault @ ErrorCode::InvalidRevenueAccounts)]
+    pub revenue_vault: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::authority = holder)]
+    pub holder_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct OpenSalaryStream<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + SalaryStream::INIT_SPACE,
+        seeds = [b"salary_stream", campaign.key().as_ref(), &campaign.salary_stream_count.to_le_bytes()],
+        bump
+    )]
+    pub salary_stream: Account<'info, SalaryStream>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + WithdrawalReceipt::INIT_SPACE,
+        seeds = [b"withdrawal", campaign.key().as_ref(), &campaign.withdrawal_count.to_le_bytes()],
+        bump
+    )]
+    pub receipt: Account<'info, WithdrawalReceipt>,
+
+    #[account(
+        seeds = [b"withdrawal_approval", campaign.key().as_ref(), &campaign.withdrawal_count.to_le_bytes()],
+        bump = withdrawal_approval.bump
+    )]
+    pub withdrawal_approval: Option<Account<'info, WithdrawalApproval>>,
+
+    // The creator, or a co-creator holding the withdraw role
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: the signer's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", creator.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSalary<'info> {
+    #[account(
+        mut,
+        seeds = [b"salary_stream", salary_stream.campaign.as_ref(), &salary_stream.index.to_le_bytes()],
+        bump = salary_stream.bump,
+        has_one = recipient
+    )]
+    pub salary_stream: Account<'info, SalaryStream>,
+
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetSalaryStreamPaused<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"salary_stream", campaign.key().as_ref(), &salary_stream.index.to_le_bytes()],
+        bump = salary_stream.bump
+    )]
+    pub salary_stream: Account<'info, SalaryStream>,
+
+    pub governance: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MintDonationReceipt<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        seeds = [b"contribution", campaign.key().as_ref(), donor.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(
+        init,
+        payer = donor,
+        seeds = [b"donation_receipt", campaign.key().as_ref(), donor.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = campaign,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = donor,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = donor
+    )]
+    pub donor_receipt_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub donor: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CrankRefundBatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    // Anyone; paid a slice of each closed record's rent
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LinkIdentity<'info> {
+    #[account(
+        mut,
+        seeds = [b"creator_profile", creator.key().as_ref()],
+        bump = creator_profile.bump
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    pub creator: Signer<'info>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// CHECK: the domain's name registry account; its address, owner and data are checked in the handler
+    pub name_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: instructions sysvar, read to find the verifier's Ed25519 signature
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshIdentity<'info> {
+    #[account(
+        mut,
+        seeds = [b"creator_profile", creator_profile.creator.as_ref()],
+        bump = creator_profile.bump
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    /// CHECK: the linked domain's name registry account; its address, owner and data are checked in the handler
+    pub name_account: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MintReservedAllocation<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(mut, token::mint = token_mint)]
+    pub destination: Account<'info, TokenAccount>,
+
+    // The creator, or a co-creator holding the withdraw role
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Which slice of total_supply a mint draws from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SupplyBucket {
+    Curve,
+    Creator,
+    Liquidity,
+    Airdrop,
+}
+
+#[derive(Accounts)]
+pub struct InitializePriceFeed<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PriceFeed::INIT_SPACE,
+        seeds = [b"price_feed", campaign.key().as_ref()],
+        bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterDepositAdapter<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DepositAdapter::INIT_SPACE,
+        seeds = [b"deposit_adapter", adapter_program.key().as_ref()],
+        bump
+    )]
+    pub adapter: Account<'info, DepositAdapter>,
+
+    /// CHECK: the registering program; it proves itself through adapter_signer
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
+
+    // The program's adapter PDA, signed for with invoke_signed
+    #[account(seeds = [DEPOSIT_ADAPTER_SIGNER_SEED], bump, seeds::program = adapter_program.key())]
+    pub adapter_signer: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetDepositAdapterActive<'info> {
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(mut, seeds = [b"deposit_adapter", adapter.program.as_ref()], bump = adapter.bump)]
+    pub adapter: Account<'info, DepositAdapter>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, reference: [u8; 32])]
+pub struct DepositViaAdapter<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(mut, seeds = [b"deposit_adapter", adapter.program.as_ref()], bump = adapter.bump)]
+    pub adapter: Box<Account<'info, DepositAdapter>>,
+
+    #[account(address = adapter.signer @ ErrorCode::Unauthorized)]
+    pub adapter_signer: Signer<'info>,
+
+    /// CHECK: the adapter's user, who owns the resulting position
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        associated_token::mint = token_mint,
+        associated_token::authority = beneficiary
+    )]
+    pub beneficiary_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + ContributionRecord::INIT_SPACE,
+        seeds = [b"contribution", campaign.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub contribution_record: Box<Account<'info, ContributionRecord>>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + AdapterSource::INIT_SPACE,
+        seeds = [
+            b"adapter_source",
+            contribution_record.key().as_ref(),
+            adapter.key().as_ref(),
+            reference.as_ref()
+        ],
+        bump
+    )]
+    pub adapter_source: Box<Account<'info, AdapterSource>>,
+
+    #[account(
+        mut,
+        seeds = [b"price_history", campaign.key().as_ref()],
+        bump = price_history.load()?.bump
+    )]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    // Oracle-layout price account, refreshed by the trade when passed
+    #[account(mut, seeds = [b"price_feed", campaign.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Option<Box<Account<'info, PriceFeed>>>,
+
+    // Optional; refreshed with this campaign's totals when passed in
+    #[account(mut, seeds = [b"leaderboard"], bump = leaderboard.load()?.bump)]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    // Required while a sponsor's match is registered on the campaign
+    #[account(
+        mut,
+        seeds = [b"match", campaign.key().as_ref()],
+        bump = match_commitment.bump
+    )]
+    pub match_commitment: Option<Box<Account<'info, MatchCommitment>>>,
+
+    /// CHECK: the beneficiary's backer profile, updated when they have initialized one
+    #[account(mut, seeds = [b"backer_profile", beneficiary.key().as_ref()], bump)]
+    pub backer_profile: UncheckedAccount<'info>,
+
+    // Pays the deposit and any rent, usually the adapter's vault
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// CHECK: must be the campaign's governance mint
+    #[account(mut, address = campaign.governance_mint @ ErrorCode::InvalidTokenMint)]
+    pub governance_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: PDA used only as the governance mint and freeze authority
+    #[account(
+        seeds = [b"governance_authority", campaign.key().as_ref()],
+        bump = campaign.governance_authority_bump
+    )]
+    pub governance_authority: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: beneficiary's governance ATA, verified and created in the handler
+    #[account(mut)]
+    pub beneficiary_governance_account: Option<UncheckedAccount<'info>>,
+
+    // Only required for uniform-price raises
+    #[account(mut, address = campaign.ticket_mint @ ErrorCode::InvalidTokenMint)]
+    pub ticket_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(mut, token::mint = ticket_mint, token::authority = beneficiary)]
+    pub beneficiary_ticket_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(mut, address = global_config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // Required once the insurance pool takes a share of platform fees
+    #[account(mut, seeds = [b"insurance_pool"], bump = insurance_pool.bump)]
+    pub insurance_pool: Option<Account<'info, InsurancePool>>,
+
+    /// CHECK: the beneficiary's denylist entry address, which must be empty; required while screening is on
+    #[account(seeds = [b"denylist", beneficiary.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    // Required when the campaign restricts contributor regions
+    #[account(seeds = [b"region", beneficiary.key().as_ref()], bump = region_attestation.bump)]
+    pub region_attestation: Option<Account<'info, RegionAttestation>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct ArchiveCampaign<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(seeds = [b"price_history", campaign.key().as_ref()], bump = price_history.load()?.bump)]
+    pub price_history: AccountLoader<'info, PriceHistory>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CampaignArchive::INIT_SPACE,
+        seeds = [b"campaign_archive", campaign.key().as_ref()],
+        bump
+    )]
+    pub archive: Account<'info, CampaignArchive>,
+
+    // Anyone can pay the rent to preserve a campaign
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(contributor: Pubkey)]
+pub struct GetPosition<'info> {
+    #[account(
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Option<Account<'info, ContributionRecord>>,
+
+    #[account(
+        address = associated_token::get_associated_token_address(&contributor, &campaign.token_mint)
+            @ ErrorCode::InvalidTokenAccount
+    )]
+    pub contributor_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"stake", campaign.key().as_ref(), contributor.as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Option<Account<'info, StakePosition>>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct ConfigureAllowlistTier<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AllowlistTier::INIT_SPACE,
+        seeds = [b"allowlist_tier", campaign.key().as_ref(), &[index]],
+        bump
+    )]
+    pub tier: Account<'info, AllowlistTier>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTierBonus<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator.as_ref(), campaign.name_hash.as_ref()],
+        bump = campaign.bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump = contribution_record.bump
+    )]
+    pub contribution_record: Account<'info, ContributionRecord>,
+
+    #[account(mut, address = campaign.token_mint @ ErrorCode::InvalidTokenMint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        associated_token::mint = token_mint,
+        associated_token::authority = contributor
+    )]
+    pub contributor_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum CampaignStatus {
+    Active,
+    // Waiting on the platform reviewer before it can take contributions
+    PendingReview,
+    Graduated,
+    // Ended below target; contributors can refund
+    Failed,
+}
+
+// Off-chain intent a contributor signs so a relayer can submit on their behalf
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ContributionIntent {
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+    pub expiry: i64,
+}
+
+// What the attestation oracle signs; kind is a frontend-defined code
+// (audit passed, team KYC'd, ...) and data_hash points at the report
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AttestationStatement {
+    pub campaign: Pubkey,
+    pub kind: u16,
+    pub data_hash: [u8; 32],
+    pub nonce: u64,
+}
+
+// What the identity verifier signs to vouch that a creator controls a DID
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct IdentityStatement {
+    pub creator: Pubkey,
+    pub did: String,
+    pub nonce: u64,
+    pub expiry: i64,
+}
+
+// Opt-in features chosen by the creator at initialization
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CampaignOptions {
+    // Share of each contribution locked as a redemption reserve (0 disables the floor)
+    pub floor_reserve_bps: u16,
+    // Also issue a non-transferable governance token 1:1 with curve tokens
+    pub governance_token: bool,
+    // Governance tokens stay frozen until this long after the campaign ends
+    pub governance_lockup_secs: i64,
+    // Keep contributor token accounts frozen until the campaign graduates
+    pub freeze_during_raise: bool,
+    // Issue claim tickets and settle every contribution at the closing price
+    pub uniform_price: bool,
+    // Let contributors refund part of their position if the campaign fails
+    pub partial_refunds: bool,
+    // Raised amount at which the campaign is flagged ready to graduate (0 uses the target)
+    pub graduation_threshold: u64,
+    // Largest price move a single contribution may cause (0 disables the limit)
+    pub max_price_impact_bps: u16,
+    // Share of each contribution held back for curve buy-backs (0 lets the creator withdraw everything)
+    pub curve_reserve_bps: u16,
+    // Bit n set bars contributors attested to region n (0 disables the gate)
+    pub restricted_regions_bitmap: u64,
+    // Lending yield on idle SOL goes to contributors instead of the creator
+    pub yield_to_contributors: bool,
+    // Curve fees kept by the campaign on every buy and sell
+    pub buy_fee_bps: u16,
+    pub sell_fee_bps: u16,
+    // Post-graduation price rise per SOL bought, in bps of the closing price (0 disables it)
+    pub secondary_slope_bps: u16,
+    // Fee on secondary trades, paid to the creator
+    pub secondary_fee_bps: u16,
+    // Curve opens at this time (0 opens it straight away)
+    pub start_time: i64,
+    // Accept escrowed deposits before start_time
+    pub pre_deposits: bool,
+    // How long after graduation the creator and co-creators must wait to sell
+    pub team_lockup_secs: i64,
+    // Split of the supply left unsold at graduation (all zero leaves it unminted)
+    pub distribution: UnsoldDistribution,
+    // Ascending raise targets past target_amount; each one reached unlocks the
+    // next slice of withdrawals
+    pub stretch_goals: Vec<u64>,
+    // Withdrawals above this need sign-off from the top three backers (0 disables)
+    pub withdrawal_approval_threshold: u64,
+    // Ascending contributor counts; the treasury vest releases one equal tranche
+    // per milestone reached, so broad distribution unlocks it rather than size
+    pub contributor_milestones: Vec<u32>,
+    // Remaining contribution a backer needs to count towards the milestones,
+    // so each one costs a sybil real SOL (0 counts every backer)
+    pub milestone_min_contribution: u64,
+    // Share of each creator withdrawal left behind for the campaign's stakers
+    pub exit_fee_bps: u16,
+    // Most the campaign will raise; contributions past it are partially filled (0 = uncapped)
+    pub hard_cap: u64,
+    // Hold the campaign as escrowed pre-deposits until this campaign graduates
+    pub parent_campaign: Option<Pubkey>,
+    // Reject buys that would move the price more than this far from its
+    // average over the last circuit_breaker_slots (0 = off)
+    pub circuit_breaker_bps: u16,
+    pub circuit_breaker_slots: u64,
+    // Contributions are pure donations: nothing is minted, and donors can
+    // mint themselves a one-off receipt NFT instead
+    pub donation_mode: bool,
+    // Backers can't sell until this long after their latest buy (0 = no minimum)
+    pub min_hold_secs: i64,
+    // Slices of total_supply held back from the curve; the curve sells the rest
+    pub creator_allocation: u64,
+    pub liquidity_reserve: u64,
+    pub airdrop_reserve: u64,
+    // Most of the raise the creator can withdraw per drip_epoch_secs (0 = no cap)
+    pub drip_cap_bps: u16,
+    pub drip_epoch_secs: i64,
+    // Issuance curve; None takes CurveConfig::default()
+    pub curve: Option<CurveConfig>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Campaign {
+    pub creator: Pubkey,
+    #[max_len(50)]
+    pub name: String,
+    pub name_hash: [u8; 32],
+    #[max_len(200)]
+    pub description: String,
+    pub target_amount: u64,
+    pub raised_amount: u64,
+    #[max_len(10)]
+    pub token_symbol: String,
+    #[max_len(50)]
+    pub token_name: String,
+    pub total_supply: u64,
+    // total_supply split into buckets, each minted only up to its own size
+    pub curve_supply: u64,
+    pub creator_allocation: u64,
+    pub liquidity_reserve: u64,
+    pub airdrop_reserve: u64,
+    pub curve_minted: u64,
+    pub creator_minted: u64,
+    pub liquidity_minted: u64,
+    pub airdrop_minted: u64,
+    // Counted in curve_minted but not minted yet: unclaimed tier bonuses and
+    // an open closing auction's lot
+    pub curve_undelivered: u64,
+    pub token_mint: Pubkey,
+    pub created_at: i64,
+    pub end_time: i64,
+    pub status: CampaignStatus,
+    pub floor_reserve_bps: u16,
+    pub floor_reserve: u64,
+    // Lamports paid per whole token on redemption, fixed at graduation
+    pub floor_price: u64,
+    pub graduated_at: i64,
+    pub governance_mint: Pubkey,
+    pub governance_authority_bump: u8,
+    pub governance_lockup_secs: i64,
+    pub freeze_during_raise: bool,
+    pub ticket_mint: Pubkey,
+    // Set once initialize_price_feed opens the feed; every trade must pass it from then on
+    pub price_feed: Pubkey,
+    // Raised amount at graduation, which fixes the ticket conversion rate
+    pub clearing_raised: u64,
+    pub partial_refunds: bool,
+    pub graduation_threshold: u64,
+    pub ready_to_graduate: bool,
+    pub max_price_impact_bps: u16,
+    pub match_commitment: Pubkey,
+    // Creator SOL taken out and later spent back on the curve as treasury buys
+    pub withdrawn_amount: u64,
+    pub treasury_bought: u64,
+    // Gross contributions so far, which decide the platform fee tier
+    pub fee_basis_raised: u64,
+    // raised_amount split between SOL backing curve sells and SOL the creator
+    // may withdraw; sells drain the reserve first
+    pub curve_reserve_bps: u16,
+    pub reserve_balance: u64,
+    pub withdrawable_balance: u64,
+    pub restricted_regions_bitmap: u64,
+    // Principal out with the lending program; counts towards raised_amount but
+    // is in neither balance until recalled
+    pub lent_amount: u64,
+    pub yield_to_contributors: bool,
+    // SOL held for contributors outside the curve (lending yield and retained
+    // curve fees), paid out with refunds or folded into the floor reserve at graduation
+    pub refund_buffer: u64,
+    pub buy_fee_bps: u16,
+    pub sell_fee_bps: u16,
+    pub secondary_slope_bps: u16,
+    pub secondary_fee_bps: u16,
+    // Primary closing price the secondary curve starts from
+    pub secondary_base_price: u64,
+    // SOL held for the secondary curve, kept apart from the creator's withdrawable balance
+    pub secondary_raised: u64,
+    // Concurrent merkle tree holding contribution receipts for compressed
+    // campaigns, and how many leaves have been appended to it
+    pub record_tree: Pubkey,
+    pub compressed_record_count: u64,
+    // Whether the creator's profile has stopped counting this campaign as active
+    pub profile_settled: bool,
+    // Number of WithdrawalReceipts written, which seeds the next one
+    pub withdrawal_count: u64,
+    // Number of SalaryStreams opened, which seeds the next one
+    pub salary_stream_count: u64,
+    // Set by the admin during incidents; paused_raised is the raise the exit queue is priced at
+    pub paused: bool,
+    pub paused_raised: u64,
+    // Exit requests are numbered from tail; head is the next one to pay
+    pub exit_queue_head: u64,
+    pub exit_queue_tail: u64,
+    pub pending_exit_sol: u64,
+    // Sum of price * seconds since launch, rolled forward on every curve trade
+    pub price_cumulative: u128,
+    pub last_trade_price: u64,
+    pub last_trade_at: i64,
+    pub start_time: i64,
+    // Pre-launch deposit queue: head is the next deposit to convert, tail the next index handed out
+    pub pre_deposits: bool,
+    pub pre_deposit_head: u64,
+    pub pre_deposit_tail: u64,
+    pub pre_deposit_total: u64,
+    // The opening block the queue converts at: its SOL and the tokens that SOL
+    // buys from the start of the curve, so every deposit gets the same rate
+    pub queue_sol: u64,
+    pub queue_tokens: u64,
+    // Team members the creator has delegated roles to; the creator holds every role
+    pub co_creators: [CoCreator; MAX_CO_CREATORS],
+    pub co_creator_count: u8,
+    pub contributions_paused: bool,
+    pub update_count: u64,
+    // Bond held while the campaign awaits review
+    pub review_bond: u64,
+    // Holder accounts of a failed campaign are being frozen
+    pub tokens_invalidated: bool,
+    pub team_lockup_secs: i64,
+    // Governance PDA of a realm over the campaign token; once set it holds
+    // every creator role and the founder team holds none
+    pub governance: Pubkey,
+    pub distribution: UnsoldDistribution,
+    pub curve: CurveConfig,
+    pub distribution_finalized: bool,
+    // Treasury share of the unsold supply, released linearly from the vault
+    pub treasury_vesting_start: i64,
+    pub treasury_vesting_total: u64,
+    pub treasury_vesting_released: u64,
+    pub stretch_goals: [u64; MAX_STRETCH_GOALS],
+    pub stretch_goal_count: u8,
+    pub stretch_goals_reached: u8,
+    pub withdrawal_approval_threshold: u64,
+    // Withdrawn since the last approved withdrawal, so splitting one large
+    // withdrawal into chunks under the threshold still needs sign-off
+    pub unapproved_withdrawn: u64,
+    // Next attestation index; the oracle signs it, so each signature posts once
+    pub attestation_nonce: u64,
+    pub contributor_milestones: [u32; MAX_CONTRIBUTOR_MILESTONES],
+    pub contributor_milestone_count: u8,
+    pub contributor_milestones_reached: u8,
+    pub milestone_min_contribution: u64,
+    // Backers currently counting towards the milestones: records holding at
+    // least the minimum, plus compressed receipts over it
+    pub milestone_backers: u32,
+    pub exit_fee_bps: u16,
+    pub total_staked: u64,
+    // Exit fees per staked token (Q64.64), accumulated over the campaign's life
+    pub exit_rewards_per_token_q64: u128,
+    // Exit fees held in the campaign account until stakers claim them
+    pub exit_rewards_pool: u64,
+    // Part of the pool charged while nobody was staked, handed to the next stake
+    pub exit_rewards_held: u64,
+    // Launch calendar window the campaign booked, if any, and the start time
+    // it had before booking, restored if the booking is cancelled
+    pub launch_window: u64,
+    pub launch_slot_reserved: bool,
+    pub booked_start_time: i64,
+    pub hard_cap: u64,
+    // Campaign this one is conditional on; it opens once the parent graduates
+    pub parent_campaign: Pubkey,
+    // Backers with at least this much contributed can register for gated content (0 = off)
+    pub content_threshold: u64,
+    pub gated_content_count: u64,
+    // Curve price at graduation; the reserve for the closing auction
+    pub closing_price: u64,
+    pub circuit_breaker_bps: u16,
+    pub circuit_breaker_slots: u64,
+    // Where the breaker's current window opened, as a point on the price accumulator
+    pub breaker_window_slot: u64,
+    pub breaker_window_at: i64,
+    pub breaker_window_cumulative: u128,
+    pub breaker_window_price: u64,
+    pub donation_mode: bool,
+    pub min_hold_secs: i64,
+    // Latest window end across allowlist tiers; plain contributions wait for it
+    pub allowlist_ends_at: i64,
+    pub faq_count: u8,
+    pub drip_cap_bps: u16,
+    pub drip_epoch_secs: i64,
+    // Withdrawals over the last drip epoch, an eighth of it per bucket, and
+    // the bucket the latest one landed in
+    pub drip_buckets: [u64; DRIP_BUCKETS],
+    pub drip_bucket_at: i64,
+    // Running stats so leaderboards don't need to replay events
+    pub contributor_count: u32,
+    pub contribution_count: u64,
+    pub unique_sellers: u32,
+    pub peak_price: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ContributionRecord {
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    pub sol_contributed: u64,
+    pub tokens_received: u64,
+    // Highest nonce consumed by contribute_with_signature
+    pub relay_nonce: u64,
+    // Highest client-supplied nonce consumed by contribute
+    pub client_nonce: u64,
+    pub has_sold: bool,
+    // Weighted-average lamports paid per whole token (TOKEN_PRECISION units)
+    pub avg_entry_price: u64,
+    // Loyalty rebate inputs: fees paid, fees already rebated, and the last
+    // time the holder bought in fresh or sold
+    pub fees_paid: u64,
+    pub rebated_fees: u64,
+    pub holding_since: i64,
+    // Head of this backer's receipt hash chain and how many receipts it covers
+    pub receipt_hash: [u8; 32],
+    pub receipt_count: u64,
+    // Token-weighted time of the curve buys, which starts the campaign's
+    // minimum holding period
+    pub last_buy_at: i64,
+    // Allowlist tier bonus reserved from the curve bucket, minted after graduation
+    pub pending_bonus: u64,
+    // Whether this backer is one of the campaign's milestone_backers
+    pub counts_toward_milestones: bool,
+    pub bump: u8,
+}
+
+// Where some of a position's deposits came from: the adapter that routed them,
+// the adapter's own reference (a vault position, a savings goal) and how much
+// has come through it. One per adapter and reference, so none overwrites another.
+#[account]
+#[derive(InitSpace)]
+pub struct AdapterSource {
+    pub campaign: Pubkey,
+    pub beneficiary: Pubkey,
+    pub adapter: Pubkey,
+    pub reference: [u8; 32],
+    pub routed: u64,
+    pub bump: u8,
+}
+
+impl ContributionRecord {
+    // sol_contributed is the remaining cost basis, so the average is just the ratio
+    pub fn refresh_entry_price(&mut self) -> Result<()> {
+        self.avg_entry_price = if self.tokens_received == 0 {
+            0
+        } else {
+            u64::try_from(
+                (self.sol_contributed as u128)
+                    .checked_mul(TOKEN_PRECISION)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    / self.tokens_received as u128,
+            )
+            .map_err(|_| ErrorCode::MathOverflow)?
+        };
+        Ok(())
+    }
+}
+
+#[zero_copy]
+pub struct PriceSample {
+    pub timestamp: i64,
+    pub raised_amount: u64,
+    pub price: u64,
+    // Campaign's price_cumulative as of this trade, split into little-endian
+    // halves: account data is only 8-byte aligned, which a u128 field isn't
+    pub price_cumulative: [u64; 2],
+}
+
+impl PriceSample {
+    pub fn price_cumulative(&self) -> u128 {
+        (self.price_cumulative[1] as u128) << 64 | self.price_cumulative[0] as u128
+    }
+}
+
+// Ring buffer of the most recent trades so charts need a single account fetch.
+// Zero-copy since every buy and sell writes it: the 3.5KB is used in place
+// instead of being deserialized onto the heap and written back.
+#[account(zero_copy)]
+pub struct PriceHistory {
+    pub campaign: Pubkey,
+    // Slot the next sample is written to
+    pub head: u16,
+    pub count: u16,
+    pub bump: u8,
+    // Pads the header to 40 bytes so samples start 8-byte aligned
+    pub _padding: [u8; 3],
+    pub samples: [PriceSample; PRICE_HISTORY_LEN],
+    // Largest backers by SOL contributed, kept here rather than on Campaign
+    // since every buy already writes this account
+    pub top_backers: [LeaderboardEntry; LEADERBOARD_LEN],
+}
+
+impl PriceHistory {
+    pub fn record(
+        &mut self,
+        timestamp: i64,
+        raised_amount: u64,
+        price: u64,
+        price_cumulative: u128,
+    ) {
+        self.samples[self.head as usize] = PriceSample {
+            timestamp,
+            raised_amount,
+            price,
+            price_cumulative: [price_cumulative as u64, (price_cumulative >> 64) as u64],
+        };
+        self.head = ((self.head as usize + 1) % PRICE_HISTORY_LEN) as u16;
+        if (self.count as usize) < PRICE_HISTORY_LEN {
+            self.count += 1;
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ExtensionProposal {
+    pub campaign: Pubkey,
+    pub duration: i64,
+    pub voting_ends_at: i64,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub finalized: bool,
+    pub approved: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ExtensionVote {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub approve: bool,
+    pub bump: u8,
+}
+
+// Platform-wide settings owned by the admin
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalConfig {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    // Progressive platform fee on each campaign's cumulative raise
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS],
+    pub fee_tier_count: u8,
+    // Share of the platform fee paid to the frontend that routed a contribution
+    pub frontend_fee_share_bps: u16,
+    pub limits: CampaignLimits,
+    // Allow each token symbol on only one active campaign at a time
+    pub enforce_unique_symbols: bool,
+    // AMM used to pay creators out in stable_mint instead of SOL
+    pub swap_program: Pubkey,
+    pub stable_mint: Pubkey,
+    // Compliance provider allowed to issue region attestations
+    pub region_attestor: Pubkey,
+    // Lending market idle campaign SOL may be deposited into
+    pub lending: LendingMarket,
+    // Multisig the program's upgrade authority is meant to sit with, and what
+    // verify_deployment last found on chain
+    pub expected_upgrade_authority: Pubkey,
+    pub upgrade_authority_status: UpgradeAuthorityStatus,
+    pub deployment_verified_at: i64,
+    // Set once admin has been handed to an SPL Governance account; from then
+    // on admin instructions only pass when a proposal executes them
+    pub governance_program: Pubkey,
+    pub realm: Pubkey,
+    // Permissioned launchpad mode: new campaigns wait for the reviewer and post a bond
+    pub require_review: bool,
+    pub reviewer: Pubkey,
+    pub review_bond: u64,
+    // Slice of the treasury's platform fee diverted into the insurance pool
+    pub insurance_fee_share_bps: u16,
+    // Oracle whose signed facts about campaigns (audits, KYC) are accepted
+    pub attestation_oracle: Pubkey,
+    // Address screening: while enabled, contributors and withdrawing creators
+    // must show they have no denylist entry. The screening oracle can list and
+    // delist addresses alongside the admin.
+    pub denylist_enabled: bool,
+    pub denylist_authority: Pubkey,
+    // Signs DID attestations creators link to their profile
+    pub identity_verifier: Pubkey,
+    // While enabled, new campaigns must show their symbol and name skeletons
+    // have no blocklist entry
+    pub name_blocklist_enabled: bool,
+    // Mint authority of the AMM's LP tokens; only its LP can be locked
+    pub lp_mint_authority: Pubkey,
+    pub bump: u8,
+}
+
+// The one lending market campaigns may lend to. Its deposit and withdraw
+// instructions take the reserve, its liquidity supply, the campaign's
+// position, the yield vault, the campaign as owner and the token program,
+// followed by a little-endian u64 amount.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct LendingMarket {
+    pub program: Pubkey,
+    pub reserve: Pubkey,
+    pub liquidity_supply: Pubkey,
+    pub deposit_discriminator: [u8; 8],
+    pub withdraw_discriminator: [u8; 8],
+}
+
+// Bounds on campaign parameters accepted by initialize_campaign
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct CampaignLimits {
+    pub min_duration_secs: i64,
+    pub max_duration_secs: i64,
+    pub min_target_amount: u64,
+    pub max_target_amount: u64,
+    // Campaigns one creator may run at once (0 for no cap)
+    pub max_active_per_creator: u16,
+    // Wait after one of a creator's campaigns fails before they can launch again
+    pub failure_cooldown_secs: i64,
+}
+
+impl CampaignLimits {
+    fn validate(&self) -> Result<()> {
+        require!(
+            self.min_duration_secs > 0 && self.min_duration_secs <= self.max_duration_secs,
+            ErrorCode::InvalidCampaignLimits
+        );
+        require!(
+            self.min_target_amount > 0 && self.min_target_amount <= self.max_target_amount,
+            ErrorCode::InvalidCampaignLimits
+        );
+        require!(
+            self.failure_cooldown_secs >= 0,
+            ErrorCode::InvalidCampaignLimits
+        );
+        Ok(())
+    }
+}
+
+// Integrator registration keyed by the hash of its domain
+#[account]
+#[derive(InitSpace)]
+pub struct FrontendConfig {
+    pub authority: Pubkey,
+    pub domain_hash: [u8; 32],
+    pub payout: Pubkey,
+    pub bump: u8,
+}
+
+impl Campaign {
+    // Report why a campaign can't take part in trading rather than a bare "not active"
+    // Price-seconds accumulated up to `now`, counting the last trade's price since it was set
+    pub fn cumulative_price_at(&self, now: i64) -> Result<u128> {
+        let elapsed = now.saturating_sub(self.last_trade_at).max(0) as u128;
+        self.price_cumulative
+            .checked_add(self.last_trade_price as u128 * elapsed)
+            .ok_or(ErrorCode::MathOverflow.into())
+    }
+
+    // Roll the accumulator forward to a trade and start counting its price
+    fn accumulate_price(&mut self, now: i64, price: u64) -> Result<u128> {
+        self.price_cumulative = self.cumulative_price_at(now)?;
+        self.last_trade_price = price;
+        self.last_trade_at = now;
+        Ok(self.price_cumulative)
+    }
+
+    // Count tokens against their bucket so no pool mints into another's share
+    pub fn draw_supply(&mut self, bucket: SupplyBucket, amount: u64) -> Result<()> {
+        let (size, minted) = match bucket {
+            SupplyBucket::Curve => (self.curve_supply, &mut self.curve_minted),
+            SupplyBucket::Creator => (self.creator_allocation, &mut self.creator_minted),
+            SupplyBucket::Liquidity => (self.liquidity_reserve, &mut self.liquidity_minted),
+            SupplyBucket::Airdrop => (self.airdrop_reserve, &mut self.airdrop_minted),
+        };
+        let total = minted.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        require!(total <= size, ErrorCode::SupplyBucketExhausted);
+        *minted = total;
+        Ok(())
+    }
+
+    // Curve tokens burned back into the campaign can be minted again, so
+    // trading in and out doesn't use the bucket up. Saturates because holders
+    // can also burn tokens the curve never minted.
+    pub fn release_supply(&mut self, bucket: SupplyBucket, amount: u64) {
+        let minted = match bucket {
+            SupplyBucket::Curve => &mut self.curve_minted,
+            SupplyBucket::Creator => &mut self.creator_minted,
+            SupplyBucket::Liquidity => &mut self.liquidity_minted,
+            SupplyBucket::Airdrop => &mut self.airdrop_minted,
+        };
+        *minted = minted.saturating_sub(amount);
+    }
+
+    // Tokens a queued deposit converts to at the opening block's rate. The
+    // queue as a whole never gets more than the block buys, as cancelled
+    // deposits and fees only leave part of it converting.
+    pub fn queued_tokens(&self, amount: u64) -> Result<u64> {
+        if self.queue_sol == 0 {
+            return Ok(0);
+        }
+        u64::try_from(amount as u128 * self.queue_tokens as u128 / self.queue_sol as u128)
+            .map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    // Spread an exit fee over the tokens staked now, or hold it for the next
+    // stake when there are none
+    fn accrue_exit_fee(&mut self, amount: u64) -> Result<()> {
+        self.exit_rewards_pool = self
+            .exit_rewards_pool
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if self.total_staked == 0 {
+            self.exit_rewards_held = self
+                .exit_rewards_held
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+            return Ok(());
+        }
+        self.exit_rewards_per_token_q64 = self
+            .exit_rewards_per_token_q64
+            .checked_add(((amount as u128) << 64) / self.total_staked as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    // Call once a stake has been added to total_staked, after its position settled
+    fn release_held_exit_rewards(&mut self) -> Result<()> {
+        let held = std::mem::take(&mut self.exit_rewards_held);
+        if held == 0 {
+            return Ok(());
+        }
+        self.exit_rewards_pool -= held;
+        self.accrue_exit_fee(held)
+    }
+
+    pub fn unminted(&self, bucket: SupplyBucket) -> u64 {
+        match bucket {
+            SupplyBucket::Curve => self.curve_supply - self.curve_minted,
+            SupplyBucket::Creator => self.creator_allocation - self.creator_minted,
+            SupplyBucket::Liquidity => self.liquidity_reserve - self.liquidity_minted,
+            SupplyBucket::Airdrop => self.airdrop_reserve - self.airdrop_minted,
+        }
+    }
+
+    // The creator can do anything a role allows; co-creators need the role granted
+    pub fn authorize(&self, signer: Pubkey, role: u8) -> Result<()> {
+        if self.governance != Pubkey::default() {
+            require_keys_eq!(
+                signer,
+                self.governance,
+                ErrorCode::AuthorityHeldByGovernance
+            );
+            return Ok(());
+        }
+        if signer == self.creator {
+            return Ok(());
+        }
+        let member = self.co_creators[..self.co_creator_count as usize]
+            .iter()
+            .find(|c| c.key == signer)
+            .ok_or(ErrorCode::NotCampaignCreator)?;
+        require!(member.roles & role == role, ErrorCode::MissingCreatorRole);
+        Ok(())
+    }
+
+    // Campaigns only ever leave Active, once, for Graduated or Failed, and
+    // only reach Active from review
+    pub fn transition(&mut self, next: CampaignStatus) -> Result<()> {
+        let legal = match (self.status, next) {
+            (current, next) if current == next => true,
+            (CampaignStatus::PendingReview, CampaignStatus::Active) => true,
+            (CampaignStatus::Active, CampaignStatus::Graduated | CampaignStatus::Failed) => true,
+            _ => false,
+        };
+        require!(legal, ErrorCode::IllegalStatusTransition);
+        self.status = next;
+        Ok(())
+    }
+
+    pub fn is_team_member(&self, key: Pubkey) -> bool {
+        key == self.creator
+            || self.co_creators[..self.co_creator_count as usize]
+                .iter()
+                .any(|c| c.key == key)
+    }
+
+    // The team can't sell into its own raise, and only sells afterwards once
+    // the post-graduation lockup has run
+    pub fn check_team_embargo(&self, seller: Pubkey, now: i64) -> Result<()> {
+        if !self.is_team_member(seller) {
+            return Ok(());
+        }
+        require!(
+            self.status == CampaignStatus::Graduated
+                && now >= self.graduated_at.saturating_add(self.team_lockup_secs),
+            ErrorCode::TeamSellEmbargo
+        );
+        Ok(())
+    }
+
+    pub fn qualifies_for_milestones(&self, basis: u64) -> bool {
+        basis > 0 && basis >= self.milestone_min_contribution
+    }
+
+    pub fn ensure_active(&self) -> Result<()> {
+        require!(!self.paused, ErrorCode::CampaignPaused);
+        match self.status {
+            CampaignStatus::Active => Ok(()),
+            CampaignStatus::PendingReview => err!(ErrorCode::CampaignPendingReview),
+            CampaignStatus::Graduated => err!(ErrorCode::CampaignAlreadyGraduated),
+            CampaignStatus::Failed => err!(ErrorCode::CampaignFailed),
+        }
+    }
+
+    // Awaiting review or live, never funded, and created long enough ago to reclaim
+    pub fn is_stale(&self, now: i64) -> bool {
+        matches!(
+            self.status,
+            CampaignStatus::PendingReview | CampaignStatus::Active
+        ) && self.raised_amount == 0
+            && self.contributor_count == 0
+            && now >= self.created_at.saturating_add(STALE_CAMPAIGN_SECS)
+    }
+
+    // How much of a purchase still fits under the hard cap
+    pub fn cap_room(&self, amount: u64) -> u64 {
+        if self.hard_cap == 0 {
+            amount
+        } else {
+            amount.min(self.hard_cap.saturating_sub(self.raised_amount))
+        }
+    }
+
+    // Most the creator may have withdrawn in total: the primary target until the
+    // first stretch goal is reached, then up to the latest goal reached. Lifted
+    // at graduation, and never applies without stretch goals.
+    pub fn withdrawal_cap(&self) -> u64 {
+        if self.stretch_goal_count == 0 || self.status == CampaignStatus::Graduated {
+            return u64::MAX;
+        }
+        match self.stretch_goals_reached {
+            0 => self.target_amount,
+            reached => self.stretch_goals[reached as usize - 1],
+        }
+    }
+
+    pub fn needs_withdrawal_approval(&self, amount: u64) -> bool {
+        self.withdrawal_approval_threshold > 0
+            && self.unapproved_withdrawn.saturating_add(amount) > self.withdrawal_approval_threshold
+    }
+
+    // Failure is settled lazily: an active campaign that ended below target has failed
+    pub fn has_failed(&self, now: i64) -> bool {
+        match self.status {
+            CampaignStatus::Failed => true,
+            CampaignStatus::Active => {
+                now >= self.end_time && self.raised_amount < self.target_amount
+            }
+            CampaignStatus::PendingReview | CampaignStatus::Graduated => false,
+        }
+    }
+
+    // Split incoming SOL between the curve reserve and the creator's share
+    fn credit_balances(&mut self, amount: u64) -> Result<()> {
+        let reserve_share = calculate_bps_share(amount, self.curve_reserve_bps)?;
+        self.reserve_balance = self
+            .reserve_balance
+            .checked_add(reserve_share)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.withdrawable_balance = self
+            .withdrawable_balance
+            .checked_add(amount - reserve_share)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    // SOL paid back to holders comes out of the reserve before the creator's share
+    fn debit_balances(&mut self, amount: u64) {
+        let from_reserve = amount.min(self.reserve_balance);
+        self.reserve_balance -= from_reserve;
+        self.withdrawable_balance = self
+            .withdrawable_balance
+            .saturating_sub(amount - from_reserve);
+    }
+}
+
+// Share of supply set aside for holders of another mint, claimable by merkle proof
+#[account]
+#[derive(InitSpace)]
+pub struct Airdrop {
+    pub campaign: Pubkey,
+    pub source_mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AirdropClaim {
+    pub airdrop: Pubkey,
+    pub claimant: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+// Escrowed LP position for a graduated campaign, public so third parties can verify it
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidityLock {
+    pub campaign: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lp_vault: Pubkey,
+    pub amount: u64,
+    pub lock_duration: i64,
+    pub locked_at: i64,
+    pub unlocks_at: i64,
+    // Token-holder weight for and against releasing the LP once the lock expires
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub unlocked: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidityUnlockVote {
+    pub lock: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub approve: bool,
+    pub bump: u8,
+}
+
+// Sponsor escrow that tops up each contribution by `ratio_bps` until `cap` or `expiry`
+#[account]
+#[derive(InitSpace)]
+pub struct MatchCommitment {
+    pub campaign: Pubkey,
+    pub sponsor: Pubkey,
+    pub ratio_bps: u16,
+    pub cap: u64,
+    pub matched: u64,
+    // Sponsor's ledger: what the matches added to raised_amount, refunded to
+    // the sponsor rather than the backers if the campaign fails
+    pub basis: u64,
+    pub expiry: i64,
+    pub bump: u8,
+}
+
+// A ranked campaign or backer and its score
+#[zero_copy]
+#[derive(Default)]
+pub struct LeaderboardEntry {
+    pub key: Pubkey,
+    pub value: u64,
+}
+
+// Platform-wide top campaigns, refreshed by whichever contributions pass it in
+#[account(zero_copy)]
+pub struct Leaderboard {
+    pub by_raised: [LeaderboardEntry; LEADERBOARD_LEN],
+    pub by_contributors: [LeaderboardEntry; LEADERBOARD_LEN],
+    pub bump: u8,
+    pub _padding: [u8; 7],
+}
+
+impl Leaderboard {
+    pub fn record(&mut self, campaign: Pubkey, raised_amount: u64, contributor_count: u32) {
+        rank_entry(&mut self.by_raised, campaign, raised_amount);
+        rank_entry(
+            &mut self.by_contributors,
+            campaign,
+            contributor_count as u64,
+        );
+    }
+}
+
+// Entries stay sorted by value, highest first, with empty slots at the end
+fn rank_entry(entries: &mut [LeaderboardEntry; LEADERBOARD_LEN], key: Pubkey, value: u64) {
+    let slot = match entries.iter().position(|entry| entry.key == key) {
+        Some(index) => index,
+        None if value > entries[LEADERBOARD_LEN - 1].value => LEADERBOARD_LEN - 1,
+        None => return,
+    };
+    entries[slot] = LeaderboardEntry { key, value };
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.value));
+}
+
+// Uniqueness marker for a token symbol, keyed by the hash of its uppercase form
+#[account]
+#[derive(InitSpace)]
+pub struct SymbolIndex {
+    pub campaign: Pubkey,
+    pub bump: u8,
+}
+
+// Trusted cross-chain sender and the rate bridged USDC is credited at
+#[account]
+#[derive(InitSpace)]
+pub struct WormholeConfig {
+    pub core_bridge: Pubkey,
+    // Token bridge whose redemptions credit contributions; the config is the redeemer
+    pub token_bridge: Pubkey,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub usdc_mint: Pubkey,
+    pub custody: Pubkey,
+    // Lamports credited per whole bridged USDC
+    pub lamports_per_usdc: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct WormholeReceived {
+    pub emitter_chain: u16,
+    pub sequence: u64,
+    pub bump: u8,
+}
+
+// Campaign-funded token rebates for contributors who keep holding
+#[account]
+#[derive(InitSpace)]
+pub struct RebatePool {
+    pub campaign: Pubkey,
+    pub vault: Pubkey,
+    pub min_holding_secs: i64,
+    // Tokens rebated per whole SOL of platform fees paid
+    pub tokens_per_sol: u64,
+    pub distributed: u64,
+    pub bump: u8,
+}
+
+// Return data of get_campaign_summary
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CampaignSummary {
+    pub status: CampaignStatus,
+    pub raised_amount: u64,
+    pub target_amount: u64,
+    pub current_price: u64,
+    pub supply_minted: u64,
+    pub time_remaining: i64,
+    pub platform_fee_bps: u16,
+    pub frontend_fee_share_bps: u16,
+    pub reserve_balance: u64,
+    pub withdrawable_balance: u64,
+}
+
+// Return data of get_position
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct PositionStatement {
+    pub contributor: Pubkey,
+    // Balance of the contributor's associated token account
+    pub tokens_held: u64,
+    pub tokens_frozen: bool,
+    pub tokens_staked: u64,
+    // Curve tokens the campaign minted to this contributor and still on the record
+    pub tokens_received: u64,
+    pub cost_basis: u64,
+    pub avg_entry_price: u64,
+    // Seconds until the campaign's minimum holding period lets the position sell
+    pub hold_remaining_secs: i64,
+    pub claimable_rewards: u64,
+    pub refund_eligible: bool,
+    // Full refund including the refund buffer share, when eligible
+    pub refund_amount: u64,
+}
+
+// Fee charged on the part of a campaign's cumulative raise at or above `threshold`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct FeeTier {
+    pub threshold: u64,
+    pub fee_bps: u16,
+}
+
+impl GlobalConfig {
+    pub fn check_denylist_authority(&self, signer: Pubkey) -> Result<()> {
+        require!(
+            signer == self.admin
+                || (self.denylist_authority != Pubkey::default()
+                    && signer == self.denylist_authority),
+            ErrorCode::Unauthorized
+        );
+        Ok(())
+    }
+
+    fn set_fee_tiers(&mut self, tiers: &[FeeTier]) -> Result<()> {
+        require!(
+            !tiers.is_empty() && tiers.len() <= MAX_FEE_TIERS && tiers[0].threshold == 0,
+            ErrorCode::InvalidFeeTiers
+        );
+        for (i, tier) in tiers.iter().enumerate() {
+            require!(tier.fee_bps <= MAX_PLATFORM_FEE_BPS, ErrorCode::FeeTooHigh);
+            require!(
+                i == 0 || tier.threshold > tiers[i - 1].threshold,
+                ErrorCode::InvalidFeeTiers
+            );
+        }
+
+        self.fee_tiers = [FeeTier::default(); MAX_FEE_TIERS];
+        self.fee_tiers[..tiers.len()].copy_from_slice(tiers);
+        self.fee_tier_count = tiers.len() as u8;
+        Ok(())
+    }
+
+    fn active_fee_tiers(&self) -> &[FeeTier] {
+        &self.fee_tiers[..self.fee_tier_count as usize]
+    }
+
+    // Marginal rate for the next lamport raised
+    pub fn fee_bps_at(&self, raised: u64) -> u16 {
+        self.active_fee_tiers()
+            .iter()
+            .rev()
+            .find(|tier| raised >= tier.threshold)
+            .map_or(0, |tier| tier.fee_bps)
+    }
+
+    // Fee on raising `amount` more once `raised` is already in, split across tiers
+    pub fn tiered_fee(&self, raised: u64, amount: u64) -> Result<u64> {
+        let end = raised.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        let tiers = self.active_fee_tiers();
+        let mut fee: u64 = 0;
+        for (i, tier) in tiers.iter().enumerate() {
+            let tier_end = tiers.get(i + 1).map_or(u64::MAX, |next| next.threshold);
+            let from = raised.max(tier.threshold);
+            let to = end.min(tier_end);
+            if to > from {
+                fee = fee
+                    .checked_add(calculate_bps_share(to - from, tier.fee_bps)?)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+        Ok(fee)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum BadgeKind {
+    FirstContribution,
+    TopBacker,
+    // Held through to graduation without selling
+    Graduate,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct BadgeClaim {
+    pub campaign: Pubkey,
+    pub holder: Pubkey,
+    pub kind: BadgeKind,
+    pub bump: u8,
+}
+
+// What a contribution did, returned by simulate_contribute
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ContributionOutcome {
+    // SOL paid into the campaign after the platform fee
+    pub sol_amount: u64,
+    pub fee_amount: u64,
+    pub matched_amount: u64,
+    // Zero for uniform-price raises, which issue tickets instead
+    pub token_amount: u64,
+    pub new_total: u64,
+    pub new_price: u64,
+}
+
+// Where an attestor says a wallet's owner resides, as a bit index into campaign bitmaps
+#[account]
+#[derive(InitSpace)]
+pub struct RegionAttestation {
+    pub holder: Pubkey,
+    pub region: u8,
+    pub attested_at: i64,
+    pub bump: u8,
+}
+
+// Per-creator bookkeeping for the platform's launch policy
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorProfile {
+    pub creator: Pubkey,
+    pub active_campaigns: u16,
+    pub total_campaigns: u32,
+    // End time of the creator's most recent failed campaign
+    pub last_failure_at: i64,
+    // Verified .sol domain or DID, shown alongside the creator's campaigns
+    pub identity_kind: IdentityKind,
+    #[max_len(64)]
+    pub identity_handle: String,
+    pub identity_linked_at: i64,
+    // Highest DID attestation nonce linked so far
+    pub identity_nonce: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum IdentityKind {
+    None,
+    SnsDomain,
+    Did,
+}
+
+// One creator withdrawal, numbered in order so disputes can cite it. The
+// memo itself lives off chain; only its hash is committed here.
+#[account]
+#[derive(InitSpace)]
+pub struct WithdrawalReceipt {
+    pub campaign: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    pub purpose: WithdrawalPurpose,
+    pub memo_hash: [u8; 32],
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+// One user's tickets inside an exchange's batch claim
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ExchangeDeposit {
+    pub user: Pubkey,
+    pub ticket_amount: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ExchangeClaim {
+    pub campaign: Pubkey,
+    pub exchange: Pubkey,
+    pub batch_id: u64,
+    pub claims_root: [u8; 32],
+    pub deposit_count: u32,
+    pub ticket_amount: u64,
+    pub token_amount: u64,
+    pub bump: u8,
+}
+
+// A queued exit, paid out in order once the campaign is unpaused
+#[account]
+#[derive(InitSpace)]
+pub struct ExitRequest {
+    pub campaign: Pubkey,
+    pub holder: Pubkey,
+    pub index: u64,
+    pub token_amount: u64,
+    pub sol_owed: u64,
+    pub requested_at: i64,
+    pub bump: u8,
+}
+
+// SOL escrowed before launch, converted in index order by process_pre_deposit
+#[account]
+#[derive(InitSpace)]
+pub struct QueuedDeposit {
+    pub campaign: Pubkey,
+    pub depositor: Pubkey,
+    pub index: u64,
+    // Net of the platform fee; zeroed if the deposit is cancelled
+    pub amount: u64,
+    pub fee_paid: u64,
+    // Rent for the token account and contribution record the crank creates,
+    // paid back to the cranker as far as they were needed. The rest goes back
+    // to the depositor with the deposit's own rent.
+    pub rent_allowance: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct CoCreator {
+    pub key: Pubkey,
+    // ROLE_* bits
+    pub roles: u8,
+}
+
+// Protocol-wide reserve filled from platform fees and paid out to
+// contributors of campaigns governance has ruled fraudulent
+#[account]
+#[derive(InitSpace)]
+pub struct InsurancePool {
+    pub accrued: u64,
+    // Set aside for rulings but not yet claimed
+    pub reserved: u64,
+    pub paid_out: u64,
+    pub bump: u8,
+}
+
+impl InsurancePool {
+    pub fn available(&self) -> Result<u64> {
+        self.accrued
+            .checked_sub(self.reserved)
+            .and_then(|left| left.checked_sub(self.paid_out))
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct FraudRuling {
+    pub campaign: Pubkey,
+    pub claims_root: [u8; 32],
+    pub total_basis: u64,
+    pub claimed_basis: u64,
+    pub compensation: u64,
+    pub paid_out: u64,
+    pub ruled_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct InsuranceClaim {
+    pub ruling: Pubkey,
+    pub claimant: Pubkey,
+    pub basis: u64,
+    pub payout: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct UnsoldDistribution {
+    pub liquidity_bps: u16,
+    pub treasury_bps: u16,
+    pub burn_bps: u16,
+    pub treasury_vesting_secs: i64,
+}
+
+impl UnsoldDistribution {
+    pub fn is_configured(&self) -> bool {
+        self.liquidity_bps > 0 || self.treasury_bps > 0 || self.burn_bps > 0
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !self.is_configured() {
+            return Ok(());
+        }
+        let total = self.liquidity_bps as u64 + self.treasury_bps as u64 + self.burn_bps as u64;
+        require!(total == BPS_DENOMINATOR, ErrorCode::InvalidDistribution);
+        require!(self.treasury_vesting_secs >= 0, ErrorCode::InvalidLockup);
+        Ok(())
+    }
+}
+
+// How many tokens the curve issues per SOL. The early bonus starts at
+// bonus_bps on top of the base rate and shrinks linearly to nothing by the
+// time bonus_until lamports have been raised.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct CurveConfig {
+    // Whole tokens per SOL before the bonus
+    pub tokens_per_sol: u64,
+    pub bonus_bps: u16,
+    pub bonus_until: u64,
+}
+
+impl Default for CurveConfig {
+    // 1,000 tokens per SOL, with a 20% bonus tapering off over the first 10 SOL
+    fn default() -> Self {
+        Self {
+            tokens_per_sol: 1_000,
+            bonus_bps: 2_000,
+            bonus_until: 10 * LAMPORTS_PER_SOL,
+        }
+    }
+}
+
+impl CurveConfig {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.tokens_per_sol > 0
+                && self.bonus_bps as u64 <= BPS_DENOMINATOR
+                && (self.bonus_bps == 0 || self.bonus_until > 0),
+            ErrorCode::InvalidCurveConfig
+        );
+        // The opening rate is the highest the curve offers, so if it fits they all
+        // do, and its price is the lowest, which still has to be a whole lamport
+        curve_rate_q64(self, 0).map_err(|_| ErrorCode::InvalidCurveConfig)?;
+        require!(
+            calculate_token_price(self, 0).map_err(|_| ErrorCode::InvalidCurveConfig)? > 0,
+            ErrorCode::InvalidCurveConfig
+        );
+        Ok(())
+    }
+}
+
+// Sign-offs collected for one withdrawal, keyed by its receipt index
+#[account]
+#[derive(InitSpace)]
+pub struct WithdrawalApproval {
+    pub campaign: Pubkey,
+    pub withdrawal_index: u64,
+    pub amount: u64,
+    // Top backers at the first approval; bit n of the mask is required[n]
+    pub required: [Pubkey; WITHDRAWAL_APPROVERS],
+    pub required_count: u8,
+    pub approved_mask: u8,
+    pub bump: u8,
+}
+
+impl WithdrawalApproval {
+    pub fn is_complete(&self) -> bool {
+        self.required_count > 0 && self.approved_mask == (1u8 << self.required_count) - 1
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakePosition {
+    pub campaign: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    // Last time the position grew
+    pub staked_at: i64,
+    // Campaign exit-fee accumulator as of the last settlement
+    pub rewards_checkpoint_q64: u128,
+    pub rewards_owed: u64,
+    pub bump: u8,
+}
+
+impl StakePosition {
+    // Credits the exit fees accrued on the current stake since the last checkpoint
+    pub fn settle(&mut self, rewards_per_token_q64: u128) -> Result<()> {
+        let accrued = (self.amount as u128)
+            .checked_mul(rewards_per_token_q64 - self.rewards_checkpoint_q64)
+            .ok_or(ErrorCode::MathOverflow)?
+            >> 64;
+        self.rewards_owed = self
+            .rewards_owed
+            .checked_add(accrued as u64)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.rewards_checkpoint_q64 = rewards_per_token_q64;
+        Ok(())
+    }
+
+    pub fn add(&mut self, amount: u64) -> Result<()> {
+        self.amount = self
+            .amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.staked_at = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CampaignAttestation {
+    pub campaign: Pubkey,
+    pub oracle: Pubkey,
+    pub kind: u16,
+    pub data_hash: [u8; 32],
+    pub nonce: u64,
+    pub posted_at: i64,
+    pub bump: u8,
+}
+
+// Tip budget and rate limit for a campaign's price crank
+#[account]
+#[derive(InitSpace)]
+pub struct PriceCrank {
+    pub campaign: Pubkey,
+    pub last_slot: u64,
+    pub crank_count: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchCalendar {
+    pub window_secs: i64,
+    pub capacity_per_window: u8,
+    pub bump: u8,
+}
+
+// Bookings for one launch calendar window
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchSlot {
+    pub window_index: u64,
+    pub booked: u8,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct GatedContent {
+    pub campaign: Pubkey,
+    pub index: u64,
+    pub payload_hash: [u8; 32],
+    pub key_epoch: u64,
+    pub posted_at: i64,
+    pub bump: u8,
+}
+
+// A backer's public encryption key (e.g. x25519) for gated content
+#[account]
+#[derive(InitSpace)]
+pub struct ContentKeyRegistration {
+    pub campaign: Pubkey,
+    pub backer: Pubkey,
+    pub encryption_key: [u8; 32],
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ClosingAuction {
+    pub campaign: Pubkey,
+    // Tokens on offer, sold as one lot
+    pub supply: u64,
+    // Lamports per whole token
+    pub reserve_price: u64,
+    pub bid_end: i64,
+    pub reveal_end: i64,
+    pub best_bidder: Pubkey,
+    pub best_price: u64,
+    pub settled: bool,
+    pub bump: u8,
+}
+
+impl ClosingAuction {
+    // Past this point the winner no longer has to be paid out
+    pub fn settle_expired(&self, now: i64) -> bool {
+        now >= self.reveal_end.saturating_add(CLOSING_AUCTION_SETTLE_SECS)
+    }
+
+    // What the whole lot costs at a price per whole token
+    pub fn lot_cost(&self, price: u64) -> Result<u64> {
+        u64::try_from(
+            (self.supply as u128)
+                .checked_mul(price as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / TOKEN_PRECISION,
+        )
+        .map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ClosingBid {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub commitment: [u8; 32],
+    // Lamports held in this account towards the bid
+    pub deposit: u64,
+    // Zero until revealed
+    pub price: u64,
+    pub bump: u8,
+}
+
+// One wallet's positions across every campaign, for a portfolio view in a single fetch
+#[account]
+#[derive(InitSpace)]
+pub struct BackerProfile {
+    pub wallet: Pubkey,
+    // Campaigns ever backed
+    pub campaign_count: u32,
+    // Positions with cost basis still in them
+    pub active_positions: u32,
+    // Campaigns the wallet has tokens staked in
+    pub staked_positions: u32,
+    // Lamports paid in, fees included
+    pub total_contributed: u64,
+    // Sale and refund proceeds less the cost basis they closed out
+    pub realized_pnl: i64,
+    pub bump: u8,
+}
+
+impl BackerProfile {
+    pub fn record_contribution(
+        &mut self,
+        first_contribution: bool,
+        opens_position: bool,
+        paid: u64,
+    ) -> Result<()> {
+        if first_contribution {
+            self.campaign_count = self.campaign_count.saturating_add(1);
+        }
+        if opens_position {
+            self.active_positions = self.active_positions.saturating_add(1);
+        }
+        self.total_contributed = self
+            .total_contributed
+            .checked_add(paid)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_stake(&mut self, opens_stake: bool, closes_stake: bool) {
+        if opens_stake {
+            self.staked_positions = self.staked_positions.saturating_add(1);
+        }
+        if closes_stake {
+            self.staked_positions = self.staked_positions.saturating_sub(1);
+        }
+    }
+
+    pub fn record_exit(&mut self, basis: u64, proceeds: u64, closes_position: bool) -> Result<()> {
+        let pnl = proceeds as i128 - basis as i128;
+        self.realized_pnl =
+            i64::try_from(self.realized_pnl as i128 + pnl).map_err(|_| ErrorCode::MathOverflow)?;
+        if closes_position {
+            self.active_positions = self.active_positions.saturating_sub(1);
+        }
+        Ok(())
+    }
+}
+
+// Present only while symbols and names with this skeleton are blocked
+#[account]
+#[derive(InitSpace)]
+pub struct BlockedName {
+    pub skeleton_hash: [u8; 32],
+    pub blocked_at: i64,
+    pub bump: u8,
+}
+
+// Present only while the address is listed
+#[account]
+#[derive(InitSpace)]
+pub struct DenylistEntry {
+    pub address: Pubkey,
+    pub reason_hash: [u8; 32],
+    pub listed_at: i64,
+    pub bump: u8,
+}
+
+// Distributor for revenue an outside source shares with a campaign's holders
+#[account]
+#[derive(InitSpace)]
+pub struct RevenueStream {
+    pub campaign: Pubkey,
+    pub source: Pubkey,
+    // Mint revenue is paid in and the vault holding it; both the default key
+    // for a SOL stream, whose revenue sits in this account
+    pub revenue_mint: Pubkey,
+    pub vault: Pubkey,
+    // Deposited since the last snapshot
+    pub pending: u64,
+    pub total_deposited: u64,
+    pub total_claimed: u64,
+    pub epoch_count: u64,
+    pub bump: u8,
+}
+
+// One snapshot's worth of revenue, shared pro rata by snapshotted balance
+#[account]
+#[derive(InitSpace)]
+pub struct RevenueEpoch {
+    pub stream: Pubkey,
+    pub index: u64,
+    pub holders_root: [u8; 32],
+    pub total_balance: u64,
+    pub amount: u64,
+    pub claimed: u64,
+    pub published_at: i64,
+    // Voided in its challenge window, or swept once its claim window ended
+    pub closed: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RevenueClaim {
+    pub epoch: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+// Share of a graduated raise paid out to the creator by the second. Holds the
+// unclaimed lamports itself; paused time pushes the end of the schedule back.
+#[account]
+#[derive(InitSpace)]
+pub struct SalaryStream {
+    pub campaign: Pubkey,
+    // Position among the campaign's streams, part of the seeds
+    pub index: u64,
+    pub recipient: Pubkey,
+    pub total: u64,
+    pub claimed: u64,
+    pub start_time: i64,
+    pub duration_secs: i64,
+    // When the current pause began (0 while running)
+    pub paused_at: i64,
+    // Time spent paused in earlier, finished pauses
+    pub paused_secs: i64,
+    pub bump: u8,
+}
+
+impl SalaryStream {
+    pub fn accrued(&self, now: i64) -> Result<u64> {
+        let until = if self.paused_at != 0 {
+            self.paused_at
+        } else {
+            now
+        };
+        let elapsed = until
+            .saturating_sub(self.start_time)
+            .saturating_sub(self.paused_secs)
+            .clamp(0, self.duration_secs);
+        u64::try_from(self.total as u128 * elapsed as u128 / self.duration_secs as u128)
+            .map_err(|_| ErrorCode::MathOverflow.into())
+    }
+}
+
+// The curve price in the layout of a Pyth push-oracle price update, so lending
+// protocols and aggregators can read launch tokens with decoders they already
+// have. Everything from write_authority to posted_slot lines up with Pyth's
+// PriceUpdateV2; only the discriminator and owner differ, and consumers
+// allowlist feeds by address anyway.
+#[account]
+#[derive(InitSpace)]
+pub struct PriceFeed {
+    // The campaign PDA, the only thing that updates the feed
+    pub write_authority: Pubkey,
+    pub verification_level: u8,
+    // The campaign's address
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    // The curve quotes an exact price, so confidence is always zero
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub prev_publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+    pub posted_slot: u64,
+    pub campaign: Pubkey,
+    pub bump: u8,
+}
+
+impl PriceFeed {
+    pub fn publish(&mut self, now: i64, slot: u64, price: u64) -> Result<()> {
+        let price = i64::try_from(price).map_err(|_| ErrorCode::MathOverflow)?;
+        // Round the step away from zero so the average reaches the price
+        // instead of stalling once the gap is under the weight
+        let gap = price - self.ema_price;
+        self.ema_price +=
+            (gap + gap.signum() * (PRICE_FEED_EMA_WEIGHT - 1)) / PRICE_FEED_EMA_WEIGHT;
+        self.prev_publish_time = self.publish_time;
+        self.publish_time = now;
+        self.price = price;
+        self.posted_slot = slot;
+        Ok(())
+    }
+}
+
+// A program allowed to route deposits into campaigns on its users' behalf
+#[account]
+#[derive(InitSpace)]
+pub struct DepositAdapter {
+    pub program: Pubkey,
+    // The program's DEPOSIT_ADAPTER_SIGNER_SEED PDA, which signs its deposits
+    pub signer: Pubkey,
+    #[max_len(32)]
+    pub name: String,
+    pub active: bool,
+    pub total_routed: u64,
+    pub deposit_count: u64,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+// What's left of a campaign once its accounts are gone: the outcome, the
+// headline numbers and hashes that pin the final state and trade history, so
+// an archived copy of either can be checked against the chain.
+#[account]
+#[derive(InitSpace)]
+pub struct CampaignArchive {
+    pub campaign: Pubkey,
+    pub creator: Pubkey,
+    pub token_mint: Pubkey,
+    pub name_hash: [u8; 32],
+    pub outcome: CampaignStatus,
+    pub target_amount: u64,
+    pub raised_amount: u64,
+    pub contributor_count: u32,
+    pub contribution_count: u64,
+    pub total_supply: u64,
+    pub created_at: i64,
+    pub end_time: i64,
+    pub graduated_at: i64,
+    // sha256 of the Borsh-encoded Campaign at archive time
+    pub state_hash: [u8; 32],
+    // sha256 of the price history account's data, discriminator included
+    pub history_hash: [u8; 32],
+    pub archived_at: i64,
+    pub archived_slot: u64,
+    pub bump: u8,
+}
+
+// One allowlist tier of a campaign. Leaves are sha256(wallet).
+#[account]
+#[derive(InitSpace)]
+pub struct AllowlistTier {
+    pub campaign: Pubkey,
+    pub index: u8,
+    pub merkle_root: [u8; 32],
+    pub starts_at: i64,
+    pub ends_at: i64,
+    // Most a wallet's position can reach through this tier (0 = uncapped)
+    pub per_wallet_cap: u64,
+    pub bonus_bps: u16,
+    pub total_contributed: u64,
+    pub contribution_count: u64,
+    pub bump: u8,
+}
+
+// One answer in a campaign's FAQ, keyed by the hash of its question
+#[account]
+#[derive(InitSpace)]
+pub struct FaqEntry {
+    pub campaign: Pubkey,
+    pub question_hash: [u8; 32],
+    pub answer_hash: [u8; 32],
+    // Where the question and answer text live
+    #[max_len(200)]
+    pub uri: String,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+#[event]
+pub struct ContributionEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub new_total: u64,
+    pub fee_amount: u64,
+    // Solana Pay reference the payment was tagged with, if any
+    pub reference: Option<Pubkey>,
+}
+
+#[event]
+pub struct SellEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub seller: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub new_total: u64,
+}
+
+#[event]
+pub struct RelayedContributionEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    pub relayer: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct WithdrawalEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct TokenPriceEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub price: u64,
+    pub raised_amount: u64,
+}
+
+#[event]
+pub struct GraduationEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub raised_amount: u64,
+    pub floor_reserve: u64,
+    pub floor_price: u64,
+}
+
+#[event]
+pub struct FloorRedemptionEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub holder: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub remaining_reserve: u64,
+}
+
+#[event]
+pub struct ExtensionProposedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub duration: i64,
+    pub voting_ends_at: i64,
+}
+
+#[event]
+pub struct ExtensionVoteEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub approve: bool,
+}
+
+#[event]
+pub struct ExtensionFinalizedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub approved: bool,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct VoteEscrowReleasedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FrontendRegisteredEvent {
+    pub schema_version: u8,
+    pub frontend: Pubkey,
+    pub authority: Pubkey,
+    pub domain_hash: [u8; 32],
+    pub payout: Pubkey,
+}
+
+#[event]
+pub struct FrontendFeeEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub frontend: Pubkey,
+    pub payout: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ThawEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub accounts_thawed: u32,
+}
+
+#[event]
+pub struct TicketRedeemedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub holder: Pubkey,
+    pub ticket_amount: u64,
+    pub token_amount: u64,
+}
+
+#[event]
+pub struct RefundEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    pub burned: u64,
+    pub sol_amount: u64,
+}
+
+#[event]
+pub struct AirdropConfiguredEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub source_mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+}
+
+#[event]
+pub struct AirdropClaimedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub claimant: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct LiquidityLockedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lp_vault: Pubkey,
+    pub amount: u64,
+    pub unlocks_at: i64,
+}
+
+#[event]
+pub struct LiquidityUnlockVoteEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub approve: bool,
+}
+
+#[event]
+pub struct LiquidityUnlockedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MatchRegisteredEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub sponsor: Pubkey,
+    pub ratio_bps: u16,
+    pub cap: u64,
+    pub expiry: i64,
+}
+
+#[event]
+pub struct MatchDrawnEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub sponsor: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct MatchClosedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub sponsor: Pubkey,
+    pub matched: u64,
+}
+
+#[event]
+pub struct SymbolReleasedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub symbol: String,
+}
+
+// Creator market-making buy, kept separate from ContributionEvent so
+// indexers can tell it apart from organic demand
+#[event]
+pub struct TreasuryBuyEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub creator: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub new_total: u64,
+    pub fee_amount: u64,
+}
+
+#[event]
+pub struct WormholeContributionEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub beneficiary: Pubkey,
+    pub emitter_chain: u16,
+    pub sequence: u64,
+    pub usdc_amount: u64,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub fee_amount: u64,
+}
+
+#[event]
+pub struct GraduationReadyEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub raised_amount: u64,
+    pub threshold: u64,
+}
+
+#[event]
+pub struct LoyaltyRebateEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub holder: Pubkey,
+    pub fees_rebated: u64,
+    pub token_amount: u64,
+}
+
+#[event]
+pub struct StablecoinWithdrawalEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub creator: Pubkey,
+    pub sol_amount: u64,
+    pub stable_amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct CampaignSnapshotEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub slot: u64,
+    pub chunk_index: u16,
+    pub chunk_count: u16,
+    pub data: Vec<u8>,
+}
+
+#[event]
+pub struct BadgeClaimedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub holder: Pubkey,
+    pub kind: BadgeKind,
+}
+
+#[event]
+pub struct RegionAttestedEvent {
+    pub schema_version: u8,
+    pub holder: Pubkey,
+    pub attestor: Pubkey,
+    pub region: u8,
+}
+
+#[event]
+pub struct LendingDepositEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub amount: u64,
+    pub lent_amount: u64,
+}
+
+#[event]
+pub struct LendingRecallEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub principal: u64,
+    pub returned: u64,
+    pub yield_amount: u64,
+    pub to_contributors: bool,
+}
+
+#[event]
+pub struct CurveFeeEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub trader: Pubkey,
+    pub is_buy: bool,
+    pub amount: u64,
+    pub retained_total: u64,
+}
+
+#[event]
+pub struct CompressedRecordsEnabledEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub max_depth: u32,
+    pub max_buffer_size: u32,
+}
+
+#[event]
+pub struct CompressedContributionEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    pub leaf_index: u64,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub new_total: u64,
+}
+
+#[event]
+pub struct CompressedRecordClaimedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    pub leaf_index: u64,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+}
+
+#[event]
+pub struct DeploymentVerifiedEvent {
+    pub schema_version: u8,
+    // Default when the authority has been renounced
+    pub upgrade_authority: Pubkey,
+    pub status: UpgradeAuthorityStatus,
+    pub verified_at: i64,
+}
+
+#[event]
+pub struct AdminTransferredToGovernanceEvent {
+    pub schema_version: u8,
+    pub previous_admin: Pubkey,
+    pub governance: Pubkey,
+    pub realm: Pubkey,
+    pub governance_program: Pubkey,
+}
+
+#[event]
+pub struct CampaignSettledEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub creator: Pubkey,
+    pub active_campaigns: u16,
+}
+
+#[event]
+pub struct WithdrawalReceiptEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    pub purpose: WithdrawalPurpose,
+    pub memo_hash: [u8; 32],
+}
+
+#[event]
+pub struct ExchangeClaimEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub exchange: Pubkey,
+    pub batch_id: u64,
+    pub claims_root: [u8; 32],
+    pub deposit_count: u32,
+    pub ticket_amount: u64,
+    pub token_amount: u64,
+}
+
+#[event]
+pub struct SecondaryTradeEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub trader: Pubkey,
+    pub is_buy: bool,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub fee: u64,
+    pub price: u64,
+}
+
+#[event]
+pub struct CampaignPausedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub paused: bool,
+    pub protected_raised: u64,
+}
+
+#[event]
+pub struct ExitRequestedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub holder: Pubkey,
+    pub index: u64,
+    pub token_amount: u64,
+    pub sol_owed: u64,
+}
+
+#[event]
+pub struct ExitProcessedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub holder: Pubkey,
+    pub index: u64,
+    pub sol_amount: u64,
+}
+
+#[event]
+pub struct PriceHistoryMigratedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub samples: u16,
+}
+
+#[event]
+pub struct RelayEscrowWithdrawnEvent {
+    pub schema_version: u8,
+    pub contributor: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct TwapEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub twap: u64,
+    // Seconds actually averaged over
+    pub window: i64,
+}
+
+#[event]
+pub struct PreDepositEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub depositor: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PreDepositCancelledEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub depositor: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PreDepositProcessedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub depositor: Pubkey,
+    pub index: u64,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+}
+
+#[event]
+pub struct CoCreatorUpdatedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub member: Pubkey,
+    pub roles: u8,
+}
+
+#[event]
+pub struct CampaignMetadataUpdatedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct ContributionsPausedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub paused: bool,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct CampaignUpdatePostedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub author: Pubkey,
+    pub index: u64,
+    pub content_hash: [u8; 32],
+}
+
+// Everything an accounting tool needs to rebuild and check a receipt hash
+#[event]
+pub struct ContributionReceiptEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    pub receipt_index: u64,
+    // Position of this contribution among all of the campaign's trades
+    pub sequence: u64,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub price: u64,
+    pub timestamp: i64,
+    pub receipt_hash: [u8; 32],
+}
+
+#[event]
+pub struct CampaignReviewedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub reviewer: Pubkey,
+    pub approved: bool,
+    // Hash of the reviewer's off-chain notes; zero on approval
+    pub reason_hash: [u8; 32],
+}
+
+#[event]
+pub struct TokensInvalidatedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub token_mint: Pubkey,
+    // Set on the crank call that first marks the token dead
+    pub first: bool,
+    pub accounts_frozen: u32,
+}
+
+#[event]
+pub struct BasketContributionEvent {
+    pub schema_version: u8,
+    pub contributor: Pubkey,
+    pub campaigns: Vec<Pubkey>,
+    pub total_amount: u64,
+    pub total_fee: u64,
+}
+
+#[event]
+pub struct FraudRulingEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub claims_root: [u8; 32],
+    pub total_basis: u64,
+    pub compensation: u64,
+}
+
+#[event]
+pub struct InsuranceClaimEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub claimant: Pubkey,
+    pub basis: u64,
+    pub payout: u64,
+}
+
+#[event]
+pub struct StaleCampaignExpiredEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub creator: Pubkey,
+    pub keeper: Pubkey,
+    pub keeper_tip: u64,
+}
+
+#[event]
+pub struct CampaignAuthorityTransferredEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub previous_authority: Pubkey,
+    pub governance: Pubkey,
+    pub realm: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EventSchemaEntry {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+}
+
+#[event]
+pub struct EventSchemaEvent {
+    pub schema_version: u8,
+    pub events: Vec<EventSchemaEntry>,
+}
+
+#[event]
+pub struct CurveDiscrepancyEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub expected_min: u64,
+    pub expected_max: u64,
+    pub actual_supply: u64,
+    // Positive when more tokens exist than the curve accounts for
+    pub discrepancy: i64,
+}
+
+#[event]
+pub struct DistributionFinalizedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub unsold: u64,
+    pub liquidity: u64,
+    pub treasury: u64,
+    pub burned: u64,
 }
 
 #[event]
-pub struct WithdrawalEvent {
+pub struct VestedTreasuryReleasedEvent {
+    pub schema_version: u8,
     pub campaign: Pubkey,
-    pub creator: Pubkey,
     pub amount: u64,
     pub remaining: u64,
 }
 
 #[event]
-pub struct TokenPriceEvent {
+pub struct StretchGoalReachedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub index: u8,
+    pub goal: u64,
+    pub raised_amount: u64,
+}
+
+#[event]
+pub struct WithdrawalProposedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub withdrawal_index: u64,
+    pub amount: u64,
+    // Top backers who have to sign off
+    pub approvers: Vec<Pubkey>,
+}
+
+#[event]
+pub struct WithdrawalProposalCancelledEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub withdrawal_index: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WithdrawalApprovedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub withdrawal_index: u64,
+    pub approver: Pubkey,
+    pub amount: u64,
+    pub complete: bool,
+}
+
+#[event]
+pub struct StakeEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub staked: u64,
+}
+
+#[event]
+pub struct UnstakeEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub staked: u64,
+    pub forfeited_rewards: u64,
+}
+
+#[event]
+pub struct AttestationPostedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub oracle: Pubkey,
+    pub kind: u16,
+    pub data_hash: [u8; 32],
+    pub nonce: u64,
+}
+
+#[event]
+pub struct ContributorMilestoneReachedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub index: u8,
+    pub milestone_backers: u32,
+}
+
+#[event]
+pub struct ContributorMilestoneLostEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub index: u8,
+    pub milestone_backers: u32,
+}
+
+#[event]
+pub struct ExitFeeCollectedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct StakingRewardsClaimedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LaunchSlotReservedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub window_index: u64,
+    pub window_start: i64,
+    pub booked: u8,
+}
+
+#[event]
+pub struct LaunchSlotReleasedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub window_index: u64,
+    pub booked: u8,
+}
+
+#[event]
+pub struct CampaignLaunchedEvent {
+    pub schema_version: u8,
     pub campaign: Pubkey,
+    pub window_index: u64,
+    pub launched_at: i64,
+}
+
+#[event]
+pub struct ExcessRefundedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ParentCampaignResolvedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub parent: Pubkey,
+    pub parent_succeeded: bool,
+}
+
+#[event]
+pub struct GatedContentPostedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub index: u64,
+    pub payload_hash: [u8; 32],
+    pub key_epoch: u64,
+}
+
+#[event]
+pub struct ContentKeyRegisteredEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub backer: Pubkey,
+    pub encryption_key: [u8; 32],
+}
+
+#[event]
+pub struct ContentKeyRevokedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub backer: Pubkey,
+    pub sol_contributed: u64,
+}
+
+#[event]
+pub struct ClosingAuctionOpenedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub supply: u64,
+    pub reserve_price: u64,
+    pub bid_end: i64,
+    pub reveal_end: i64,
+}
+
+#[event]
+pub struct ClosingBidPlacedEvent {
+    pub schema_version: u8,
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub deposit: u64,
+}
+
+#[event]
+pub struct ClosingBidRevealedEvent {
+    pub schema_version: u8,
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub price: u64,
+}
+
+#[event]
+pub struct ClosingAuctionSettledEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub winner: Pubkey,
     pub price: u64,
+    pub supply: u64,
+    pub proceeds: u64,
+}
+
+#[event]
+pub struct AddressDeniedEvent {
+    pub schema_version: u8,
+    pub address: Pubkey,
+    pub listed_by: Pubkey,
+    pub reason_hash: [u8; 32],
+}
+
+#[event]
+pub struct AddressAllowedEvent {
+    pub schema_version: u8,
+    pub address: Pubkey,
+    pub cleared_by: Pubkey,
+}
+
+#[event]
+pub struct RevenueStreamRegisteredEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub source: Pubkey,
+    pub revenue_mint: Pubkey,
+}
+
+#[event]
+pub struct RevenueDepositedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub amount: u64,
+    pub pending: u64,
+}
+
+#[event]
+pub struct RevenueSnapshotEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub epoch: u64,
+    pub amount: u64,
+    pub total_balance: u64,
+}
+
+#[event]
+pub struct RevenueEpochClosedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub epoch: u64,
+    // Revenue moved back to pending: the whole epoch when voided, what went unclaimed when swept
+    pub returned: u64,
+    pub voided: bool,
+}
+
+#[event]
+pub struct RevenueClaimedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub epoch: u64,
+    pub holder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SalaryStreamOpenedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub index: u64,
+    pub recipient: Pubkey,
+    pub total: u64,
+    pub start_time: i64,
+    pub duration_secs: i64,
+}
+
+#[event]
+pub struct SalaryClaimedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub index: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub claimed: u64,
+    pub total: u64,
+}
+
+#[event]
+pub struct SalaryStreamPausedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub index: u64,
+    pub paused: bool,
+    pub accrued: u64,
+}
+
+#[event]
+pub struct DonationReceiptMintedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub donor: Pubkey,
+    pub receipt_mint: Pubkey,
+    pub donated: u64,
+}
+
+#[event]
+pub struct RefundBatchCrankedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub cranker: Pubkey,
+    pub records: u32,
+    pub total_refunded: u64,
+    pub total_tips: u64,
+}
+
+#[event]
+pub struct CampaignCreatedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub creator: Pubkey,
+    pub target_amount: u64,
+    pub end_time: i64,
+    pub identity_kind: IdentityKind,
+    pub creator_handle: String,
+}
+
+#[event]
+pub struct IdentityLinkedEvent {
+    pub schema_version: u8,
+    pub creator: Pubkey,
+    pub identity_kind: IdentityKind,
+    pub handle: String,
+}
+
+#[event]
+pub struct IdentityUnlinkedEvent {
+    pub schema_version: u8,
+    pub creator: Pubkey,
+    pub handle: String,
+}
+
+#[event]
+pub struct ReservedAllocationMintedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub bucket: SupplyBucket,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct PriceFeedInitializedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub price_feed: Pubkey,
+    pub price: i64,
+    pub exponent: i32,
+}
+
+#[event]
+pub struct DepositAdapterRegisteredEvent {
+    pub schema_version: u8,
+    pub adapter: Pubkey,
+    pub program: Pubkey,
+    pub name: String,
+}
+
+#[event]
+pub struct DepositAdapterUpdatedEvent {
+    pub schema_version: u8,
+    pub adapter: Pubkey,
+    pub active: bool,
+}
+
+#[event]
+pub struct AdapterDepositEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub adapter: Pubkey,
+    pub beneficiary: Pubkey,
+    pub sol_amount: u64,
+    pub reference: [u8; 32],
+}
+
+#[event]
+pub struct CampaignArchivedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub archive: Pubkey,
+    pub outcome: CampaignStatus,
     pub raised_amount: u64,
+    pub contributor_count: u32,
+    pub state_hash: [u8; 32],
+    pub history_hash: [u8; 32],
+}
+
+#[event]
+pub struct AllowlistTierConfiguredEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub tier: u8,
+    pub merkle_root: [u8; 32],
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub per_wallet_cap: u64,
+    pub bonus_bps: u16,
+}
+
+#[event]
+pub struct AllowlistContributionEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub tier: u8,
+    pub contributor: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub bonus_bps: u16,
+}
+
+#[event]
+pub struct NameBlockedEvent {
+    pub schema_version: u8,
+    pub skeleton_hash: [u8; 32],
+    pub blocked: bool,
+}
+
+#[event]
+pub struct FaqEntryUpdatedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub question_hash: [u8; 32],
+    pub answer_hash: [u8; 32],
+    pub uri: String,
+    pub removed: bool,
+}
+
+#[event]
+pub struct ContributionSplitEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    // Everything minted, before the splits
+    pub token_amount: u64,
+    // Owners of the beneficiary token accounts, in the order given
+    pub beneficiaries: Vec<Pubkey>,
+    pub split_bps: Vec<u16>,
+    pub amounts: Vec<u64>,
+    // Cost basis moved onto each beneficiary's record with its tokens
+    pub basis_amounts: Vec<u64>,
+}
+
+#[event]
+pub struct TierBonusClaimedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MatchRefundedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub sponsor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct GlobalConfigUpdatedEvent {
+    pub schema_version: u8,
+    pub admin: Pubkey,
+    pub setting: String,
+}
+
+#[event]
+pub struct FrontendPayoutUpdatedEvent {
+    pub schema_version: u8,
+    pub frontend: Pubkey,
+    pub payout: Pubkey,
+}
+
+#[event]
+pub struct GovernanceTokensUnlockedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub holder_governance_account: Pubkey,
+}
+
+#[event]
+pub struct LeaderboardInitializedEvent {
+    pub schema_version: u8,
+    pub leaderboard: Pubkey,
+    pub payer: Pubkey,
+}
+
+#[event]
+pub struct RebatePoolConfiguredEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub min_holding_secs: i64,
+    pub tokens_per_sol: u64,
+    pub deposited: u64,
+}
+
+#[event]
+pub struct PriceCrankFundedEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ContentThresholdSetEvent {
+    pub schema_version: u8,
+    pub campaign: Pubkey,
+    pub threshold: u64,
+}
+
+#[event]
+pub struct ClosingBidReclaimedEvent {
+    pub schema_version: u8,
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub deposit: u64,
 }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Campaign is not active")]
-    CampaignNotActive,
-    #[msg("Invalid contribution amount")]
-    InvalidAmount,
-    #[msg("Unauthorized access")]
-    Unauthorized,
-    #[msg("Insufficient funds")]
-    InsufficientFunds,
-}
\ No newline at end of file
+#[event]
+pub struct BackerProfileInitializedEvent {
+    pub schema_version: u8,
+    pub wallet: Pubkey,
+}
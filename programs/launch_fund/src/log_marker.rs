@@ -0,0 +1,252 @@
+// Plain-text twin of every event, for relayers that watch logs but can't
+// decode Anchor's binary event data. Each event is also logged as one line:
+//
+//     LF1:contribution:{"schema_version":2,"campaign":"...","amount":1000000}
+//
+// The prefix only changes if the line format itself does, and the name is the
+// event's type without its Event suffix, in snake case. Fields appear in
+// declaration order under their Rust names. Pubkeys are base58, byte arrays hex,
+// u128 values strings so JavaScript parsers don't lose precision.
+use anchor_lang::prelude::*;
+use std::fmt::Write;
+
+use crate::{
+    BadgeKind, CampaignStatus, IdentityKind, SupplyBucket, UpgradeAuthorityStatus,
+    WithdrawalPurpose,
+};
+
+pub const LOG_MARKER_PREFIX: &str = "LF1";
+
+// Emit an event and log its marker line. Takes the same struct literal emit! does.
+macro_rules! emit_logged {
+    ($event:ident { $($field:ident $(: $value:expr)?),* $(,)? }) => {{
+        let event = $event { $($field $(: $value)?),* };
+        let mut line = $crate::log_marker::LogLine::new(stringify!($event));
+        $(line.field(stringify!($field), &event.$field);)*
+        line.finish();
+        emit!(event);
+    }};
+}
+
+pub struct LogLine {
+    out: String,
+    first: bool,
+}
+
+impl LogLine {
+    pub fn new(event: &str) -> Self {
+        let mut out = String::with_capacity(256);
+        out.push_str(LOG_MARKER_PREFIX);
+        out.push(':');
+        let name = event.strip_suffix("Event").unwrap_or(event);
+        for (i, c) in name.chars().enumerate() {
+            if c.is_ascii_uppercase() {
+                if i > 0 {
+                    out.push('_');
+                }
+                out.push(c.to_ascii_lowercase());
+            } else {
+                out.push(c);
+            }
+        }
+        out.push_str(":{");
+        Self { out, first: true }
+    }
+
+    pub fn field<T: LogValue + ?Sized>(&mut self, name: &str, value: &T) {
+        if !self.first {
+            self.out.push(',');
+        }
+        self.first = false;
+        write_str(&mut self.out, name);
+        self.out.push(':');
+        value.write_json(&mut self.out);
+    }
+
+    pub fn finish(self) {
+        msg!("{}", self.into_line());
+    }
+
+    fn into_line(mut self) -> String {
+        self.out.push('}');
+        self.out
+    }
+}
+
+pub trait LogValue {
+    fn write_json(&self, out: &mut String);
+}
+
+macro_rules! log_number {
+    ($($ty:ty),*) => {
+        $(impl LogValue for $ty {
+            fn write_json(&self, out: &mut String) {
+                let _ = write!(out, "{}", self);
+            }
+        })*
+    };
+}
+
+log_number!(u8, u16, u32, u64, i32, i64);
+
+impl LogValue for u128 {
+    fn write_json(&self, out: &mut String) {
+        let _ = write!(out, "\"{}\"", self);
+    }
+}
+
+impl LogValue for bool {
+    fn write_json(&self, out: &mut String) {
+        out.push_str(if *self { "true" } else { "false" });
+    }
+}
+
+impl LogValue for Pubkey {
+    fn write_json(&self, out: &mut String) {
+        let _ = write!(out, "\"{}\"", self);
+    }
+}
+
+impl<const N: usize> LogValue for [u8; N] {
+    fn write_json(&self, out: &mut String) {
+        out.push('"');
+        for byte in self {
+            let _ = write!(out, "{:02x}", byte);
+        }
+        out.push('"');
+    }
+}
+
+impl LogValue for String {
+    fn write_json(&self, out: &mut String) {
+        write_str(out, self);
+    }
+}
+
+impl<T: LogValue> LogValue for Option<T> {
+    fn write_json(&self, out: &mut String) {
+        match self {
+            Some(value) => value.write_json(out),
+            None => out.push_str("null"),
+        }
+    }
+}
+
+impl<T: LogValue> LogValue for Vec<T> {
+    fn write_json(&self, out: &mut String) {
+        out.push('[');
+        for (i, value) in self.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            value.write_json(out);
+        }
+        out.push(']');
+    }
+}
+
+// Enums log their variant name as a string
+macro_rules! log_enum {
+    ($($ty:ident { $($variant:ident),* $(,)? })*) => {
+        $(impl LogValue for $ty {
+            fn write_json(&self, out: &mut String) {
+                write_str(out, match self {
+                    $($ty::$variant => stringify!($variant),)*
+                });
+            }
+        })*
+    };
+}
+
+log_enum! {
+    BadgeKind { FirstContribution, TopBacker, Graduate }
+    CampaignStatus { Active, PendingReview, Graduated, Failed }
+    IdentityKind { None, SnsDomain, Did }
+    SupplyBucket { Curve, Creator, Liquidity, Airdrop }
+    UpgradeAuthorityStatus { Unverified, Renounced, ExpectedAuthority, UnexpectedAuthority }
+    WithdrawalPurpose { Development, Marketing, Liquidity, Operations, Legal, Other, Salary }
+}
+
+fn write_str(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(event: &str, build: impl FnOnce(&mut LogLine)) -> String {
+        let mut line = LogLine::new(event);
+        build(&mut line);
+        line.into_line()
+    }
+
+    #[test]
+    fn names_drop_the_suffix_and_go_snake_case() {
+        assert_eq!(line("ContributionEvent", |_| {}), "LF1:contribution:{}");
+        assert_eq!(
+            line("ClosingBidReclaimedEvent", |_| {}),
+            "LF1:closing_bid_reclaimed:{}"
+        );
+        // Only a trailing Event is dropped
+        assert_eq!(line("EventSchema", |_| {}), "LF1:event_schema:{}");
+    }
+
+    #[test]
+    fn fields_keep_declaration_order() {
+        let logged = line("ContributionEvent", |line| {
+            line.field("schema_version", &2u8);
+            line.field("amount", &1_000_000u64);
+            line.field("delta", &-5i64);
+            line.field("refunded", &false);
+        });
+        assert_eq!(
+            logged,
+            r#"LF1:contribution:{"schema_version":2,"amount":1000000,"delta":-5,"refunded":false}"#
+        );
+    }
+
+    #[test]
+    fn values_use_their_documented_encodings() {
+        let key = Pubkey::new_from_array([1; 32]);
+        let logged = line("SampleEvent", |line| {
+            line.field("key", &key);
+            line.field("hash", &[0xab_u8, 0x01, 0x00]);
+            line.field("big", &u128::MAX);
+            line.field("missing", &None::<u64>);
+            line.field("present", &Some(7u16));
+            line.field("list", &vec![1u32, 2, 3]);
+            line.field("status", &CampaignStatus::PendingReview);
+        });
+        assert_eq!(
+            logged,
+            format!(
+                r#"LF1:sample:{{"key":"{}","hash":"ab0100","big":"{}","missing":null,"present":7,"list":[1,2,3],"status":"PendingReview"}}"#,
+                key,
+                u128::MAX
+            )
+        );
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        let logged = line("SampleEvent", |line| {
+            line.field("text", &"a \"quoted\" \\ path\n".to_string());
+        });
+        assert_eq!(
+            logged,
+            r#"LF1:sample:{"text":"a \"quoted\" \\ path\u000a"}"#
+        );
+    }
+}
@@ -0,0 +1,67 @@
+// Canonical token symbols and names, so the symbol index and indexers see one
+// spelling per token. Symbols are uppercase ASCII letters and digits; names are
+// ASCII letters, digits and single spaces. Skeletons fold lookalike characters
+// together so one blocklist entry covers every spelling of a word.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+use crate::{ErrorCode, MAX_SYMBOL_LEN, MAX_TOKEN_NAME_LEN};
+
+pub fn symbol(raw: &str) -> Result<String> {
+    let symbol = raw.trim().to_ascii_uppercase();
+    require!(
+        !symbol.is_empty()
+            && symbol.len() <= MAX_SYMBOL_LEN
+            && symbol.bytes().all(|byte| byte.is_ascii_alphanumeric()),
+        ErrorCode::InvalidTokenSymbol
+    );
+    Ok(symbol)
+}
+
+// Surrounding whitespace is dropped and runs inside collapse to one space
+pub fn token_name(raw: &str) -> Result<String> {
+    let name = raw.split_ascii_whitespace().collect::<Vec<_>>().join(" ");
+    require!(
+        !name.is_empty()
+            && name.len() <= MAX_TOKEN_NAME_LEN
+            && name
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b' '),
+        ErrorCode::InvalidTokenName
+    );
+    Ok(name)
+}
+
+// Symbol index seed, taken from the raw argument so account constraints can
+// derive it before the handler canonicalizes
+pub fn symbol_seed(raw: &str) -> [u8; 32] {
+    hash(raw.trim().to_ascii_uppercase().as_bytes()).to_bytes()
+}
+
+// Symbol index seed of an existing campaign, from the symbol it stored.
+// Campaigns created before symbols were canonicalized stored them as typed and
+// were indexed by their to_uppercase() hash, which this keeps finding; for a
+// canonical symbol it's the same seed symbol_seed gives.
+pub fn stored_symbol_seed(symbol: &str) -> [u8; 32] {
+    hash(symbol.to_uppercase().as_bytes()).to_bytes()
+}
+
+// Uppercased with digits read as the letters they imitate, and anything that
+// isn't a letter or digit dropped: "T0KEN", "Token" and "to ken" all match
+pub fn skeleton_hash(raw: &str) -> [u8; 32] {
+    let skeleton: Vec<u8> = raw
+        .bytes()
+        .filter(|byte| byte.is_ascii_alphanumeric())
+        .map(|byte| match byte.to_ascii_uppercase() {
+            b'0' => b'O',
+            b'1' | b'L' => b'I',
+            b'3' => b'E',
+            b'4' => b'A',
+            b'5' => b'S',
+            b'7' => b'T',
+            b'8' => b'B',
+            other => other,
+        })
+        .collect();
+    hash(&skeleton).to_bytes()
+}
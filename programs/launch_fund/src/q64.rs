@@ -0,0 +1,88 @@
+// Q64.64 fixed point: a u128 whose low 64 bits are the fraction. Curve math
+// runs in it so prices and rates keep their sub-lamport part until the final
+// floor instead of losing it at every integer division.
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+pub const ONE: u128 = 1 << 64;
+
+pub fn ratio(numerator: u128, denominator: u128) -> Result<u128> {
+    require!(
+        denominator > 0 && denominator <= u64::MAX as u128,
+        ErrorCode::MathOverflow
+    );
+    let whole = numerator / denominator;
+    // The remainder is below the denominator, so shifting it can't overflow
+    let fraction = ((numerator % denominator) << 64) / denominator;
+    whole
+        .checked_mul(ONE)
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or(ErrorCode::MathOverflow.into())
+}
+
+// floor(value * n), with value split so the product can't overflow on the way
+pub fn mul_floor(value: u128, n: u64) -> Result<u64> {
+    let whole = (value >> 64)
+        .checked_mul(n as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let fraction = ((value & (ONE - 1)) * n as u128) >> 64;
+    u64::try_from(whole + fraction).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+// floor(n / value); n is an integer, so n << 64 still fits
+pub fn div_floor(n: u64, value: u128) -> Result<u64> {
+    require!(value > 0, ErrorCode::MathOverflow);
+    u64::try_from(((n as u128) << 64) / value).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratio_keeps_the_fraction() {
+        assert_eq!(ratio(6, 3).unwrap(), 2 * ONE);
+        assert_eq!(ratio(1, 2).unwrap(), ONE / 2);
+        assert_eq!(ratio(7, 4).unwrap(), ONE + 3 * ONE / 4);
+        // A third doesn't divide evenly and floors to the last bit
+        assert_eq!(ratio(1, 3).unwrap(), ONE / 3);
+    }
+
+    #[test]
+    fn ratio_rejects_bad_denominators() {
+        assert!(ratio(1, 0).is_err());
+        assert!(ratio(1, u64::MAX as u128 + 1).is_err());
+        // A whole part of 2^64 or more doesn't fit in the integer half
+        assert!(ratio(ONE, 1).is_err());
+        assert!(ratio(u64::MAX as u128, 1).is_ok());
+    }
+
+    #[test]
+    fn mul_floor_rounds_down() {
+        assert_eq!(mul_floor(ratio(1, 3).unwrap(), 3).unwrap(), 0);
+        assert_eq!(mul_floor(ratio(1, 3).unwrap(), 4).unwrap(), 1);
+        assert_eq!(
+            mul_floor(ratio(5, 4).unwrap(), 1_000_000_000).unwrap(),
+            1_250_000_000
+        );
+        assert_eq!(mul_floor(ONE, u64::MAX).unwrap(), u64::MAX);
+        assert!(mul_floor(2 * ONE, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn div_floor_inverts_mul_floor() {
+        let rate = ratio(5, 4).unwrap();
+        assert_eq!(div_floor(1_250_000_000, rate).unwrap(), 1_000_000_000);
+        // and rounds down where the fraction doesn't divide evenly
+        assert_eq!(div_floor(10, ratio(3, 1).unwrap()).unwrap(), 3);
+        assert_eq!(div_floor(5, ratio(1, 2).unwrap()).unwrap(), 10);
+        // Round trips never hand back more than went in
+        let third = ratio(1, 3).unwrap();
+        for n in [1u64, 7, 999, 1_000_003] {
+            assert!(div_floor(mul_floor(third, n).unwrap(), third).unwrap() <= n);
+        }
+        assert!(div_floor(1, 0).is_err());
+        assert!(div_floor(u64::MAX, 1).is_err());
+    }
+}
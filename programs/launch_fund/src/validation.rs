@@ -0,0 +1,44 @@
+// Amount checks shared by the contribute, sell and refund paths, so every entry
+// point rejects the same inputs with the same error
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+// Smallest contribution the curve takes (0.001 SOL). Below this the bonus
+// multiplier hands out tokens for amounts that don't cover their own fees.
+pub const MIN_CONTRIBUTION_LAMPORTS: u64 = 1_000_000;
+
+pub fn nonzero(amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::ZeroAmount);
+    Ok(())
+}
+
+pub fn contribution(amount: u64) -> Result<()> {
+    nonzero(amount)?;
+    require!(
+        amount >= MIN_CONTRIBUTION_LAMPORTS,
+        ErrorCode::ContributionBelowMinimum
+    );
+    Ok(())
+}
+
+// Inclusive range check reporting the caller's own error
+pub fn within(amount: u64, min: u64, max: u64, error: ErrorCode) -> Result<()> {
+    if amount < min || amount > max {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+// A cap of zero means uncapped
+pub fn within_cap(current: u64, amount: u64, cap: u64) -> Result<()> {
+    let total = current.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    require!(cap == 0 || total <= cap, ErrorCode::HardCapExceeded);
+    Ok(())
+}
+
+// Output of a conversion that rounded down to zero is dust the caller can't be paid
+pub fn output(amount: u64) -> Result<u64> {
+    require!(amount > 0, ErrorCode::AmountTooSmall);
+    Ok(amount)
+}
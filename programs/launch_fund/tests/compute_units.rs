@@ -0,0 +1,295 @@
+// Compute unit budget for contribute, run against the built program:
+//   anchor build && cargo test-sbf --manifest-path programs/launch_fund/Cargo.toml
+// cargo test-sbf turns on the test-sbf feature and points SBF_OUT_DIR at the
+// shared object, so a plain cargo test skips this file.
+#![cfg(feature = "test-sbf")]
+
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use launch_fund::{CampaignLimits, CampaignOptions, FeeTier, UnsoldDistribution};
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_program_test::{find_file, read_file, tokio, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    sysvar,
+    transaction::Transaction,
+};
+use solana_sdk_ids::bpf_loader_upgradeable;
+
+// Repeat contributions hit the init_if_needed and record update paths
+const CONTRIBUTE_CU_BUDGET: u64 = 60_000;
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &launch_fund::ID).0
+}
+
+// Deploys the program through the upgradeable loader with `admin` as its
+// upgrade authority, which initialize_global_config checks
+fn add_upgradeable_program(test: &mut ProgramTest, admin: &Pubkey) {
+    let elf =
+        read_file(find_file("launch_fund.so").expect("build the program first: anchor build"));
+    let programdata_address = get_program_data_address(&launch_fund::ID);
+
+    let program = bincode::serialize(&UpgradeableLoaderState::Program {
+        programdata_address,
+    })
+    .unwrap();
+    let mut programdata = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(*admin),
+    })
+    .unwrap();
+    programdata.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+    programdata.extend_from_slice(&elf);
+
+    for (address, data, executable) in [
+        (launch_fund::ID, program, true),
+        (programdata_address, programdata, false),
+    ] {
+        test.add_account(
+            address,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: bpf_loader_upgradeable::id(),
+                executable,
+                rent_epoch: 0,
+            },
+        );
+    }
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> u64 {
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    let outcome = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    outcome.result.unwrap();
+    outcome.metadata.unwrap().compute_units_consumed
+}
+
+fn campaign_options() -> CampaignOptions {
+    CampaignOptions {
+        floor_reserve_bps: 0,
+        governance_token: false,
+        governance_lockup_secs: 0,
+        freeze_during_raise: false,
+        uniform_price: false,
+        partial_refunds: false,
+        graduation_threshold: 0,
+        max_price_impact_bps: 0,
+        curve_reserve_bps: 0,
+        restricted_regions_bitmap: 0,
+        yield_to_contributors: false,
+        buy_fee_bps: 0,
+        sell_fee_bps: 0,
+        secondary_slope_bps: 0,
+        secondary_fee_bps: 0,
+        start_time: 0,
+        pre_deposits: false,
+        team_lockup_secs: 0,
+        distribution: UnsoldDistribution::default(),
+        stretch_goals: vec![],
+        withdrawal_approval_threshold: 0,
+        contributor_milestones: vec![],
+        milestone_min_contribution: 0,
+        exit_fee_bps: 0,
+        hard_cap: 0,
+        parent_campaign: None,
+        circuit_breaker_bps: 0,
+        circuit_breaker_slots: 0,
+        donation_mode: false,
+        min_hold_secs: 0,
+        creator_allocation: 0,
+        liquidity_reserve: 0,
+        airdrop_reserve: 0,
+        drip_cap_bps: 0,
+        drip_epoch_secs: 0,
+        curve: None,
+    }
+}
+
+#[tokio::test]
+async fn contribute_stays_under_its_compute_budget() {
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let contributor = Keypair::new();
+    let treasury = Pubkey::new_unique();
+
+    let mut test = ProgramTest::default();
+    test.prefer_bpf(true);
+    add_upgradeable_program(&mut test, &admin.pubkey());
+    for wallet in [&admin, &creator, &contributor] {
+        test.add_account(
+            wallet.pubkey(),
+            Account::new(10 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+        );
+    }
+    let mut context = test.start_with_context().await;
+
+    let global_config = pda(&[b"global_config"]);
+    let initialize_config = Instruction {
+        program_id: launch_fund::ID,
+        accounts: launch_fund::accounts::InitializeGlobalConfig {
+            global_config,
+            program: launch_fund::ID,
+            program_data: get_program_data_address(&launch_fund::ID),
+            admin: admin.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: launch_fund::instruction::InitializeGlobalConfig {
+            treasury,
+            fee_tiers: vec![FeeTier {
+                threshold: 0,
+                fee_bps: 0,
+            }],
+            frontend_fee_share_bps: 0,
+            limits: CampaignLimits {
+                min_duration_secs: 3600,
+                max_duration_secs: 90 * 24 * 3600,
+                min_target_amount: LAMPORTS_PER_SOL / 10,
+                max_target_amount: 100_000 * LAMPORTS_PER_SOL,
+                max_active_per_creator: 0,
+                failure_cooldown_secs: 0,
+            },
+            enforce_unique_symbols: false,
+        }
+        .data(),
+    };
+    send(&mut context, initialize_config, &[&admin]).await;
+
+    let name = "Compute Budget".to_string();
+    let campaign = pda(&[
+        b"campaign",
+        creator.pubkey().as_ref(),
+        hash(name.as_bytes()).as_ref(),
+    ]);
+    let price_history = pda(&[b"price_history", campaign.as_ref()]);
+    let token_mint = Keypair::new();
+    let now = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+    let initialize_campaign = Instruction {
+        program_id: launch_fund::ID,
+        accounts: launch_fund::accounts::InitializeCampaign {
+            campaign,
+            token_mint: token_mint.pubkey(),
+            price_history,
+            ticket_mint: None,
+            creator: creator.pubkey(),
+            governance_mint: None,
+            governance_authority: None,
+            global_config,
+            symbol_index: None,
+            symbol_block_entry: None,
+            name_block_entry: None,
+            creator_profile: pda(&[b"creator_profile", creator.pubkey().as_ref()]),
+            system_program: system_program::ID,
+            token_program: anchor_spl::token::ID,
+            token_2022_program: None,
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: launch_fund::instruction::InitializeCampaign {
+            name,
+            description: "A test campaign for crowdfunding".to_string(),
+            target_amount: 10 * LAMPORTS_PER_SOL,
+            token_symbol: "TEST".to_string(),
+            token_name: "Test Token".to_string(),
+            total_supply: 1_000_000 * 1_000_000_000,
+            end_time: now + 30 * 24 * 3600,
+            options: campaign_options(),
+        }
+        .data(),
+    };
+    send(&mut context, initialize_campaign, &[&creator, &token_mint]).await;
+
+    let contribute = |amount: u64| Instruction {
+        program_id: launch_fund::ID,
+        accounts: launch_fund::accounts::Contribute {
+            campaign,
+            token_mint: token_mint.pubkey(),
+            contributor_token_account: get_associated_token_address(
+                &contributor.pubkey(),
+                &token_mint.pubkey(),
+            ),
+            contribution_record: pda(&[
+                b"contribution",
+                campaign.as_ref(),
+                contributor.pubkey().as_ref(),
+            ]),
+            price_history,
+            price_feed: None,
+            contributor: contributor.pubkey(),
+            governance_mint: None,
+            governance_authority: None,
+            contributor_governance_account: None,
+            ticket_mint: None,
+            contributor_ticket_account: None,
+            global_config,
+            treasury,
+            insurance_pool: None,
+            frontend: None,
+            frontend_payout: None,
+            match_commitment: None,
+            leaderboard: None,
+            backer_profile: pda(&[b"backer_profile", contributor.pubkey().as_ref()]),
+            denylist_entry: None,
+            region_attestation: None,
+            system_program: system_program::ID,
+            token_program: anchor_spl::token::ID,
+            token_2022_program: None,
+            associated_token_program: associated_token::ID,
+        }
+        .to_account_metas(None),
+        data: launch_fund::instruction::Contribute {
+            amount,
+            client_nonce: None,
+            split_bps: vec![],
+        }
+        .data(),
+    };
+
+    send(
+        &mut context,
+        contribute(LAMPORTS_PER_SOL / 10),
+        &[&contributor],
+    )
+    .await;
+    let units = send(
+        &mut context,
+        contribute(LAMPORTS_PER_SOL / 20),
+        &[&contributor],
+    )
+    .await;
+    println!("contribute compute units: {units}");
+    assert!(
+        units < CONTRIBUTE_CU_BUDGET,
+        "contribute used {units} compute units, over its {CONTRIBUTE_CU_BUDGET} budget"
+    );
+}
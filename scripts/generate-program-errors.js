@@ -0,0 +1,66 @@
+// Regenerates lib/programErrors.ts from the program's ErrorCode enum, so the
+// frontend knows every variant's code, name, message and group.
+// Run after adding or rewording an error: npm run generate:errors
+const fs = require('fs')
+const path = require('path')
+
+const ERRORS_RS = path.join(__dirname, '../programs/launch_fund/src/errors.rs')
+const OUTPUT = path.join(__dirname, '../lib/programErrors.ts')
+// Anchor's offset for custom program errors
+const ERROR_CODE_OFFSET = 6000
+
+function declaredErrors(source) {
+  const start = source.indexOf('pub enum ErrorCode {')
+  const body = source.slice(start, source.indexOf('\n}\n', start)).split('\n').slice(1)
+
+  const errors = []
+  let category = null
+  let message = null
+  let next = 0
+  for (const raw of body) {
+    const line = raw.trim()
+    const group = line.match(/^\/\/ (Validation|Auth|State|Math):/)
+    const msg = line.match(/^#\[msg\("(.*)"\)\]$/)
+    const variant = line.match(/^(\w+)(?: = (\d+))?,$/)
+    if (group) {
+      category = group[1].toLowerCase()
+    } else if (msg) {
+      message = msg[1]
+    } else if (variant) {
+      const value = variant[2] === undefined ? next : Number(variant[2])
+      if (!category || message === null) {
+        throw new Error(`${variant[1]} needs a group comment and a #[msg] above it`)
+      }
+      errors.push({ code: ERROR_CODE_OFFSET + value, name: variant[1], message, category })
+      message = null
+      next = value + 1
+    }
+  }
+  return errors
+}
+
+const errors = declaredErrors(fs.readFileSync(ERRORS_RS, 'utf8'))
+const entries = errors
+  .map(({ code, name, message, category }) =>
+    `  ${code}: { name: '${name}', message: ${JSON.stringify(message)}, category: '${category}' },`)
+  .join('\n')
+
+fs.writeFileSync(
+  OUTPUT,
+  `// Generated by scripts/generate-program-errors.js from programs/launch_fund/src/errors.rs.
+// Do not edit by hand; rerun npm run generate:errors instead.
+
+export type ProgramErrorCategory = 'validation' | 'auth' | 'state' | 'math'
+
+export interface ProgramError {
+  name: string
+  message: string
+  category: ProgramErrorCategory
+}
+
+export const PROGRAM_ERRORS: Record<number, ProgramError> = {
+${entries}
+}
+`
+)
+console.log(`Wrote ${errors.length} program errors to ${path.relative(process.cwd(), OUTPUT)}`)